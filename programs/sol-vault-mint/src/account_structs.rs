@@ -1,14 +1,19 @@
 use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+#[allow(deprecated)]
 use anchor_lang::solana_program::bpf_loader_upgradeable::{self};
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
+    // init_if_needed so a second initialize surfaces AlreadyInitialized from the processor
+    // instead of a generic account-in-use error. A config resized by set_max_administrators
+    // still fails earlier, on Anchor's space check.
     #[account(
-        init,
+        init_if_needed,
         payer = signer,
         space = Config::LEN,
         seeds = [b"config"],
@@ -19,7 +24,7 @@ pub struct Initialize<'info> {
     #[account(
         constraint = vault_token_account.mint == vault_mint.key() @ CustomErrorCode::InvalidMint
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: This is a PDA that acts as the redeem vault authority, validated by seeds constraint
     /// This PDA will be set as the owner of the redeem_vault_token_account in the config
@@ -38,15 +43,33 @@ pub struct Initialize<'info> {
         constraint = redeem_vault_token_account.mint == vault_mint.key() @ CustomErrorCode::InvalidMint,
         constraint = (redeem_vault_token_account.owner == signer.key() || redeem_vault_token_account.owner == redeem_vault_authority.key()) @ CustomErrorCode::InvalidAuthority
     )]
-    pub redeem_vault_token_account: Account<'info, TokenAccount>,
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+    // mut so its authorities can be handed to the program PDAs
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the mint authority PDA the mint must end up with
+    #[account(
+        seeds = [b"mint_authority"],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
 
-    pub vault_mint: Account<'info, Mint>,
-    pub mint: Account<'info, Mint>,
+    /// CHECK: This is the freeze authority PDA the mint must end up with
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 
     /// CHECK: This is the program data account that contains the update authority
@@ -57,13 +80,16 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
-pub struct Pause<'info> {
+pub struct MigrateConfig<'info> {
+    /// CHECK: Unchecked because an older layout may not deserialize until it is reallocated;
+    /// deserialized in the processor after the resize.
     #[account(
         mut,
         seeds = [b"config"],
-        bump = config.bump
+        bump,
+        owner = crate::ID
     )]
-    pub config: Account<'info, Config>,
+    pub config: UncheckedAccount<'info>,
 
     /// CHECK: This is the program data account that contains the update authority
     #[account(
@@ -71,68 +97,83 @@ pub struct Pause<'info> {
     )]
     pub program_data: UncheckedAccount<'info>,
 
+    // Read for their decimals when migrating a version 0 config; matched against the config
+    // in the processor, since it is only deserialized there
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // pays for the extra rent when the account grows
+    #[account(mut)]
     pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Deposit<'info> {
+#[instruction(max_administrators: u8)]
+pub struct SetMaxAdministrators<'info> {
     #[account(
-        seeds = [b"config"], 
-        bump = config.bump
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch,
+        realloc = Config::space(max_administrators),
+        realloc::payer = signer,
+        realloc::zero = false
     )]
     pub config: Account<'info, Config>,
 
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        mut,
-        token::mint = config.vault,
-        constraint = vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
-        constraint = vault_token_account.owner == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub program_data: UncheckedAccount<'info>,
+
+    // pays for the extra rent when the account grows
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
 
+#[derive(Accounts)]
+pub struct Pause<'info> {
     #[account(
         mut,
-        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub mint: Account<'info, Mint>,
+    pub config: Account<'info, Config>,
 
-    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        seeds = [b"mint_authority"],
-        bump,
-        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub mint_authority: UncheckedAccount<'info>,
+    pub program_data: UncheckedAccount<'info>,
 
-    #[account()]
     pub signer: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
     #[account(
         mut,
-        token::mint = config.vault,
-        constraint = user_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
-        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub user_vault_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
 
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        mut,
-        token::mint = config.mint,
-        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
-        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub user_mint_token_account: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
-}
+    pub program_data: UncheckedAccount<'info>,
 
-// Helper function to derive the program data address
-fn get_program_data_address(program_id: &Pubkey) -> Pubkey {
-    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+    pub signer: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateFreezeAdministrators<'info> {
+pub struct UpdateExchangeRate<'info> {
     #[account(
         mut,
         seeds = [b"config"],
@@ -150,7 +191,7 @@ pub struct UpdateFreezeAdministrators<'info> {
 }
 
 #[derive(Accounts)]
-pub struct UpdateRewardsAdministrators<'info> {
+pub struct SetDepositLimits<'info> {
     #[account(
         mut,
         seeds = [b"config"],
@@ -168,236 +209,2551 @@ pub struct UpdateRewardsAdministrators<'info> {
 }
 
 #[derive(Accounts)]
-pub struct FreezeTokenAccount<'info> {
+pub struct SetMintCap<'info> {
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
 
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDepositFee<'info> {
     #[account(
         mut,
-        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
 
     #[account(
-        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+        constraint = fee_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint
     )]
-    pub mint: Account<'info, Mint>,
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: This is the freeze authority PDA
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        seeds = [b"freeze_authority"],
-        bump
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub freeze_authority_pda: UncheckedAccount<'info>,
+    pub program_data: UncheckedAccount<'info>,
 
     pub signer: Signer<'info>,
-    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct ThawTokenAccount<'info> {
+pub struct SetRedeemFee<'info> {
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
 
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        mut,
-        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetClaimFee<'info> {
     #[account(
-        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub mint: Account<'info, Mint>,
+    pub config: Account<'info, Config>,
 
-    /// CHECK: This is the freeze authority PDA
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        seeds = [b"freeze_authority"],
-        bump
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub freeze_authority_pda: UncheckedAccount<'info>,
+    pub program_data: UncheckedAccount<'info>,
 
     pub signer: Signer<'info>,
-    pub token_program: Program<'info, Token>,
 }
 
-// admin posts an epoch root
 #[derive(Accounts)]
-#[instruction(index: u64)]
-pub struct CreateRewardsEpoch<'info> {
+pub struct SetRedeemDelay<'info> {
     #[account(
-        seeds = [b"config"], 
+        mut,
+        seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
 
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        init,
-        payer=admin,
-        space=RewardsEpoch::LEN,
-        seeds=[b"epoch", index.to_le_bytes().as_ref()],
-        bump
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub epoch: Account<'info, RewardsEpoch>,
-    pub system_program: Program<'info, System>,
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
 }
 
-// user claims this epoch’s amount
 #[derive(Accounts)]
-pub struct ClaimRewards<'info> {
+pub struct SetRedeemExpiry<'info> {
     #[account(
-        seeds = [b"config"], 
+        mut,
+        seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    pub epoch: Account<'info, RewardsEpoch>,
+
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        init,
-        payer = user,
-        space = ClaimRecord::LEN,
-        seeds = [b"claim", epoch.key().as_ref(), user.key().as_ref()],
-        bump
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub claim_record: Account<'info, ClaimRecord>,
+    pub program_data: UncheckedAccount<'info>,
 
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardsFromVault<'info> {
     #[account(
         mut,
-        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub mint: Account<'info, Mint>,
+    pub config: Account<'info, Config>,
 
-    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        seeds = [b"mint_authority"],
-        bump,
-        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub mint_authority: UncheckedAccount<'info>,
+    pub program_data: UncheckedAccount<'info>,
 
-    #[account(
-        mut,
-        constraint = user_mint_token_account.mint == mint.key() @ CustomErrorCode::InvalidMint,
-        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
-    )]
-    pub user_mint_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    pub signer: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct RequestRedeem<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
-
+pub struct SetVestingCliff<'info> {
     #[account(
         mut,
-        constraint = user_mint_token_account.mint == mint.key() @ CustomErrorCode::InvalidMint,
-        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub user_mint_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
 
-    // NOTE: payer is the user (signer), NOT the PDA
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        init,
-        payer = signer,
-        space = RedemptionRequest::LEN,
-        seeds = [b"redemption_request", signer.key().as_ref()],
-        bump
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub redemption_request: Account<'info, RedemptionRequest>,
+    pub program_data: UncheckedAccount<'info>,
 
-    /// CHECK: PDA delegate/authority; NOT a signer, NOT a payer
-    #[account(
-        seeds = [b"redeem_vault_authority"],
-        bump
-    )]
-    pub redeem_vault_authority: AccountInfo<'info>,
+    pub signer: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetAllowlistEnabled<'info> {
     #[account(
-        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub mint: Account<'info, Mint>,
+    pub config: Account<'info, Config>,
 
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        seeds = [b"config"], 
-        bump = config.bump
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub config: Account<'info, Config>,
+    pub program_data: UncheckedAccount<'info>,
 
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub signer: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CompleteRedeem<'info> {
-    #[account()]
-    pub admin: Signer<'info>,
-
-    /// The original user (to validate and to receive close rent)
-    /// (Not required to be a signer here.)
-    /// must be writeable to close the account = user will transfer rent to user
-    #[account(mut)]
-    pub user: SystemAccount<'info>,
-
+pub struct SetMinVaultReserve<'info> {
     #[account(
         mut,
-        close = user,   // refund rent to the original user
-        seeds = [b"redemption_request", user.key().as_ref()],
-        bump = redemption_request.bump
-        // optionally: has_one = user,
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub redemption_request: Account<'info, RedemptionRequest>,
+    pub config: Account<'info, Config>,
 
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        mut,
-        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
-        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
-    pub user_mint_token_account: Account<'info, TokenAccount>, // wYLDS
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetPerUserDepositCap<'info> {
     #[account(
         mut,
-        constraint = user_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        seeds = [b"config"],
+        bump = config.bump
     )]
-    pub user_vault_token_account: Account<'info, TokenAccount>, // USDC dest
+    pub config: Account<'info, Config>,
 
+    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        mut,
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceOracle<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        token::mint = config.vault,
+        constraint = vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.owner == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>, // USDC mint, needed for transfer_checked
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    // pays for the user position on a first deposit
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = config.vault,
+        constraint = user_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Only checked against config.fee_vault when a deposit fee is configured.
+    #[account(
+        mut,
+        constraint = fee_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = (config.deposit_fee_bps == 0 || fee_vault_token_account.key() == config.fee_vault) @ CustomErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // created on the user's first deposit, tracks lifetime deposits against the per-user cap
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = UserPosition::LEN,
+        seeds = [b"position", signer.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    // Only required while config.allowlist_enabled is set
+    #[account(
+        seeds = [b"allow", signer.key().as_ref()],
+        bump = allowlist_entry.bump
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    /// CHECK: blocklist PDA for the signer; the processor rejects the call if it exists.
+    /// Required rather than optional so it cannot be omitted to skip the check.
+    #[account(
+        seeds = [b"blocked", signer.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositWithSplit<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        token::mint = config.vault,
+        constraint = vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.owner == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>, // USDC mint, needed for transfer_checked
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = config.vault,
+        constraint = user_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Only checked against config.fee_vault when a deposit fee is configured.
+    #[account(
+        mut,
+        constraint = fee_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = (config.deposit_fee_bps == 0 || fee_vault_token_account.key() == config.fee_vault) @ CustomErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: one record per user, created or updated by the processor only when part of the
+    /// deposit is deferred; repeat split deposits add to the deferred amount
+    #[account(
+        mut,
+        seeds = [b"vesting", signer.key().as_ref()],
+        bump
+    )]
+    pub vesting_record: UncheckedAccount<'info>,
+
+    // see Deposit
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = UserPosition::LEN,
+        seeds = [b"position", signer.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    // Only required while config.allowlist_enabled is set
+    #[account(
+        seeds = [b"allow", signer.key().as_ref()],
+        bump = allowlist_entry.bump
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    /// CHECK: blocklist PDA for the signer, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", signer.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = signer, // refund rent to the user
+        seeds = [b"vesting", signer.key().as_ref()],
+        bump = vesting_record.bump,
+        constraint = vesting_record.user == signer.key() @ CustomErrorCode::InvalidAuthority
+    )]
+    pub vesting_record: Account<'info, VestingRecord>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: blocklist PDA for the signer, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", signer.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Helper function to derive the program data address
+fn get_program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+#[derive(Accounts)]
+pub struct UpdateFreezeAdministrators<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardsAdministrators<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeTokenAccount<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's FreezeScope PDA; its quota is enforced in the processor when it
+    /// exists. Required rather than optional so it cannot be omitted to skip the check.
+    #[account(
+        mut,
+        seeds = [b"freeze_scope", signer.key().as_ref()],
+        bump
+    )]
+    pub freeze_scope: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ThawTokenAccount<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// remaining_accounts = writable token accounts of `mint` to freeze
+#[derive(Accounts)]
+pub struct FreezeTokenAccountsBatch<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's FreezeScope PDA; its quota is enforced in the processor when it
+    /// exists. Required rather than optional so it cannot be omitted to skip the check.
+    #[account(
+        mut,
+        seeds = [b"freeze_scope", signer.key().as_ref()],
+        bump
+    )]
+    pub freeze_scope: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeTokenAccountWithReason<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // init_if_needed so a record left behind by a plain thaw_token_account is overwritten
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = FreezeRecord::LEN,
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's FreezeScope PDA; its quota is enforced in the processor when it
+    /// exists. Required rather than optional so it cannot be omitted to skip the check.
+    #[account(
+        mut,
+        seeds = [b"freeze_scope", signer.key().as_ref()],
+        bump
+    )]
+    pub freeze_scope: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ThawTokenAccountWithReason<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // rent goes back to the thawing administrator
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// admin posts an epoch root
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct CreateRewardsEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer=admin,
+        space=RewardsEpoch::LEN,
+        seeds=[b"epoch", index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub epoch: Account<'info, RewardsEpoch>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepUnclaimed<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub epoch: Account<'info, RewardsEpoch>,
+}
+
+// user claims this epoch’s amount
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: the user's claimed-epoch bitmap, created or grown by the processor
+    #[account(
+        mut,
+        seeds = [b"claim_bitmap", user.key().as_ref()],
+        bump
+    )]
+    pub claim_bitmap: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub epoch: Account<'info, RewardsEpoch>,
+    /// CHECK: created by the processor so an existing record fails with
+    /// RewardsAlreadyClaimed rather than a generic account-in-use error
+    #[account(
+        mut,
+        seeds = [b"claim", epoch.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub claim_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that owns the rewards vault, validated by seeds constraint
+    #[account(
+        seeds = [b"rewards_vault_authority"],
+        bump
+    )]
+    pub rewards_vault_authority: UncheckedAccount<'info>,
+
+    // only required when config.rewards_from_vault is set
+    #[account(
+        mut,
+        constraint = rewards_vault_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = rewards_vault_token_account.owner == rewards_vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub rewards_vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == mint.key() @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: blocklist PDA for the user, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", user.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// user claims several epochs at once; each epoch and its claim record PDA are passed
+// as remaining_accounts pairs in the same order as the claims
+#[derive(Accounts)]
+pub struct ClaimRewardsMulti<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: the user's claimed-epoch bitmap, created or grown by the processor
+    #[account(
+        mut,
+        seeds = [b"claim_bitmap", user.key().as_ref()],
+        bump
+    )]
+    pub claim_bitmap: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that owns the rewards vault, validated by seeds constraint
+    #[account(
+        seeds = [b"rewards_vault_authority"],
+        bump
+    )]
+    pub rewards_vault_authority: UncheckedAccount<'info>,
+
+    // only required when config.rewards_from_vault is set
+    #[account(
+        mut,
+        constraint = rewards_vault_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = rewards_vault_token_account.owner == rewards_vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub rewards_vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == mint.key() @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: blocklist PDA for the user, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", user.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRedeem<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == mint.key() @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // NOTE: payer is the user (signer), NOT the PDA
+    // init_if_needed so an existing request surfaces RedemptionAlreadyPending from the
+    // processor instead of a generic account-in-use error
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = RedemptionRequest::LEN,
+        seeds = [b"redemption_request", signer.key().as_ref()],
+        bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// CHECK: PDA delegate/authority; NOT a signer, NOT a payer
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // USDC mint; its owner is the token program used to derive the default destination ATA
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: blocklist PDA for the signer, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", signer.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteRedeem<'info> {
+    // pays for the extra rent when a request written under an older layout grows
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The original user (to validate and to receive close rent)
+    /// (Not required to be a signer here.)
+    /// must be writeable to close the account = user will transfer rent to user
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+
+    /// CHECK: Unchecked so a request written under an older layout can be brought up to date
+    /// before it is deserialized (see migrate_redemption_request). Closed to `user` by the
+    /// processor once the request is fully filled.
+    #[account(
+        mut,
+        seeds = [b"redemption_request", user.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub redemption_request: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>, // wYLDS
+
+    #[account(
+        mut,
+        constraint = user_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+    )]
+    pub user_vault_token_account: InterfaceAccount<'info, TokenAccount>, // USDC dest, checked against the request
+
+    #[account(
+        mut,
+        constraint = redeem_vault_token_account.key() == config.redeem_vault @ CustomErrorCode::InvalidRedeemVault,
+        constraint = redeem_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = redeem_vault_token_account.owner == redeem_vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>, // USDC source
+
+    // Only checked against config.fee_vault when a redeem fee is configured.
+    #[account(
+        mut,
+        constraint = fee_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = (config.redeem_fee_bps == 0 || fee_vault_token_account.key() == config.fee_vault) @ CustomErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>, // USDC fee dest
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>, // USDC mint, needed for transfer_checked
+
+    /// CHECK: the user's deposit position; reduced in the processor when it exists. Users who
+    /// only received wYLDS by transfer have none.
+    #[account(
+        mut,
+        seeds = [b"position", user.key().as_ref()],
+        bump
+    )]
+    pub user_position: UncheckedAccount<'info>,
+
+    /// CHECK: Required and matched against config.price_oracle when one is configured; the
+    /// owner and layout are validated when the price is read.
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>, // wYLDS mint, checked against the request
+
+    /// CHECK: PDA authority (delegate & vault authority)
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowMintProgramCaller<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+// a whitelisted partner program mints wYLDS via CPI, signing with its own PDA
+#[derive(Accounts)]
+pub struct ExternalProgramMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    // A PDA of the calling program, signed via invoke_signed; the program itself is checked
+    // against config.allow_mint_program_caller through the instructions sysvar
+    pub external_mint_program_caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the instructions sysvar, validated by address
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_caller_quota", config.allow_mint_program_caller.as_ref()],
+        bump = mint_caller_quota.bump
+    )]
+    pub mint_caller_quota: Account<'info, MintCallerQuota>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRedeem<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = signer, // refund rent to the user
+        seeds = [b"redemption_request", signer.key().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.user == signer.key() @ CustomErrorCode::InvalidAuthority
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>, // wYLDS
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireRedeem<'info> {
+    #[account()]
+    pub admin: Signer<'info>,
+
+    /// The original user (receives the close rent)
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = user, // refund rent to the original user
+        seeds = [b"redemption_request", user.key().as_ref()],
+        bump = redemption_request.bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    // Holds the allowance approved at request time, revoked where the token program allows it
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>, // wYLDS
+
+    /// CHECK: PDA authority (delegate)
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// read-only view of a user's redemption request, meant to be simulated
+#[derive(Accounts)]
+pub struct GetRedemptionStatus<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: any user may be queried; only used to derive the request PDA
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: may not exist; the processor returns RequestNotFound in that case
+    #[account(
+        seeds = [b"redemption_request", user.key().as_ref()],
+        bump
+    )]
+    pub redemption_request: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddToAllowlist<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: the address being allowlisted; only used to derive the entry PDA
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AllowlistEntry::LEN,
+        seeds = [b"allow", user.key().as_ref()],
+        bump
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromAllowlist<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin, // rent goes back to the administrator
+        seeds = [b"allow", allowlist_entry.user.as_ref()],
+        bump = allowlist_entry.bump
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+}
+
+#[derive(Accounts)]
+pub struct BlockAddress<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: the address being blocked; only used to derive the entry PDA
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = BlockedEntry::LEN,
+        seeds = [b"blocked", user.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: Account<'info, BlockedEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnblockAddress<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin, // rent goes back to the administrator
+        seeds = [b"blocked", blocked_entry.user.as_ref()],
+        bump = blocked_entry.bump
+    )]
+    pub blocked_entry: Account<'info, BlockedEntry>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = redeem_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = redeem_vault_token_account.owner == redeem_vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>, // USDC source
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>, // USDC mint, needed for transfer_checked
+
+    /// CHECK: PDA authority of the redeem vault
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct TransferMintAuthority<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint.mint_authority == Some(mint_authority.key()).into() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// remaining_accounts = (redemption_request, user, user_mint_token_account) groups; each user
+// must be writable to receive its request's rent, and each wYLDS account writable so its
+// delegate approval can be revoked
+#[derive(Accounts)]
+pub struct ReclaimAbandonedRedemptions<'info> {
+    #[account()]
+    pub admin: Signer<'info>,
+
+    /// CHECK: PDA authority (delegate)
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetRedeemVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = redeem_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = redeem_vault_token_account.owner == redeem_vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is the redeem vault authority PDA the new account must be owned by
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+// read-only redemption quote, meant to be simulated
+#[derive(Accounts)]
+pub struct SimulateRedeem<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = redeem_vault_token_account.key() == config.redeem_vault @ CustomErrorCode::InvalidRedeemVault
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Required and matched against config.price_oracle when one is configured; the
+    /// owner and layout are validated when the price is read.
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+}
+
+// claim_rewards plus the user's deposit position, which the payout is added to
+#[derive(Accounts)]
+pub struct ClaimAndRestake<'info> {
+    pub claim: ClaimRewards<'info>,
+
+    // see Deposit
+    #[account(
+        init_if_needed,
+        payer = claim.user,
+        space = UserPosition::LEN,
+        seeds = [b"position", claim.user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// read-only TVL snapshot, meant to be simulated
+#[derive(Accounts)]
+pub struct GetTvl<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.owner == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = redeem_vault_token_account.key() == config.redeem_vault @ CustomErrorCode::InvalidRedeemVault
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct RebalanceToRedeemVault<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.owner == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = redeem_vault_token_account.key() == config.redeem_vault @ CustomErrorCode::InvalidRedeemVault
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+
+    // the deposit vault's owner signs the transfer itself
+    #[account(
+        constraint = signer.key() == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub signer: Signer<'info>,
+
+    pub vault_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetVaultAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinRedeem<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDepositCooldown<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRedeemAuthorityMinLamports<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+// read-only config view, meant to be simulated
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeEpoch<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub epoch: Account<'info, RewardsEpoch>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardsEpochRoot<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub epoch: Account<'info, RewardsEpoch>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeConfigAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptConfigAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub signer: Signer<'info>,
+}
+
+// claim_rewards paying a token account the signer need not own; the claim record is still
+// keyed on the signer
+#[derive(Accounts)]
+pub struct ClaimRewardsTo<'info> {
+    pub claim: ClaimRewards<'info>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == claim.config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+}
+
+// deposit submitted by a relayer for a user who approved the deposit delegate PDA on their
+// vault token account; see Deposit for the shared checks
+#[derive(Accounts)]
+pub struct DepositDelegated<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.owner == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: PDA the user approves as delegate on their vault token account, validated by
+    /// seeds constraint
+    #[account(
+        seeds = [b"deposit_delegate"],
+        bump
+    )]
+    pub deposit_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: the depositor; need not sign, their approval is checked in the processor
+    pub user: UncheckedAccount<'info>,
+
+    // submits the transaction and pays for the user position on a first deposit
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = (config.deposit_fee_bps == 0 || fee_vault_token_account.key() == config.fee_vault) @ CustomErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = UserPosition::LEN,
+        seeds = [b"position", user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        seeds = [b"allow", user.key().as_ref()],
+        bump = allowlist_entry.bump
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    /// CHECK: blocklist PDA for the user, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", user.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub vault_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetNextEpochIndex<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+// remaining_accounts = one writable mint token account per recipient, in the same order
+#[derive(Accounts)]
+pub struct Airdrop<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(admin: Pubkey)]
+pub struct SetFreezeScope<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = FreezeScope::LEN,
+        seeds = [b"freeze_scope", admin.as_ref()],
+        bump
+    )]
+    pub freeze_scope: Account<'info, FreezeScope>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// remaining_accounts = (redemption_request, user, user_mint_token_account,
+// user_vault_token_account, user_position) groups; all but the request's owner program
+// check are validated in the processor
+#[derive(Accounts)]
+pub struct CompleteRedeemBatch<'info> {
+    #[account()]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = redeem_vault_token_account.key() == config.redeem_vault @ CustomErrorCode::InvalidRedeemVault,
         constraint = redeem_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
         constraint = redeem_vault_token_account.owner == redeem_vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
     )]
-    pub redeem_vault_token_account: Account<'info, TokenAccount>, // USDC source
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>, // USDC source
+
+    // Only checked against config.fee_vault when a redeem fee is configured.
+    #[account(
+        mut,
+        constraint = fee_vault_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = (config.redeem_fee_bps == 0 || fee_vault_token_account.key() == config.fee_vault) @ CustomErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>, // USDC fee dest
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>, // USDC mint, needed for transfer_checked
+
+    /// CHECK: Required and matched against config.price_oracle when one is configured; the
+    /// owner and layout are validated when the price is read.
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>, // wYLDS mint
+
+    /// CHECK: PDA authority (delegate & vault authority)
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct GetClaimBitmap<'info> {
+    /// CHECK: the user's claimed-epoch bitmap; may not exist yet
+    #[account(
+        seeds = [b"claim_bitmap", user.as_ref()],
+        bump
+    )]
+    pub claim_bitmap: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(caller: Pubkey)]
+pub struct SetMintCallerQuota<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = MintCallerQuota::LEN,
+        seeds = [b"mint_caller_quota", caller.as_ref()],
+        bump
+    )]
+    pub mint_caller_quota: Account<'info, MintCallerQuota>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddVaultMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = vault_token_account.mint == vault_mint.key() @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.owner == config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = redeem_vault_token_account.mint == vault_mint.key() @ CustomErrorCode::InvalidVaultMint,
+        constraint = redeem_vault_token_account.owner == redeem_vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the asset's redeem vault, validated by seeds
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    // init fails for a mint that is already accepted
+    #[account(
+        init,
+        payer = signer,
+        space = VaultAsset::LEN,
+        seeds = [b"vault_asset", vault_mint.key().as_ref()],
+        bump
+    )]
+    pub vault_asset: Account<'info, VaultAsset>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveVaultMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"vault_asset", vault_asset.vault_mint.as_ref()],
+        bump = vault_asset.bump
+    )]
+    pub vault_asset: Account<'info, VaultAsset>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxVaultMints<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositAsset<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"vault_asset", vault_mint.key().as_ref()],
+        bump = vault_asset.bump
+    )]
+    pub vault_asset: Account<'info, VaultAsset>,
+
+    pub vault_mint: InterfaceAccount<'info, Mint>, // needed for transfer_checked
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_asset.vault_token_account @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_vault_token_account.mint == vault_mint.key() @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = UserPosition::LEN,
+        seeds = [b"position", signer.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    // Only required while config.allowlist_enabled is set
+    #[account(
+        seeds = [b"allow", signer.key().as_ref()],
+        bump = allowlist_entry.bump
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    /// CHECK: blocklist PDA for the signer, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", signer.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemAsset<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"vault_asset", vault_mint.key().as_ref()],
+        bump = vault_asset.bump
+    )]
+    pub vault_asset: Account<'info, VaultAsset>,
+
+    pub vault_mint: InterfaceAccount<'info, Mint>, // needed for transfer_checked
+
+    #[account(
+        mut,
+        constraint = redeem_vault_token_account.key() == vault_asset.redeem_vault_token_account @ CustomErrorCode::InvalidRedeemVault
+    )]
+    pub redeem_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA authority of the asset's redeem vault
+    #[account(
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_vault_token_account.mint == vault_mint.key() @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the signer's deposit position; reduced in the processor when it exists (see
+    /// CompleteRedeem)
+    #[account(
+        mut,
+        seeds = [b"position", signer.key().as_ref()],
+        bump
+    )]
+    pub user_position: UncheckedAccount<'info>,
+
+    /// CHECK: blocklist PDA for the signer, checked in the processor (see Deposit)
+    #[account(
+        seeds = [b"blocked", signer.key().as_ref()],
+        bump
+    )]
+    pub blocked_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the vault mint; may differ from the wYLDS token program
+    pub vault_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoundingPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
 
     #[account(
         mut,
-        constraint = mint.key() == redemption_request.mint,
         constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
     )]
-    pub mint: Account<'info, Mint>, // wYLDS mint
+    pub mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK: PDA authority (delegate & vault authority)
     #[account(
+        mut,
+        constraint = user_mint_token_account.mint == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_vault_token_account.key() == config.fee_vault @ CustomErrorCode::InvalidFeeVault,
+        constraint = fee_vault_token_account.owner == fee_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>, // USDC source
+
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>, // USDC mint, needed for transfer_checked
+
+    /// CHECK: PDA that owns the fee vault, validated by seeds constraint
+    #[account(
+        seeds = [b"fee_authority"],
+        bump
+    )]
+    pub fee_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // token program of the vault mint; only vault tokens move here
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetSlotBasedEpochDeadlines<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRedeemQueueHead<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+// complete_redeem plus the program data proving `redeem.admin` is the upgrade authority
+#[derive(Accounts)]
+pub struct CompleteRedeemPriority<'info> {
+    pub redeem: CompleteRedeem<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDefaultFrozen<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // checked for the DefaultAccountState extension when enforcement is turned on
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+// see ThawTokenAccount
+#[derive(Accounts)]
+pub struct ThawAfterKyc<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.key() == config.mint @ CustomErrorCode::InvalidMint,
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FundRedeemAuthority<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: PDA holding the SOL buffer; system owned, receives lamports only
+    #[account(
+        mut,
+        seeds = [b"redeem_vault_authority"],
+        bump
+    )]
+    pub redeem_vault_authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRedeemAuthorityExcess<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: PDA holding the SOL buffer; signs the transfer out with its seeds
+    #[account(
+        mut,
         seeds = [b"redeem_vault_authority"],
         bump
     )]
     pub redeem_vault_authority: AccountInfo<'info>,
 
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRedemptionRequest<'info> {
+    // pays for the extra rent when the request grows
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: only used to derive the request PDA; matched against the request in the processor
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: Unchecked because an older layout does not deserialize until it is reallocated;
+    /// deserialized in the processor after the resize.
+    #[account(
+        mut,
+        seeds = [b"redemption_request", user.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub redemption_request: UncheckedAccount<'info>,
+
+    // its owner is the token program used to derive the default destination ATA
+    #[account(
+        constraint = vault_mint.key() == config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardsAdminGracePeriod<'info> {
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
 }
 
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct MigrateRewardsEpoch<'info> {
+    // pays for the extra rent when the epoch grows
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Unchecked because an older layout does not deserialize until it is reallocated;
+    /// deserialized in the processor after the resize.
+    #[account(
+        mut,
+        seeds = [b"epoch", index.to_le_bytes().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub epoch: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == CONFIG_VERSION @ CustomErrorCode::ConfigVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
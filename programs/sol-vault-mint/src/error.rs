@@ -28,7 +28,7 @@ pub enum CustomErrorCode {
     InvalidUpgradeAuthority = 12,
     #[msg("Signer account missing.")]
     MissingSigner = 13,
-    #[msg("Too many freeze administrators.")]
+    #[msg("Too many administrators.")]
     TooManyAdministrators = 14,
     #[msg("Unauthorized freeze administrator")]
     UnauthorizedFreezeAdministrator = 15,
@@ -48,10 +48,160 @@ pub enum CustomErrorCode {
     RequestNotFound = 22,
     #[msg("Insufficient lamport funds in redeem vault authority")]
     InsufficientRedeemVaultFunds = 23,
-    #[msg("Redeem request already exists")]
-    RequestAlreadyExists = 24,
+    #[msg("A redemption request is already pending for this user")]
+    RedemptionAlreadyPending = 24,
     #[msg("Vault and mint cannot be the same")]
-    VaultAndMintCannotBeSame = 25,
-    #[msg("Protocol is paused")]
-    ProtocolPaused = 26,
+    VaultMintConflict = 25,
+    #[msg("Program is paused")]
+    ProgramPaused = 26,
+    #[msg("Caller is not the allowed external mint program")]
+    InvalidMintProgramCaller = 27,
+    #[msg("Deposits are paused")]
+    DepositsPaused = 28,
+    #[msg("Redemptions are paused")]
+    RedeemsPaused = 29,
+    #[msg("Invalid exchange rate")]
+    InvalidExchangeRate = 30,
+    #[msg("Amount cannot be scaled between vault and mint decimals")]
+    InvalidDecimalScaling = 31,
+    #[msg("Deposit amount is below the minimum")]
+    DepositBelowMinimum = 32,
+    #[msg("Deposit amount is above the maximum")]
+    DepositAboveMaximum = 33,
+    #[msg("Mint cap exceeded")]
+    MintCapExceeded = 34,
+    #[msg("Fee basis points must not exceed 10000")]
+    InvalidFeeBps = 35,
+    #[msg("Invalid fee vault token account")]
+    InvalidFeeVault = 36,
+    #[msg("Redemption timelock has not elapsed")]
+    RedeemTimelockNotElapsed = 37,
+    #[msg("Redemption request has not expired")]
+    RedemptionNotExpired = 38,
+    #[msg("Claim would exceed the epoch's total rewards")]
+    EpochCapExceeded = 39,
+    #[msg("Claim deadline for this epoch has passed")]
+    EpochClaimExpired = 40,
+    #[msg("Proof length does not match the epoch's tree depth")]
+    InvalidProofLength = 41,
+    #[msg("Too many epoch claims in one batch")]
+    TooManyEpochClaims = 42,
+    #[msg("Remaining accounts do not match the epoch claims")]
+    InvalidClaimAccounts = 43,
+    #[msg("Administrator list contains a duplicate")]
+    DuplicateAdministrator = 44,
+    #[msg("Administrator cannot be the default pubkey")]
+    InvalidAdministrator = 45,
+    #[msg("Rewards epoch has been swept")]
+    EpochSwept = 46,
+    #[msg("Rewards epoch claim deadline has not passed")]
+    EpochClaimsOpen = 47,
+    #[msg("Rewards vault token account is required when paying rewards from the vault")]
+    MissingRewardsVault = 48,
+    #[msg("Immediate mint share cannot exceed 10_000 basis points")]
+    InvalidSplitBps = 49,
+    #[msg("Vesting cliff has not been reached")]
+    VestingCliffNotReached = 50,
+    #[msg("Address is not on the deposit allowlist")]
+    AddressNotAllowlisted = 51,
+    #[msg("Address is blocked")]
+    AddressBlocked = 52,
+    #[msg("Redemption would take the vault below its minimum reserve")]
+    VaultReserveBreached = 53,
+    #[msg("Program is not paused")]
+    ProgramNotPaused = 54,
+    #[msg("Oracle price is older than the configured maximum staleness")]
+    StaleOracle = 55,
+    #[msg("Invalid oracle account")]
+    InvalidOracleAccount = 56,
+    #[msg("Redemption payout is below the requested minimum")]
+    SlippageExceeded = 57,
+    #[msg("Vault token account is not the redemption destination")]
+    InvalidRedeemDestination = 58,
+    #[msg("Config account version does not match the program; run migrate_config")]
+    ConfigVersionMismatch = 59,
+    #[msg("Administrator limit must be between the current list sizes and 32")]
+    InvalidMaxAdministrators = 60,
+    #[msg("Administrator not found")]
+    AdministratorNotFound = 61,
+    #[msg("Freeze batch must contain between 1 and 16 token accounts")]
+    InvalidFreezeBatch = 62,
+    #[msg("Nested entry into a guarded instruction")]
+    ReentrancyDetected = 63,
+    #[msg("Deposit would exceed the per-user deposit cap")]
+    UserDepositCapExceeded = 64,
+    #[msg("New authority cannot be the default public key")]
+    InvalidNewAuthority = 65,
+    #[msg("Fill amount exceeds the outstanding redemption amount")]
+    FillExceedsRequest = 66,
+    #[msg("Reclaim batch must contain between 1 and 8 requests, each with three accounts")]
+    InvalidReclaimBatch = 67,
+    #[msg("Redeem vault token account does not match the configured redeem vault")]
+    InvalidRedeemVault = 68,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow = 69,
+    #[msg("Redemption amount is below the minimum")]
+    RedeemBelowMinimum = 70,
+    #[msg("Deposit cooldown has not elapsed")]
+    DepositCooldownActive = 71,
+    #[msg("Rewards epoch is frozen")]
+    EpochFrozen = 72,
+    #[msg("Rewards epoch must be frozen first")]
+    EpochNotFrozen = 73,
+    #[msg("Rewards epoch already has claims")]
+    EpochHasClaims = 74,
+    #[msg("Program is already initialized")]
+    AlreadyInitialized = 75,
+    #[msg("Signer is not the config admin")]
+    InvalidConfigAdmin = 76,
+    #[msg("Signer is not the proposed config admin")]
+    InvalidPendingConfigAdmin = 77,
+    #[msg("Deposit delegate approval does not cover the amount")]
+    DelegateApprovalInsufficient = 78,
+    #[msg("Rewards epoch index must equal the next epoch index")]
+    NonSequentialEpoch = 79,
+    #[msg("Airdrop must contain between 1 and 10 recipients, each with one token account")]
+    InvalidAirdropBatch = 80,
+    #[msg("Freeze administrator has used up their freeze quota")]
+    FreezeQuotaExceeded = 81,
+    #[msg("User token account is frozen")]
+    UserTokenAccountFrozen = 82,
+    #[msg("Redeem batch must contain between 1 and 4 requests, each with five accounts")]
+    InvalidRedeemBatch = 83,
+    #[msg("User position account does not match the user")]
+    InvalidUserPosition = 84,
+    #[msg("External mint must be a direct CPI from the allowed program")]
+    UnauthorizedMintCaller = 85,
+    #[msg("External mint would exceed the caller's quota for the current window")]
+    MintQuotaExceeded = 86,
+    #[msg("Mint quota window must be positive")]
+    InvalidMintQuotaWindow = 87,
+    #[msg("Vault mint is already accepted")]
+    VaultMintAlreadyAccepted = 88,
+    #[msg("Accepted vault mint limit reached")]
+    TooManyVaultMints = 89,
+    #[msg("Redemption request is not at the head of the redemption queue")]
+    RedeemOutOfOrder = 90,
+    #[msg("Redeem queue head must move forward and not past the tail")]
+    InvalidRedeemQueueHead = 91,
+    #[msg("wYLDS account is frozen pending KYC approval")]
+    AccountNotKycApproved = 92,
+    #[msg("Mint does not default new accounts to frozen")]
+    MintNotDefaultFrozen = 93,
+    #[msg("Default-frozen enforcement is not enabled")]
+    DefaultFrozenDisabled = 94,
+    #[msg("Withdrawal would leave the redeem vault authority below its SOL buffer")]
+    RedeemAuthorityBufferBreached = 95,
+    #[msg("Merkle proof is longer than the maximum proof depth")]
+    ProofTooDeep = 96,
+    #[msg("Redemption request layout version does not match the program; migrate it first")]
+    RedemptionRequestVersionMismatch = 97,
+    #[msg("Rewards epoch layout version does not match the program; migrate it first")]
+    RewardsEpochVersionMismatch = 98,
+    #[msg("Oracle price update is for a different feed")]
+    OracleFeedMismatch = 99,
+    #[msg("Oracle confidence interval is too wide")]
+    OracleConfidenceTooWide = 100,
+    #[msg("Redemption request is too far behind the queue head to close yet")]
+    RedeemQueueWindowExceeded = 101,
 }
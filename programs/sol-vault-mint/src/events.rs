@@ -6,15 +6,18 @@ pub struct DepositEvent {
     pub amount: u64,
     pub mint: Pubkey,
     pub vault: Pubkey,
+    pub memo: [u8; 32], // client reference; all zeros means none
 }
 
 #[event]
 pub struct RewardsClaimed {
     pub user: Pubkey,
     pub epoch: u64,
-    pub amount: u64,
+    pub amount: u64, // proof allocation; the user receives amount - fee
     pub mint: Pubkey,
     pub vault: Pubkey,
+    pub fee: u64,
+    pub destination: Pubkey, // token account credited with amount - fee
 }
 
 #[event]
@@ -23,6 +26,9 @@ pub struct RedemptionRequested {
     pub amount: u64,
     pub vault_mint: Pubkey,
     pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub memo: [u8; 32], // client reference; all zeros means none
+    pub queue_position: u64,
 }
 
 #[event]
@@ -30,7 +36,470 @@ pub struct RedeemCompleted {
     pub user: Pubkey,
     pub admin: Pubkey,
     pub amount: u64,
+    pub fee: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// wYLDS still outstanding on the request after a partial fill; 0 once it is closed.
+    pub remaining: u64,
+}
+
+#[event]
+pub struct ExternalProgramMintEvent {
+    pub caller: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+    /// Minted by this caller in the current quota window, including this mint.
+    pub minted_in_window: u64,
+    pub max_per_window: u64,
+}
+
+#[event]
+pub struct RedemptionCancelled {
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RedemptionExpired {
+    pub user: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardsAdministratorsUpdated {
+    pub count: u8,
+    pub administrators: Vec<Pubkey>,
+}
+
+#[event]
+pub struct TokenAccountFrozenWithReason {
+    pub token_account: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub reason_code: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenAccountThawedWithReason {
+    pub token_account: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub reason_code: u16,
+    pub freeze_reason_code: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenAccountsBatchFrozen {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub requested: u8,
+    pub frozen: u8, // accounts already frozen are skipped
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenAccountFrozen {
+    pub token_account: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenAccountThawed {
+    pub token_account: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionStatus {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub requested_ts: i64,
+    pub timelock_elapsed: bool,
+}
+
+#[event]
+pub struct UnclaimedSwept {
+    pub epoch: u64,
+    pub admin: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VestingDeferred {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub unlock_ts: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AddressBlocked {
+    pub user: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct AddressUnblocked {
+    pub user: Pubkey,
+    pub admin: Pubkey,
+}
+
+/// Economic `Config` field reported by `ConfigParameterChanged`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigParameter {
+    RateNumerator,
+    RateDenominator,
+    MinDeposit,
+    MaxDeposit,
+    MintCap,
+    DepositFeeBps,
+    RedeemFeeBps,
+    RedeemDelaySeconds,
+    RedeemExpirySeconds,
+    MinVaultReserve,
+    VestingCliffSeconds,
+    MaxOracleStaleness,
+    PerUserDepositCap,
+    ClaimFeeBps,
+    MinRedeem,
+    DepositCooldownSeconds,
+    RedeemAuthorityMinLamports,
+    NextEpochIndex,
+    MaxVaultMints,
+    RoundUpOnMint,
+    RoundDownOnRedeem,
+    SlotBasedEpochDeadlines,
+    RedeemQueueHead,
+    DefaultFrozen,
+    RewardsAdminGraceSeconds,
+    MaxOracleConfBps,
+}
+
+// Emitted once per economic field a setter actually changes
+#[event]
+pub struct ConfigParameterChanged {
+    pub parameter: ConfigParameter,
+    pub old_value: u64,
+    pub new_value: u64,
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct ConfigMigrated {
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct EmergencyWithdrawEvent {
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MintAuthorityTransferred {
+    pub mint: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct RedemptionsReclaimed {
+    pub admin: Pubkey,
+    pub requested: u8,
+    pub reclaimed: u8, // requests that have not expired yet are skipped
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedeemVaultUpdated {
+    pub old_redeem_vault: Pubkey,
+    pub new_redeem_vault: Pubkey,
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct RedeemQuote {
+    pub amount: u64, // wYLDS to burn
+    pub gross: u64,  // vault tokens before the redeem fee
+    pub fee: u64,
+    pub net: u64,
+    pub vault_can_fill: bool, // redeem vault covers gross and keeps its minimum reserve
+}
+
+#[event]
+pub struct RewardsRestaked {
+    pub user: Pubkey,
+    pub epoch: u64,
+    pub amount: u64,  // wYLDS paid out by the claim
+    pub backing: u64, // vault tokens added to the user's position
+}
+
+#[event]
+pub struct TvlSnapshot {
+    pub vault_balance: u64,        // deposit vault
+    pub redeem_vault_balance: u64, // redeem vault
+    pub total_vault_tokens: u64,
+    pub mint_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultRebalanced {
+    pub amount: u64,
+    pub from: Pubkey, // deposit vault
+    pub to: Pubkey,   // redeem vault
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct VaultAuthorityUpdated {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub signer: Pubkey,
+}
+
+// Stable view of the config for clients; fields are only ever appended
+#[event]
+pub struct ConfigSnapshot {
+    pub version: u8,
+    pub vault: Pubkey,
     pub mint: Pubkey,
+    pub paused: bool, // includes a timed pause that has not yet expired
+    pub deposits_paused: bool,
+    pub redeems_paused: bool,
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub deposit_fee_bps: u16,
+    pub redeem_fee_bps: u16,
+    pub claim_fee_bps: u16,
+    pub min_deposit: u64,
+    pub max_deposit: u64,
+    pub per_user_deposit_cap: u64,
+    pub min_redeem: u64,
+    pub mint_cap: u64,
+    pub total_minted: u64,
+    pub freeze_administrators: u8,
+    pub rewards_administrators: u8,
+    pub max_administrators: u8,
+    pub epochs_created: u64, // one past the highest rewards epoch index
+}
+
+#[event]
+pub struct EpochFreezeUpdated {
+    pub epoch: u64,
+    pub admin: Pubkey,
+    pub frozen: bool,
+}
+
+#[event]
+pub struct EpochRootUpdated {
+    pub epoch: u64,
+    pub admin: Pubkey,
+    pub old_root: [u8; 32],
+    pub new_root: [u8; 32],
+    pub old_total: u64,
+    pub new_total: u64,
+}
+
+#[event]
+pub struct Initialized {
+    pub config: Pubkey,
     pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub freeze_administrators: Vec<Pubkey>,
+    pub rewards_administrators: Vec<Pubkey>,
+    pub vault_authority: Pubkey, // owner of the deposit vault token account
+    pub redeem_vault: Pubkey,    // redeem vault token account
+    pub redeem_vault_authority: Pubkey, // PDA
+    pub mint_authority: Pubkey,  // PDA
+    pub freeze_authority: Pubkey, // PDA
+    pub version: u8,
+}
+
+#[event]
+pub struct ConfigAdminProposed {
+    pub current: Pubkey, // default while the upgrade authority holds the role
+    pub proposed: Pubkey,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct ConfigAdminAccepted {
+    pub previous: Pubkey,
+    pub config_admin: Pubkey,
+}
+
+#[event]
+pub struct Heartbeat {
+    pub slot: u64,
+    pub timestamp: i64,
+    pub paused: bool, // includes a timed pause that has not yet expired
+    pub mint_supply: u64,
+}
+
+#[event]
+pub struct BackingReport {
+    pub vault_balance: u64,        // deposit vault
+    pub redeem_vault_balance: u64, // redeem vault
+    pub total_vault_tokens: u64,
+    pub mint_supply: u64,
+    pub required_backing: u64, // vault tokens the supply redeems for at the current rate
+    pub backing_ratio_bps: Option<u64>, // total / required in bps, None when the supply is 0
+    pub fully_backed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsAirdropped {
+    pub admin: Pubkey,
+    pub recipient: Pubkey,
+    pub destination: Pubkey, // recipient's mint token account
+    pub amount: u64,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct FreezeScopeSet {
+    pub admin: Pubkey,
+    pub quota: u32,
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct BatchRedeemCompleted {
+    pub admin: Pubkey,
+    pub requested: u8,
+    pub completed: u8,
+    /// Users whose requests the redeem vault could not cover; their requests stay open.
+    pub skipped: Vec<Pubkey>,
+    /// Vault tokens paid to users, net of redeem fees.
+    pub total_paid: u64,
+    pub timestamp: i64,
 }
 
+#[event]
+pub struct ClaimBitmapSnapshot {
+    pub user: Pubkey,
+    /// Bit `i % 8` of `bits[i / 8]` is set when epoch `i` was claimed.
+    pub bits: Vec<u8>,
+    /// Epochs `0..epochs_created` exist; unset bits below it are unclaimed.
+    pub epochs_created: u64,
+}
+
+#[event]
+pub struct MintCallerQuotaSet {
+    pub caller: Pubkey,
+    pub max_per_window: u64,
+    pub window_seconds: i64,
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct VaultMintAdded {
+    pub vault_mint: Pubkey,
+    pub vault_token_account: Pubkey,
+    pub redeem_vault_token_account: Pubkey,
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct VaultMintRemoved {
+    pub vault_mint: Pubkey,
+    pub signer: Pubkey,
+}
+
+#[event]
+pub struct AssetRedeemed {
+    pub user: Pubkey,
+    pub amount: u64,       // wYLDS burned
+    pub vault_amount: u64, // paid out in vault_mint, after the fee
+    pub fee: u64,
+    pub vault_mint: Pubkey,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+    pub total_minted: u64, // after the burn
+}
+
+#[event]
+pub struct FeesSwept {
+    pub admin: Pubkey,
+    pub fee_vault: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RedeemSkipped {
+    pub user: Pubkey,
+    pub signer: Pubkey,
+    pub amount: u64,
+    pub queue_position: u64,
+    pub previous_head: u64,
+    pub new_head: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountKycApproved {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedeemAuthorityFunded {
+    pub funder: Pubkey,
+    pub lamports: u64,
+    pub balance: u64, // after the transfer
+}
+
+#[event]
+pub struct RedeemAuthorityExcessWithdrawn {
+    pub signer: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+    pub balance: u64, // after the transfer
+}
+
+#[event]
+pub struct RedemptionRequestMigrated {
+    pub user: Pubkey,
+    pub admin: Pubkey,
+    pub old_len: u64, // account size before the migration
+    pub version: u8,
+    pub queue_position: u64,
+}
+
+#[event]
+pub struct RewardsEpochMigrated {
+    pub index: u64,
+    pub admin: Pubkey,
+    pub old_len: u64, // account size before the migration
+    pub version: u8,
+}
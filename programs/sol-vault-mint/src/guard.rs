@@ -1,9 +1,29 @@
 use crate::error::*;
+use crate::state::Config;
 use anchor_lang::prelude::*;
 
 #[allow(deprecated)]
 use anchor_lang::solana_program::bpf_loader_upgradeable::{UpgradeableLoaderState};
 
+// Like require_eq!, but logs both operand expressions and their values before failing, so a
+// client can see which value was wrong and what was expected. Both sides must implement
+// PartialEq and Display.
+macro_rules! require_eq_logged {
+    ($left:expr, $right:expr, $error:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        if left != right {
+            msg!(
+                "{} = {}, expected {} = {}",
+                stringify!($left),
+                left,
+                stringify!($right),
+                right
+            );
+            return Err(error!($error));
+        }
+    }};
+}
+
 pub fn validate_program_update_authority(
     program_data_account: &UncheckedAccount,
     authority: &Signer,
@@ -38,3 +58,20 @@ pub fn validate_program_update_authority(
 
     Ok(())
 }
+
+// Economic parameters belong to the accepted config admin, or to the program update
+// authority while no config admin has been accepted.
+pub fn validate_config_admin(
+    config: &Config,
+    program_data_account: &UncheckedAccount,
+    authority: &Signer,
+) -> Result<()> {
+    if config.config_admin == Pubkey::default() {
+        return validate_program_update_authority(program_data_account, authority);
+    }
+    require!(
+        authority.key() == config.config_admin,
+        CustomErrorCode::InvalidConfigAdmin
+    );
+    Ok(())
+}
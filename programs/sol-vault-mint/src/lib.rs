@@ -28,110 +28,898 @@ pub mod account_structs;
 /// through Solana's transaction model.
 pub mod error;
 pub mod events;
+#[macro_use]
 mod guard;
+mod math;
+mod oracle;
 pub mod processor;
 pub mod state;
 
 use account_structs::*;
 use anchor_lang::prelude::*;
-use state::ProofNode;
 
 declare_id!("3VkpgDpmazgvT6cLKp1UqyAqHKBM46cfpbHhc5ihYta9");
 
-#[program]
-pub mod hastra_sol_vault_mint {
-    use super::*;
-
-    /// Initializes the vault program with the required token configurations:
-    /// - vault_mint: The token that users deposit (e.g., USDC)
-    /// - mint: The token users receive when deposit received (e.g., wYLDS)
-    pub fn initialize(
-        ctx: Context<Initialize>,
-        vault_mint: Pubkey,
-        mint: Pubkey,
-        freeze_administrators: Vec<Pubkey>,
-        rewards_administrators: Vec<Pubkey>,
-    ) -> Result<()> {
-        processor::initialize(
-            ctx,
-            vault_mint,
-            mint,
-            freeze_administrators,
-            rewards_administrators,
-        )
-    }
+// #[program] emits IDL handlers beside the module that call the deprecated
+// `AccountInfo::realloc`, so the allowance has to sit on an enclosing module.
+#[allow(deprecated)]
+mod instructions {
+    use crate::account_structs::*;
+    use crate::processor;
+    use crate::state::{AirdropRecipient, EpochClaim, MerkleMode, ProofNode};
+    use crate::ID;
+    use anchor_lang::prelude::*;
 
-    /// Pauses or unpauses the program, disabling or enabling deposit and redeem functions.
-    pub fn pause(ctx: Context<Pause>, pause: bool) -> Result<()> {
-        processor::pause(ctx, pause)
-    }
+    #[program]
+    pub mod hastra_sol_vault_mint {
+        use super::*;
 
-    /// Handles user deposits of vault tokens (e.g., USDC):
-    /// - Transfers vault tokens to program vault account
-    /// - Mints equivalent amount of mint tokens (e.g., wYLDS) to user
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        processor::deposit(ctx, amount)
-    }
+        /// Initializes the vault program with the required token configurations:
+        /// - vault_mint: The token that users deposit (e.g., USDC)
+        /// - mint: The token users receive when deposit received (e.g., wYLDS)
+        pub fn initialize(
+            ctx: Context<Initialize>,
+            vault_mint: Pubkey,
+            mint: Pubkey,
+            freeze_administrators: Vec<Pubkey>,
+            rewards_administrators: Vec<Pubkey>,
+        ) -> Result<()> {
+            processor::initialize(
+                ctx,
+                vault_mint,
+                mint,
+                freeze_administrators,
+                rewards_administrators,
+            )
+        }
 
-    /// The redeem function allows users to withdraw their original vault tokens:
-    /// - Transfers vault tokens from a program vault account to user
-    /// - Burns the corresponding amount of mint tokens (e.g., wYLDS) from user
-    pub fn request_redeem(ctx: Context<RequestRedeem>, amount: u64) -> Result<()> {
-        processor::request_redeem(ctx, amount)
-    }
+        /// Grows a config account created by an older program to the current size and stamps
+        /// the current layout version. Fund-moving instructions reject unmigrated accounts.
+        pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+            processor::migrate_config(ctx)
+        }
 
-    pub fn complete_redeem(ctx: Context<CompleteRedeem>) -> Result<()> {
-        processor::complete_redeem(ctx)
-    }
+        /// Sets the maximum length of each administrator list (at most 32) and resizes the
+        /// config account to fit. Cannot go below the current list sizes.
+        pub fn set_max_administrators(
+            ctx: Context<SetMaxAdministrators>,
+            max_administrators: u8,
+        ) -> Result<()> {
+            processor::set_max_administrators(ctx, max_administrators)
+        }
 
-    pub fn update_freeze_administrators(
-        ctx: Context<UpdateFreezeAdministrators>,
-        new_administrators: Vec<Pubkey>,
-    ) -> Result<()> {
-        processor::update_freeze_administrators(ctx, new_administrators)
-    }
+        /// Pauses the program, disabling deposit, redeem, claim and external mint functions.
+        pub fn pause(ctx: Context<Pause>) -> Result<()> {
+            processor::pause(ctx)
+        }
 
-    pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
-        processor::freeze_token_account(ctx)
-    }
-    pub fn thaw_token_account(ctx: Context<ThawTokenAccount>) -> Result<()> {
-        processor::thaw_token_account(ctx)
-    }
+        /// Unpauses the program, re-enabling deposit, redeem, claim and external mint functions.
+        pub fn unpause(ctx: Context<Pause>) -> Result<()> {
+            processor::unpause(ctx)
+        }
 
-    pub fn update_rewards_administrators(
-        ctx: Context<UpdateRewardsAdministrators>,
-        new_administrators: Vec<Pubkey>,
-    ) -> Result<()> {
-        processor::update_rewards_administrators(ctx, new_administrators)
-    }
+        /// Pauses the program until the unix timestamp `pause_until_ts`, after which it
+        /// resumes without an `unpause`. 0 clears the timed pause.
+        pub fn pause_until(ctx: Context<Pause>, pause_until_ts: i64) -> Result<()> {
+            processor::pause_until(ctx, pause_until_ts)
+        }
 
-    pub fn create_rewards_epoch(
-        ctx: Context<CreateRewardsEpoch>,
-        index: u64,
-        merkle_root: [u8; 32],
-        total: u64,
-    ) -> Result<()> {
-        processor::create_rewards_epoch(ctx, index, merkle_root, total)
-    }
+        /// Pauses deposits and/or redemptions individually. The global `pause` still
+        /// overrides both flags.
+        pub fn set_pause_flags(
+            ctx: Context<SetPauseFlags>,
+            deposits: bool,
+            redeems: bool,
+        ) -> Result<()> {
+            processor::set_pause_flags(ctx, deposits, redeems)
+        }
+
+        /// Updates the deposit-to-mint exchange rate. Deposits mint
+        /// `amount * rate_numerator / rate_denominator` and redemptions apply the inverse.
+        pub fn update_exchange_rate(
+            ctx: Context<UpdateExchangeRate>,
+            rate_numerator: u64,
+            rate_denominator: u64,
+        ) -> Result<()> {
+            processor::update_exchange_rate(ctx, rate_numerator, rate_denominator)
+        }
+
+        /// Sets the per-transaction deposit limits. A `max_deposit` of 0 means no maximum.
+        pub fn set_deposit_limits(
+            ctx: Context<SetDepositLimits>,
+            min_deposit: u64,
+            max_deposit: u64,
+        ) -> Result<()> {
+            processor::set_deposit_limits(ctx, min_deposit, max_deposit)
+        }
+
+        /// Sets the lifetime cap on vault tokens each user may deposit, net of redemptions.
+        /// 0 means unlimited.
+        pub fn set_per_user_deposit_cap(
+            ctx: Context<SetPerUserDepositCap>,
+            per_user_deposit_cap: u64,
+        ) -> Result<()> {
+            processor::set_per_user_deposit_cap(ctx, per_user_deposit_cap)
+        }
+
+        /// Sets the ceiling on total wYLDS minted by the program. 0 means unlimited.
+        pub fn set_mint_cap(ctx: Context<SetMintCap>, mint_cap: u64) -> Result<()> {
+            processor::set_mint_cap(ctx, mint_cap)
+        }
+
+        /// Sets the vault token account `complete_redeem` pays out of. It must hold the vault mint
+        /// and be owned by the redeem vault authority PDA.
+        pub fn set_redeem_vault(ctx: Context<SetRedeemVault>) -> Result<()> {
+            processor::set_redeem_vault(ctx)
+        }
+
+        /// Sets the deposit fee in basis points and the vault token account that collects it.
+        pub fn set_deposit_fee(ctx: Context<SetDepositFee>, deposit_fee_bps: u16) -> Result<()> {
+            processor::set_deposit_fee(ctx, deposit_fee_bps)
+        }
+
+        /// Sets the redemption fee in basis points, retained in the fee vault on `complete_redeem`.
+        pub fn set_redeem_fee(ctx: Context<SetRedeemFee>, redeem_fee_bps: u16) -> Result<()> {
+            processor::set_redeem_fee(ctx, redeem_fee_bps)
+        }
+
+        /// Sets the share of each rewards claim, in basis points, withheld from the payout.
+        /// 0 disables the fee. The fee is not collected anywhere: in mint mode it is never minted,
+        /// and with `rewards_from_vault` it stays in the rewards vault. The epoch's `claimed` total
+        /// still counts the full allocation.
+        pub fn set_claim_fee(ctx: Context<SetClaimFee>, claim_fee_bps: u16) -> Result<()> {
+            processor::set_claim_fee(ctx, claim_fee_bps)
+        }
+
+        /// Sets the minimum number of seconds a redemption request must wait before it
+        /// can be completed.
+        pub fn set_redeem_delay(
+            ctx: Context<SetRedeemDelay>,
+            redeem_delay_seconds: i64,
+        ) -> Result<()> {
+            processor::set_redeem_delay(ctx, redeem_delay_seconds)
+        }
+
+        /// Sets how long a redemption request may stay pending before an admin can expire it.
+        /// 0 disables expiry.
+        pub fn set_redeem_expiry(
+            ctx: Context<SetRedeemExpiry>,
+            redeem_expiry_seconds: i64,
+        ) -> Result<()> {
+            processor::set_redeem_expiry(ctx, redeem_expiry_seconds)
+        }
+
+        /// Chooses how rewards are paid: `true` transfers from the pre-funded rewards vault
+        /// owned by the `rewards_vault_authority` PDA, `false` mints new supply.
+        pub fn set_rewards_from_vault(
+            ctx: Context<SetRewardsFromVault>,
+            enabled: bool,
+        ) -> Result<()> {
+            processor::set_rewards_from_vault(ctx, enabled)
+        }
+
+        /// Turns the deposit allowlist on or off. While enabled, `deposit` and
+        /// `deposit_with_split` require the signer's `AllowlistEntry` PDA.
+        pub fn set_allowlist_enabled(
+            ctx: Context<SetAllowlistEnabled>,
+            enabled: bool,
+        ) -> Result<()> {
+            processor::set_allowlist_enabled(ctx, enabled)
+        }
+
+        /// Creates the `[b"allow", user]` entry that lets `user` deposit while the allowlist
+        /// is enabled. Callable by a rewards administrator.
+        pub fn add_to_allowlist(ctx: Context<AddToAllowlist>) -> Result<()> {
+            processor::add_to_allowlist(ctx)
+        }
+
+        /// Closes a user's allowlist entry, refunding its rent to the administrator.
+        pub fn remove_from_allowlist(ctx: Context<RemoveFromAllowlist>) -> Result<()> {
+            processor::remove_from_allowlist(ctx)
+        }
+
+        /// Sets the amount of vault tokens `complete_redeem` must leave in the redeem vault.
+        pub fn set_min_vault_reserve(
+            ctx: Context<SetMinVaultReserve>,
+            min_vault_reserve: u64,
+        ) -> Result<()> {
+            processor::set_min_vault_reserve(ctx, min_vault_reserve)
+        }
+
+        /// Sets the price feed `complete_redeem` uses to price wYLDS in vault tokens, the Pyth feed
+        /// id its updates must carry, the maximum age of an accepted price and the widest accepted
+        /// confidence interval in basis points of the price (0 = no limit). `Pubkey::default()`
+        /// restores the exchange rate.
+        pub fn set_price_oracle(
+            ctx: Context<SetPriceOracle>,
+            price_oracle: Pubkey,
+            price_feed_id: [u8; 32],
+            max_oracle_staleness: i64,
+            max_oracle_conf_bps: u16,
+        ) -> Result<()> {
+            processor::set_price_oracle(
+                ctx,
+                price_oracle,
+                price_feed_id,
+                max_oracle_staleness,
+                max_oracle_conf_bps,
+            )
+        }
+
+        /// Handles user deposits of vault tokens (e.g., USDC):
+        /// - Transfers vault tokens to program vault account
+        /// - Mints equivalent amount of mint tokens (e.g., wYLDS) to user
+        ///
+        /// `memo` is an opaque reference echoed in `DepositEvent`; all zeros means none.
+        pub fn deposit(ctx: Context<Deposit>, amount: u64, memo: [u8; 32]) -> Result<()> {
+            processor::deposit(ctx, amount, memo)
+        }
+
+        /// Sets the cliff, in seconds, before the deferred part of a split deposit can be claimed.
+        pub fn set_vesting_cliff(
+            ctx: Context<SetVestingCliff>,
+            vesting_cliff_seconds: i64,
+        ) -> Result<()> {
+            processor::set_vesting_cliff(ctx, vesting_cliff_seconds)
+        }
+
+        /// Deposits `amount` vault tokens like `deposit` but mints only
+        /// `immediate_bps / 10_000` of the wYLDS now. The rest is added to the user's
+        /// `VestingRecord`, created only when something is deferred, and can be claimed with
+        /// `claim_vested` once the cliff counted from the record's first deferral has passed.
+        pub fn deposit_with_split(
+            ctx: Context<DepositWithSplit>,
+            amount: u64,
+            immediate_bps: u16,
+        ) -> Result<()> {
+            processor::deposit_with_split(ctx, amount, immediate_bps)
+        }
+
+        /// Mints the deferred remainder of the signer's split deposits once the cliff has
+        /// passed, closing the vesting record.
+        pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+            processor::claim_vested(ctx)
+        }
+
+        /// The redeem function allows users to withdraw their original vault tokens:
+        /// - Transfers vault tokens from a program vault account to user
+        /// - Burns the corresponding amount of mint tokens (e.g., wYLDS) from user
+        ///
+        /// `min_usdc_out` is the least the user accepts from `complete_redeem`, net of the redeem
+        /// fee; a lower payout fails with `SlippageExceeded`. 0 accepts any payout.
+        /// `destination` is the vault token account paid on completion; `None` uses the user's ATA.
+        /// `memo` is an opaque reference stored on the request and echoed in `RedemptionRequested`;
+        /// all zeros means none.
+        pub fn request_redeem(
+            ctx: Context<RequestRedeem>,
+            amount: u64,
+            min_usdc_out: u64,
+            destination: Option<Pubkey>,
+            memo: [u8; 32],
+        ) -> Result<()> {
+            processor::request_redeem(ctx, amount, min_usdc_out, destination, memo)
+        }
+
+        /// Burns the requested wYLDS and pays out the vault tokens, closing the request.
+        /// `fill_amount` redeems only that much, leaving the rest of the request open for later
+        /// fills; it may not exceed the outstanding amount (`FillExceedsRequest`).
+        /// A full completion revokes the leftover delegate allowance where the token program allows
+        /// it (Token-2022). A user account frozen since the request fails with `UserTokenAccountFrozen`.
+        /// A request written under an older layout is first brought up to date as
+        /// `migrate_redemption_request` would, with the admin paying the extra rent.
+        pub fn complete_redeem(
+            ctx: Context<CompleteRedeem>,
+            fill_amount: Option<u64>,
+        ) -> Result<()> {
+            processor::complete_redeem(ctx, fill_amount)
+        }
+
+        /// Cancels the signer's pending redemption request, revoking the delegate approval
+        /// and refunding the request account rent to the user.
+        pub fn cancel_redeem(ctx: Context<CancelRedeem>) -> Result<()> {
+            processor::cancel_redeem(ctx)
+        }
+
+        /// Closes a redemption request that has been pending longer than the configured expiry,
+        /// revoking the delegate approval where the token program allows it and refunding rent
+        /// to the user. Callable by a rewards administrator.
+        pub fn expire_redeem(ctx: Context<ExpireRedeem>) -> Result<()> {
+            processor::expire_redeem(ctx)
+        }
+
+        /// Closes up to 8 expired redemption requests passed as `(redemption_request, user,
+        /// user_mint_token_account)` groups in `remaining_accounts`, revoking each delegate
+        /// approval as `expire_redeem` does and refunding each request's rent to its user.
+        /// Requests that have not expired are skipped; one `RedemptionsReclaimed` summary is
+        /// emitted.
+        pub fn reclaim_abandoned_redemptions<'info>(
+            ctx: Context<'_, '_, 'info, 'info, ReclaimAbandonedRedemptions<'info>>,
+        ) -> Result<()> {
+            processor::reclaim_abandoned_redemptions(ctx)
+        }
+
+        /// Emits a `RedemptionStatus` event for `user`'s pending redemption (amount, request
+        /// time and whether the timelock has elapsed). Meant to be simulated; fails with
+        /// `RequestNotFound` when the user has no request.
+        pub fn get_redemption_status(ctx: Context<GetRedemptionStatus>) -> Result<()> {
+            processor::get_redemption_status(ctx)
+        }
+
+        /// Emits a `RedeemQuote` event with the gross, fee and net vault tokens `complete_redeem`
+        /// would pay for `amount` wYLDS at the current rate or oracle price, and whether the
+        /// redeem vault can cover it above its reserve. Meant to be simulated.
+        pub fn simulate_redeem(ctx: Context<SimulateRedeem>, amount: u64) -> Result<()> {
+            processor::simulate_redeem(ctx, amount)
+        }
+
+        pub fn update_freeze_administrators(
+            ctx: Context<UpdateFreezeAdministrators>,
+            new_administrators: Vec<Pubkey>,
+        ) -> Result<()> {
+            processor::update_freeze_administrators(ctx, new_administrators)
+        }
+
+        /// Adds one freeze administrator without replacing the list.
+        pub fn add_freeze_administrator(
+            ctx: Context<UpdateFreezeAdministrators>,
+            administrator: Pubkey,
+        ) -> Result<()> {
+            processor::add_freeze_administrator(ctx, administrator)
+        }
+
+        /// Removes one freeze administrator; fails with `AdministratorNotFound` if absent.
+        pub fn remove_freeze_administrator(
+            ctx: Context<UpdateFreezeAdministrators>,
+            administrator: Pubkey,
+        ) -> Result<()> {
+            processor::remove_freeze_administrator(ctx, administrator)
+        }
+
+        pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
+            processor::freeze_token_account(ctx)
+        }
+        pub fn thaw_token_account(ctx: Context<ThawTokenAccount>) -> Result<()> {
+            processor::thaw_token_account(ctx)
+        }
+
+        /// Freezes up to 16 token accounts passed as writable `remaining_accounts`, skipping any
+        /// that are already frozen, and emits one `TokenAccountsBatchFrozen` summary.
+        pub fn freeze_token_accounts_batch<'info>(
+            ctx: Context<'_, '_, 'info, 'info, FreezeTokenAccountsBatch<'info>>,
+        ) -> Result<()> {
+            processor::freeze_token_accounts_batch(ctx)
+        }
+
+        /// Freezes a token account like `freeze_token_account` and stores a `FreezeRecord`
+        /// with the administrator, `reason_code` and time for compliance audits.
+        pub fn freeze_token_account_with_reason(
+            ctx: Context<FreezeTokenAccountWithReason>,
+            reason_code: u16,
+        ) -> Result<()> {
+            processor::freeze_token_account_with_reason(ctx, reason_code)
+        }
+
+        /// Thaws a token account frozen with a reason and closes its `FreezeRecord`.
+        pub fn thaw_token_account_with_reason(
+            ctx: Context<ThawTokenAccountWithReason>,
+            reason_code: u16,
+        ) -> Result<()> {
+            processor::thaw_token_account_with_reason(ctx, reason_code)
+        }
+
+        /// Blocks `user` from depositing, requesting redemptions, claiming rewards and claiming
+        /// vested deposits by creating its `[b"blocked", user]` PDA. Callable by a freeze administrator.
+        pub fn block_address(ctx: Context<BlockAddress>) -> Result<()> {
+            processor::block_address(ctx)
+        }
+
+        /// Closes a blocklist entry, refunding its rent to the administrator.
+        pub fn unblock_address(ctx: Context<UnblockAddress>) -> Result<()> {
+            processor::unblock_address(ctx)
+        }
+
+        pub fn update_rewards_administrators(
+            ctx: Context<UpdateRewardsAdministrators>,
+            new_administrators: Vec<Pubkey>,
+        ) -> Result<()> {
+            processor::update_rewards_administrators(ctx, new_administrators)
+        }
+
+        /// Adds one rewards administrator without replacing the list.
+        pub fn add_rewards_administrator(
+            ctx: Context<UpdateRewardsAdministrators>,
+            administrator: Pubkey,
+        ) -> Result<()> {
+            processor::add_rewards_administrator(ctx, administrator)
+        }
+
+        /// Removes one rewards administrator; fails with `AdministratorNotFound` if absent.
+        pub fn remove_rewards_administrator(
+            ctx: Context<UpdateRewardsAdministrators>,
+            administrator: Pubkey,
+        ) -> Result<()> {
+            processor::remove_rewards_administrator(ctx, administrator)
+        }
 
-    /// This is the classic “airdrop/claim per epoch” design
-    /// High-level idea:
-    /// 	1.	Off-chain (admin does this each epoch):
-    /// 	•	Calculate each user’s reward for this epoch.
-    /// 	•	Build a Merkle tree of (user, amount, epoch_index).
-    /// 	•	Publish the Merkle root on-chain with the create_rewards_epoch function above.
-    ///
-    /// 	2.	On-chain:
-    /// 	•	Store each epoch’s Merkle root in a PDA.
-    /// 	•	When a user claims, they present (amount, proof) for their pubkey.
-    /// 	•	The program verifies the Merkle proof against the root.
-    /// 	•	If valid, transfer reward tokens (wYLDS) from the rewards vault to the user's mint token account.
-    /// 	•	Mark the claim as redeemed so they can’t double-claim.
-    pub fn claim_rewards(
-        ctx: Context<ClaimRewards>,
-        amount: u64,
-        proof: Vec<ProofNode>,
-    ) -> Result<()> {
-        processor::claim_rewards(ctx, amount, proof)
+        /// Posts a rewards epoch root. Claims are rejected after `claim_deadline_ts`
+        /// (unix seconds, inclusive); 0 means the epoch never expires. While
+        /// `slot_based_epoch_deadlines` is set, a non-zero `claim_deadline_ts` is instead the number
+        /// of slots after creation that claims stay open. `merkle_mode` selects
+        /// between `is_left` flagged proofs and sorted-pair (OpenZeppelin style) trees.
+        /// `tree_depth` is the exact proof length every claim must supply (0 for a single leaf).
+        pub fn create_rewards_epoch(
+            ctx: Context<CreateRewardsEpoch>,
+            index: u64,
+            merkle_root: [u8; 32],
+            total: u64,
+            claim_deadline_ts: i64,
+            merkle_mode: MerkleMode,
+            tree_depth: u8,
+        ) -> Result<()> {
+            processor::create_rewards_epoch(
+                ctx,
+                index,
+                merkle_root,
+                total,
+                claim_deadline_ts,
+                merkle_mode,
+                tree_depth,
+            )
+        }
+
+        /// Closes an epoch whose claim deadline has passed, forfeiting the unclaimed
+        /// `total - claimed` (reported in `UnclaimedSwept`). Further claims fail with
+        /// `EpochSwept`. Callable by a rewards administrator.
+        pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+            processor::sweep_unclaimed(ctx)
+        }
+
+        /// This is the classic “airdrop/claim per epoch” design
+        /// High-level idea:
+        /// 1. Off-chain (admin does this each epoch):
+        ///    - Calculate each user’s reward for this epoch.
+        ///    - Build a Merkle tree of (user, amount, epoch_index) with domain-separated hashing:
+        ///      leaves are `sha256(0x00 || user || amount_le || epoch_index_le)` and internal
+        ///      nodes are `sha256(0x01 || left || right)` (a lone node is `sha256(0x01 || node)`).
+        ///    - Publish the Merkle root on-chain with the create_rewards_epoch function above.
+        ///
+        /// 2. On-chain:
+        ///    - Store each epoch’s Merkle root in a PDA.
+        ///    - When a user claims, they present (amount, proof) for their pubkey.
+        ///    - The program verifies the Merkle proof against the root.
+        ///    - If valid, transfer reward tokens (wYLDS) from the rewards vault to the user's mint token account.
+        ///    - Mark the claim as redeemed so they can’t double-claim.
+        pub fn claim_rewards(
+            ctx: Context<ClaimRewards>,
+            amount: u64,
+            proof: Vec<ProofNode>,
+        ) -> Result<()> {
+            processor::claim_rewards(ctx, amount, proof)
+        }
+
+        /// Claims an epoch exactly like `claim_rewards` (claim record, fee and payout included)
+        /// and adds the payout, valued in vault tokens at the current exchange rate, to the
+        /// user's `UserPosition` in the same transaction. Restaked rewards go into
+        /// `restaked_rewards` and do not count towards the per-user deposit cap; redemptions
+        /// draw them down before `total_deposited`.
+        pub fn claim_and_restake(
+            ctx: Context<ClaimAndRestake>,
+            amount: u64,
+            proof: Vec<ProofNode>,
+        ) -> Result<()> {
+            processor::claim_and_restake(ctx, amount, proof)
+        }
+
+        /// Claims several epochs in one transaction (at most `MAX_EPOCH_CLAIMS`). For each
+        /// claim, pass the epoch PDA and its claim record PDA (`[b"claim", epoch, user]`) as
+        /// consecutive `remaining_accounts`, in claim order. Every proof is verified as in
+        /// `claim_rewards` and the summed amount is minted once; any invalid claim fails the
+        /// whole batch.
+        pub fn claim_rewards_multi<'info>(
+            ctx: Context<'_, '_, 'info, 'info, ClaimRewardsMulti<'info>>,
+            claims: Vec<EpochClaim>,
+        ) -> Result<()> {
+            processor::claim_rewards_multi(ctx, claims)
+        }
+
+        /// Sets the program whose PDA signer is allowed to call `external_program_mint`.
+        /// Setting it to the default pubkey disables external minting.
+        pub fn set_allow_mint_program_caller(
+            ctx: Context<SetAllowMintProgramCaller>,
+            caller: Pubkey,
+        ) -> Result<()> {
+            processor::set_allow_mint_program_caller(ctx, caller)
+        }
+
+        /// Mints wYLDS to `destination` on behalf of the whitelisted partner program (via CPI).
+        /// The partner program must invoke this directly from its own top-level instruction;
+        /// calls from any other program, or made directly, fail with `UnauthorizedMintCaller`.
+        /// The caller's `MintCallerQuota` must be set; minting past it fails with `MintQuotaExceeded`.
+        /// Like deposits, it fails with `ProgramPaused` while the program is paused and with
+        /// `ConfigVersionMismatch` until the config has been migrated.
+        pub fn external_program_mint(ctx: Context<ExternalProgramMint>, amount: u64) -> Result<()> {
+            processor::external_program_mint(ctx, amount)
+        }
+
+        /// Transfers `amount` vault tokens from the redeem vault to `destination_token_account`
+        /// for a wind-down. Only the program update authority can call it, and only while the
+        /// program is paused.
+        pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
+            processor::emergency_withdraw(ctx, amount)
+        }
+
+        /// Escape hatch for migrations: moves the mint's mint authority from the program PDA
+        /// to `new_authority` (e.g. the PDA of a redeployed program). Upgrade authority only.
+        pub fn transfer_mint_authority(
+            ctx: Context<TransferMintAuthority>,
+            new_authority: Pubkey,
+        ) -> Result<()> {
+            processor::transfer_mint_authority(ctx, new_authority)
+        }
+
+        /// Emits a `TvlSnapshot` event with the vault tokens held in the deposit and redeem
+        /// vaults, their total and the current wYLDS supply. Meant to be simulated.
+        pub fn get_tvl(ctx: Context<GetTvl>) -> Result<()> {
+            processor::get_tvl(ctx)
+        }
+
+        /// Moves `amount` vault tokens from the deposit vault to the redeem vault to fund
+        /// redemptions. Signed by the deposit vault's owner (`config.vault_authority`).
+        pub fn rebalance_to_redeem_vault(
+            ctx: Context<RebalanceToRedeemVault>,
+            amount: u64,
+        ) -> Result<()> {
+            processor::rebalance_to_redeem_vault(ctx, amount)
+        }
+
+        /// Sets `config.vault_authority`, the owner a deposit vault token account must have,
+        /// for key rotation. Upgrade authority only; the default pubkey is rejected.
+        pub fn set_vault_authority(
+            ctx: Context<SetVaultAuthority>,
+            new_authority: Pubkey,
+        ) -> Result<()> {
+            processor::set_vault_authority(ctx, new_authority)
+        }
+
+        /// Sets the smallest wYLDS amount `request_redeem` accepts, so dust redemptions that
+        /// cost more than they are worth fail with `RedeemBelowMinimum`. 0 disables the minimum.
+        pub fn set_min_redeem(ctx: Context<SetMinRedeem>, min_redeem: u64) -> Result<()> {
+            processor::set_min_redeem(ctx, min_redeem)
+        }
+
+        /// Sets the minimum number of seconds between two deposits by the same user, tracked in
+        /// their `UserPosition`. 0 disables the cooldown.
+        pub fn set_deposit_cooldown(
+            ctx: Context<SetDepositCooldown>,
+            deposit_cooldown_seconds: i64,
+        ) -> Result<()> {
+            processor::set_deposit_cooldown(ctx, deposit_cooldown_seconds)
+        }
+
+        /// Sets the lamports the redeem vault authority PDA must hold for `request_redeem` to
+        /// proceed (`InsufficientRedeemVaultFunds` otherwise). Defaults to 100_000.
+        pub fn set_redeem_authority_min_lamports(
+            ctx: Context<SetRedeemAuthorityMinLamports>,
+            redeem_authority_min_lamports: u64,
+        ) -> Result<()> {
+            processor::set_redeem_authority_min_lamports(ctx, redeem_authority_min_lamports)
+        }
+
+        /// Emits a `ConfigSnapshot` event with the config version and its stable fields, so
+        /// clients need not decode the raw account. Meant to be simulated.
+        pub fn get_config(ctx: Context<GetConfig>) -> Result<()> {
+            processor::get_config(ctx)
+        }
+
+        /// Halts claims against one rewards epoch, e.g. after a bad Merkle root is published.
+        /// Claims then fail with `EpochFrozen`; deposits and other epochs are unaffected.
+        /// Callable by a rewards administrator.
+        pub fn freeze_epoch(ctx: Context<FreezeEpoch>) -> Result<()> {
+            processor::freeze_epoch(ctx)
+        }
+
+        /// Re-opens a frozen rewards epoch to claims. Callable by a rewards administrator.
+        pub fn unfreeze_epoch(ctx: Context<FreezeEpoch>) -> Result<()> {
+            processor::unfreeze_epoch(ctx)
+        }
+
+        /// Replaces the Merkle root and total of a frozen epoch and unfreezes it. Fails with
+        /// `EpochNotFrozen` unless the epoch is frozen, and with `EpochHasClaims` once anything
+        /// has been claimed from it. Callable by a rewards administrator.
+        pub fn update_rewards_epoch_root(
+            ctx: Context<UpdateRewardsEpochRoot>,
+            new_root: [u8; 32],
+            new_total: u64,
+        ) -> Result<()> {
+            processor::update_rewards_epoch_root(ctx, new_root, new_total)
+        }
+
+        /// Proposes `new_admin` as the config admin, who holds the economic-parameter setters
+        /// (exchange rate, fees, limits, caps, delays, reserve, cooldown, redeem authority SOL
+        /// buffer) in place of the upgrade authority. Takes effect once `new_admin` signs
+        /// `accept_config_admin`. Only the program update authority can propose.
+        pub fn propose_config_admin(
+            ctx: Context<ProposeConfigAdmin>,
+            new_admin: Pubkey,
+        ) -> Result<()> {
+            processor::propose_config_admin(ctx, new_admin)
+        }
+
+        /// Completes the config admin handover; must be signed by the proposed key.
+        pub fn accept_config_admin(ctx: Context<AcceptConfigAdmin>) -> Result<()> {
+            processor::accept_config_admin(ctx)
+        }
+
+        /// Claims an epoch exactly like `claim_rewards`, but pays the rewards into `destination`,
+        /// any token account of the configured mint (e.g. a cold wallet's). The leaf and the
+        /// claim record stay bound to the signer, so the allocation can still be claimed only
+        /// once. The nested accounts still include the signer's own mint token account.
+        pub fn claim_rewards_to(
+            ctx: Context<ClaimRewardsTo>,
+            amount: u64,
+            proof: Vec<ProofNode>,
+        ) -> Result<()> {
+            processor::claim_rewards_to(ctx, amount, proof)
+        }
+
+        /// Emits a `Heartbeat` event with the current slot and timestamp, whether the program is
+        /// paused and the wYLDS supply. Needs no authority and changes nothing; meant to be
+        /// simulated by monitoring.
+        pub fn heartbeat(ctx: Context<GetConfig>) -> Result<()> {
+            processor::heartbeat(ctx)
+        }
+
+        /// Emits a `BackingReport` comparing the vault tokens in the deposit and redeem vaults
+        /// with the vault tokens the wYLDS supply redeems for at the current exchange rate,
+        /// including the ratio in basis points and whether backing is at least 100%. A zero
+        /// supply reports no ratio and counts as fully backed. Meant to be simulated.
+        pub fn check_backing(ctx: Context<GetTvl>) -> Result<()> {
+            processor::check_backing(ctx)
+        }
+
+        /// Deposits `amount` vault tokens for `user` without their signature, e.g. submitted by
+        /// a relayer. The user must first `approve` the `deposit_delegate` PDA on their vault token
+        /// account for at least `amount` (`DelegateApprovalInsufficient` otherwise). The approval
+        /// is drawn down by the deposit. wYLDS is minted to the user's mint token account, and all
+        /// deposit limits, fees, caps and allow/blocklist checks apply to the user.
+        pub fn deposit_delegated(ctx: Context<DepositDelegated>, amount: u64) -> Result<()> {
+            processor::deposit_delegated(ctx, amount)
+        }
+
+        /// Moves the index the next `create_rewards_epoch` must use forward (never back), also
+        /// raising `epochs_created` to match. Lets a migrated deployment continue after the epochs
+        /// it already has. Only the program update authority can call it.
+        pub fn set_next_epoch_index(
+            ctx: Context<SetNextEpochIndex>,
+            next_epoch_index: u64,
+        ) -> Result<()> {
+            processor::set_next_epoch_index(ctx, next_epoch_index)
+        }
+
+        /// Mints rewards directly to up to 10 recipients without a Merkle tree, for small
+        /// distributions. Each recipient's mint token account is passed in `remaining_accounts`
+        /// in the same order; it must hold the configured mint and be owned by the recipient.
+        /// Amounts must be non-zero and count against the mint cap. Emits one
+        /// `RewardsAirdropped` per recipient. Callable by a rewards administrator.
+        pub fn airdrop<'info>(
+            ctx: Context<'_, '_, 'info, 'info, Airdrop<'info>>,
+            recipients: Vec<AirdropRecipient>,
+        ) -> Result<()> {
+            processor::airdrop(ctx, recipients)
+        }
+
+        /// Limits freeze administrator `admin` to `quota` freezes (single, batched or with a
+        /// reason). Further freezes fail with `FreezeQuotaExceeded` until this is called again,
+        /// which restores the full quota. Only the program update authority can call it.
+        pub fn set_freeze_scope(
+            ctx: Context<SetFreezeScope>,
+            admin: Pubkey,
+            quota: u32,
+        ) -> Result<()> {
+            processor::set_freeze_scope(ctx, admin, quota)
+        }
+
+        /// Completes up to 4 redemption requests in full, passed as `(redemption_request, user,
+        /// user_mint_token_account, user_vault_token_account, user_position)` groups in
+        /// `remaining_accounts`. Requests the redeem vault cannot cover (balance or reserve) are
+        /// skipped and stay open; any other failure aborts the batch. Emits `RedeemCompleted` per
+        /// request and one `BatchRedeemCompleted` summary.
+        pub fn complete_redeem_batch<'info>(
+            ctx: Context<'_, '_, 'info, 'info, CompleteRedeemBatch<'info>>,
+        ) -> Result<()> {
+            processor::complete_redeem_batch(ctx)
+        }
+
+        /// Emits a `ClaimBitmapSnapshot` with the epochs `user` has claimed, one bit per epoch index,
+        /// so clients need not probe each epoch's claim record. Claims made before the bitmap was
+        /// introduced are not in it. Meant to be simulated.
+        pub fn get_claim_bitmap(ctx: Context<GetClaimBitmap>, user: Pubkey) -> Result<()> {
+            processor::get_claim_bitmap(ctx, user)
+        }
+
+        /// Lets external mint caller program `caller` mint at most `max_per_window` wYLDS per
+        /// `window_seconds`, starting a fresh window. `external_program_mint` requires this quota.
+        /// Only the program update authority can call it.
+        pub fn set_mint_caller_quota(
+            ctx: Context<SetMintCallerQuota>,
+            caller: Pubkey,
+            max_per_window: u64,
+            window_seconds: i64,
+        ) -> Result<()> {
+            processor::set_mint_caller_quota(ctx, caller, max_per_window, window_seconds)
+        }
+
+        /// Accepts `vault_mint` as an additional deposit asset for `deposit_asset`, minting
+        /// `rate_numerator / rate_denominator` wYLDS per unit (adjusted for decimals) into
+        /// `vault_token_account`, which must be owned by the vault authority. `redeem_asset` pays
+        /// it back out of `redeem_vault_token_account`, owned by the redeem vault authority PDA.
+        /// At most `max_vault_mints` can be added. Only the program update authority can call it.
+        pub fn add_vault_mint(
+            ctx: Context<AddVaultMint>,
+            rate_numerator: u64,
+            rate_denominator: u64,
+        ) -> Result<()> {
+            processor::add_vault_mint(ctx, rate_numerator, rate_denominator)
+        }
+
+        /// Stops accepting a vault mint added by `add_vault_mint`, closing its `VaultAsset`.
+        /// Only the program update authority can call it.
+        pub fn remove_vault_mint(ctx: Context<RemoveVaultMint>) -> Result<()> {
+            processor::remove_vault_mint(ctx)
+        }
+
+        /// Sets how many additional vault mints `add_vault_mint` accepts. It cannot drop below
+        /// the number already accepted. Only the program update authority can call it.
+        pub fn set_max_vault_mints(
+            ctx: Context<SetMaxVaultMints>,
+            max_vault_mints: u8,
+        ) -> Result<()> {
+            processor::set_max_vault_mints(ctx, max_vault_mints)
+        }
+
+        /// Deposits `amount` of an additional vault mint and mints wYLDS at that asset's rate.
+        /// Deposit limits, the per-user cap and the cooldown apply at the deposit's value in the
+        /// primary vault token; the deposit fee stays in the asset's vault token account.
+        pub fn deposit_asset(
+            ctx: Context<DepositAsset>,
+            amount: u64,
+            memo: [u8; 32],
+        ) -> Result<()> {
+            processor::deposit_asset(ctx, amount, memo)
+        }
+
+        /// Burns `amount` of the signer's wYLDS and pays it out at once in an additional vault
+        /// mint, at that asset's rate, from the asset's redeem vault. Fails with
+        /// `SlippageExceeded` if less than `min_vault_out` would be paid after the redeem fee.
+        pub fn redeem_asset(
+            ctx: Context<RedeemAsset>,
+            amount: u64,
+            min_vault_out: u64,
+        ) -> Result<()> {
+            processor::redeem_asset(ctx, amount, min_vault_out)
+        }
+
+        /// Sets the rounding direction of exchange-rate conversions. The defaults round minted
+        /// wYLDS down and redeemed vault tokens down, both in the protocol's favor, so a deposit
+        /// followed by a redemption never returns more than was deposited. Only the config admin can
+        /// call it.
+        pub fn set_rounding_policy(
+            ctx: Context<SetRoundingPolicy>,
+            round_up_on_mint: bool,
+            round_down_on_redeem: bool,
+        ) -> Result<()> {
+            processor::set_rounding_policy(ctx, round_up_on_mint, round_down_on_redeem)
+        }
+
+        /// Burns `amount` of the signer's own wYLDS without redeeming it: no vault tokens are
+        /// returned, the supply and `total_minted` just shrink. Emits `TokensBurned`.
+        pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
+            processor::burn_tokens(ctx, amount)
+        }
+
+        /// Transfers `amount` of collected deposit and redeem fees from the fee vault to
+        /// `treasury_token_account`. The fee vault must be owned by the `fee_authority` PDA
+        /// (`[b"fee_authority"]`), which signs the transfer. Only the program update authority can
+        /// call it.
+        pub fn sweep_fees(ctx: Context<SweepFees>, amount: u64) -> Result<()> {
+            processor::sweep_fees(ctx, amount)
+        }
+
+        /// Makes new rewards epochs read their claim deadline as a slot count rather than a unix
+        /// timestamp, so validator clock skew cannot move it. Existing epochs are unaffected.
+        /// Only the program update authority can call it.
+        pub fn set_slot_based_epoch_deadlines(
+            ctx: Context<SetSlotBasedEpochDeadlines>,
+            slot_based_epoch_deadlines: bool,
+        ) -> Result<()> {
+            processor::set_slot_based_epoch_deadlines(ctx, slot_based_epoch_deadlines)
+        }
+
+        /// Moves the redemption queue head forward past positions left empty by requests that were
+        /// cancelled or expired while not at the head. Only the program update authority can call it.
+        pub fn set_redeem_queue_head(
+            ctx: Context<SetRedeemQueueHead>,
+            redeem_queue_head: u64,
+        ) -> Result<()> {
+            processor::set_redeem_queue_head(ctx, redeem_queue_head)
+        }
+
+        /// Completes a pending redemption request in full regardless of its queue position and
+        /// closes its place in the queue, emitting `RedeemSkipped`. Only the program update
+        /// authority can call it; rewards administrators must use `complete_redeem`.
+        pub fn complete_redeem_priority(ctx: Context<CompleteRedeemPriority>) -> Result<()> {
+            processor::complete_redeem_priority(ctx)
+        }
+
+        /// Turns default-frozen enforcement on or off. While on, deposits only mint into wYLDS
+        /// accounts that `thaw_after_kyc` has thawed. Turning it on requires a Token-2022 mint whose
+        /// DefaultAccountState extension is frozen. Only the program update authority can call it.
+        pub fn set_default_frozen(
+            ctx: Context<SetDefaultFrozen>,
+            default_frozen: bool,
+        ) -> Result<()> {
+            processor::set_default_frozen(ctx, default_frozen)
+        }
+
+        /// Thaws a user's frozen wYLDS account once they have passed KYC and emits
+        /// `AccountKycApproved`. Only freeze administrators can call it, and only while
+        /// default-frozen enforcement is on.
+        pub fn thaw_after_kyc(ctx: Context<ThawAfterKyc>) -> Result<()> {
+            processor::thaw_after_kyc(ctx)
+        }
+
+        /// Transfers `lamports` from the signer to the redeem vault authority PDA, topping up the
+        /// SOL buffer `request_redeem` checks. Anyone can call it.
+        pub fn fund_redeem_authority(
+            ctx: Context<FundRedeemAuthority>,
+            lamports: u64,
+        ) -> Result<()> {
+            processor::fund_redeem_authority(ctx, lamports)
+        }
+
+        /// Moves `lamports` from the redeem vault authority PDA to `recipient`, keeping at least
+        /// `redeem_authority_min_lamports` (and the rent-exempt minimum) behind. Only the program
+        /// update authority can call it.
+        pub fn withdraw_redeem_authority_excess(
+            ctx: Context<WithdrawRedeemAuthorityExcess>,
+            lamports: u64,
+        ) -> Result<()> {
+            processor::withdraw_redeem_authority_excess(ctx, lamports)
+        }
+
+        /// Reallocates a redemption request written under an older layout to the current size and
+        /// fills the fields it lacked with defaults, dating it at the migration, so in-flight
+        /// redemptions survive an upgrade. `complete_redeem` does this itself; the other
+        /// redemption paths only accept requests at the current layout version. Only rewards
+        /// administrators can call it.
+        pub fn migrate_redemption_request(ctx: Context<MigrateRedemptionRequest>) -> Result<()> {
+            processor::migrate_redemption_request(ctx)
+        }
+
+        /// Sets how long rewards administrators replaced by `update_rewards_administrators` or
+        /// `remove_rewards_administrator` are still accepted, so transactions they signed before the
+        /// rotation still land. 0 disables the grace period. Only the program update authority can
+        /// call it.
+        pub fn set_rewards_admin_grace_period(
+            ctx: Context<SetRewardsAdminGracePeriod>,
+            grace_seconds: i64,
+        ) -> Result<()> {
+            processor::set_rewards_admin_grace_period(ctx, grace_seconds)
+        }
+
+        /// Reallocates a rewards epoch written under an older layout to the current size and fills
+        /// the fields it lacked, so epochs published before an upgrade stay claimable. Claims only
+        /// accept epochs at the current layout version. Only rewards administrators can call it.
+        pub fn migrate_rewards_epoch(
+            ctx: Context<MigrateRewardsEpoch>,
+            index: u64,
+            merkle_root: [u8; 32],
+            tree_depth: u8,
+            claimed: u64,
+        ) -> Result<()> {
+            processor::migrate_rewards_epoch(ctx, index, merkle_root, tree_depth, claimed)
+        }
     }
 }
+
+pub use instructions::*;
@@ -0,0 +1,133 @@
+use crate::error::*;
+use anchor_lang::prelude::*;
+
+/// Direction an inexact division rounds in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// `a + b`, failing with `ArithmeticOverflow` instead of wrapping.
+pub fn safe_add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b)
+        .ok_or(CustomErrorCode::ArithmeticOverflow.into())
+}
+
+/// `a * b / c` rounded as `rounding` says, with the product taken in 128 bits. Fails with
+/// `ArithmeticOverflow` on a zero divisor or a result that does not fit in a u64.
+pub fn safe_mul_div(a: u64, b: u64, c: u64, rounding: Rounding) -> Result<u64> {
+    let result = mul_div_u128(a as u128, b as u128, c as u128, rounding)?;
+    u64::try_from(result).map_err(|_| CustomErrorCode::ArithmeticOverflow.into())
+}
+
+/// 128-bit `a * b / c` behind `safe_mul_div`, for callers that scale by large factors.
+pub fn mul_div_u128(a: u128, b: u128, c: u128, rounding: Rounding) -> Result<u128> {
+    require!(c > 0, CustomErrorCode::ArithmeticOverflow);
+    let product = a
+        .checked_mul(b)
+        .ok_or(CustomErrorCode::ArithmeticOverflow)?;
+    let quotient = product / c;
+    if rounding == Rounding::Up && product % c != 0 {
+        // quotient < product, so this cannot overflow
+        Ok(quotient + 1)
+    } else {
+        Ok(quotient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (rate numerator, rate denominator) pairs, including ones folding in a decimal gap
+    const RATES: [(u64, u64); 5] = [(1, 1), (3, 7), (1_000, 999), (10, 1), (1, 1_000)];
+
+    #[test]
+    fn round_trip_never_returns_more_than_deposited() {
+        for (numerator, denominator) in RATES {
+            for deposited in (0..2_000u64).chain([999_999, 1_000_001, u32::MAX as u64]) {
+                let minted =
+                    safe_mul_div(deposited, numerator, denominator, Rounding::Down).unwrap();
+                let returned =
+                    safe_mul_div(minted, denominator, numerator, Rounding::Down).unwrap();
+                assert!(
+                    returned <= deposited,
+                    "{deposited} at {numerator}/{denominator} returned {returned}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_up_on_both_legs_can_leak_value() {
+        // 1 unit at 3/7 mints ceil(3/7) = 1, which redeems for ceil(7/3) = 3
+        let minted = safe_mul_div(1, 3, 7, Rounding::Up).unwrap();
+        assert_eq!(minted, 1);
+        assert_eq!(safe_mul_div(minted, 7, 3, Rounding::Up).unwrap(), 3);
+    }
+
+    #[test]
+    fn rounds_only_inexact_quotients_up() {
+        assert_eq!(safe_mul_div(10, 3, 4, Rounding::Down).unwrap(), 7);
+        assert_eq!(safe_mul_div(10, 3, 4, Rounding::Up).unwrap(), 8);
+        assert_eq!(safe_mul_div(10, 2, 4, Rounding::Up).unwrap(), 5);
+    }
+
+    #[test]
+    fn rounds_zero_and_exact_results_identically() {
+        for rounding in [Rounding::Down, Rounding::Up] {
+            assert_eq!(safe_mul_div(0, 3, 7, rounding).unwrap(), 0);
+            assert_eq!(safe_mul_div(7, 3, 7, rounding).unwrap(), 3);
+            assert_eq!(safe_mul_div(u64::MAX, 1, 1, rounding).unwrap(), u64::MAX);
+        }
+        // The smallest nonzero quotient rounds up to 1 and down to 0
+        assert_eq!(safe_mul_div(1, 1, u64::MAX, Rounding::Down).unwrap(), 0);
+        assert_eq!(safe_mul_div(1, 1, u64::MAX, Rounding::Up).unwrap(), 1);
+    }
+
+    fn is_overflow(result: Result<impl std::fmt::Debug>) -> bool {
+        match result {
+            Err(Error::AnchorError(e)) => {
+                e.error_code_number
+                    == anchor_lang::error::ERROR_CODE_OFFSET
+                        + CustomErrorCode::ArithmeticOverflow as u32
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn rejects_a_zero_divisor() {
+        for rounding in [Rounding::Down, Rounding::Up] {
+            assert!(is_overflow(safe_mul_div(10, 3, 0, rounding)));
+            assert!(is_overflow(safe_mul_div(0, 0, 0, rounding)));
+            assert!(is_overflow(mul_div_u128(10, 3, 0, rounding)));
+        }
+    }
+
+    #[test]
+    fn takes_the_product_in_128_bits() {
+        // u64::MAX * u64::MAX would wrap in 64 bits, but the quotient fits
+        assert_eq!(
+            safe_mul_div(u64::MAX, u64::MAX, u64::MAX, Rounding::Down).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            safe_mul_div(u64::MAX, 3, 4, Rounding::Up).unwrap(),
+            u64::MAX / 4 * 3 + 3
+        );
+    }
+
+    #[test]
+    fn rejects_results_that_do_not_fit() {
+        assert!(is_overflow(safe_mul_div(u64::MAX, 2, 1, Rounding::Down)));
+        // (2^65 - 1) / 2 is u64::MAX remainder 1: it fits rounded down, not rounded up
+        let a = 1_190_112_520_884_487_201; // (2^65 - 1) / 31
+        assert_eq!(safe_mul_div(a, 31, 2, Rounding::Down).unwrap(), u64::MAX);
+        assert!(is_overflow(safe_mul_div(a, 31, 2, Rounding::Up)));
+        assert!(is_overflow(mul_div_u128(u128::MAX, 2, 1, Rounding::Down)));
+        assert!(is_overflow(safe_add(u64::MAX, 1)));
+        assert_eq!(safe_add(u64::MAX - 1, 1).unwrap(), u64::MAX);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error::*;
+use crate::state::BPS_DENOMINATOR;
+use anchor_lang::prelude::*;
+
+/// Pyth pull-oracle receiver program; owns every `PriceUpdateV2` feed account.
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LyFJ");
+
+// sha256("account:PriceUpdateV2")[..8]
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// A price read from the oracle: `price * 10^exponent` vault tokens per mint token, give or
+/// take `conf` in the same units.
+#[derive(Debug)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+/// Reads a fully verified price for `feed_id` from a Pyth `PriceUpdateV2` account; see
+/// `parse_price_update` for the checks applied.
+pub fn read_price(
+    oracle: &AccountInfo,
+    feed_id: &[u8; 32],
+    max_staleness: i64,
+    max_conf_bps: u16,
+) -> Result<OraclePrice> {
+    require!(
+        oracle.owner == &PYTH_RECEIVER_PROGRAM_ID,
+        CustomErrorCode::InvalidOracleAccount
+    );
+    let data = oracle.try_borrow_data()?;
+    parse_price_update(
+        &data,
+        feed_id,
+        Clock::get()?.unix_timestamp,
+        max_staleness,
+        max_conf_bps,
+    )
+}
+
+/// Parses a `PriceUpdateV2` account's data, rejecting it unless it is fully verified, carries
+/// `feed_id`, was published at most `max_staleness` seconds before `now` and has a confidence
+/// interval of at most `max_conf_bps` of the price (0 disables the confidence check).
+///
+/// Layout after the discriminator: write_authority (32), verification_level (borsh enum:
+/// 0 = Partial { num_signatures: u8 }, 1 = Full), then the price message
+/// (feed_id (32), price (i64), conf (u64), exponent (i32), publish_time (i64), ...).
+/// The layout is parsed by hand to avoid pulling the Pyth SDK and its Solana version pins.
+pub fn parse_price_update(
+    data: &[u8],
+    feed_id: &[u8; 32],
+    now: i64,
+    max_staleness: i64,
+    max_conf_bps: u16,
+) -> Result<OraclePrice> {
+    require!(
+        data.len() >= 8 + 32 + 1 + 60 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        CustomErrorCode::InvalidOracleAccount
+    );
+
+    // Only fully verified updates are trusted; partial ones were checked by fewer guardians.
+    // Full carries no payload, so the price message starts right after the tag.
+    require!(data[40] == 1, CustomErrorCode::InvalidOracleAccount);
+    let message = &data[41..];
+
+    // The write authority can post any feed to an account, so the account key alone does not
+    // pin the asset being priced
+    require!(
+        &message[..32] == feed_id,
+        CustomErrorCode::OracleFeedMismatch
+    );
+
+    let price = i64::from_le_bytes(message[32..40].try_into().unwrap());
+    let conf = u64::from_le_bytes(message[40..48].try_into().unwrap());
+    let exponent = i32::from_le_bytes(message[48..52].try_into().unwrap());
+    let publish_time = i64::from_le_bytes(message[52..60].try_into().unwrap());
+    require!(price > 0, CustomErrorCode::InvalidOracleAccount);
+
+    require!(
+        now.saturating_sub(publish_time) <= max_staleness,
+        CustomErrorCode::StaleOracle
+    );
+    // conf / price <= max_conf_bps / 10_000, in u128 so neither side overflows
+    require!(
+        max_conf_bps == 0
+            || conf as u128 * BPS_DENOMINATOR as u128 <= price as u128 * max_conf_bps as u128,
+        CustomErrorCode::OracleConfidenceTooWide
+    );
+
+    Ok(OraclePrice {
+        price,
+        conf,
+        exponent,
+        publish_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: [u8; 32] = [7; 32];
+    const NOW: i64 = 1_700_000_000;
+
+    // A fully verified PriceUpdateV2 for FEED: 1.0002 (price 100_020, exponent -5) +/- 0.0005,
+    // published 10 seconds before NOW
+    fn fixture() -> Vec<u8> {
+        let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[9; 32]); // write_authority
+        data.push(1); // VerificationLevel::Full
+        data.extend_from_slice(&FEED);
+        data.extend_from_slice(&100_020i64.to_le_bytes()); // price
+        data.extend_from_slice(&50u64.to_le_bytes()); // conf
+        data.extend_from_slice(&(-5i32).to_le_bytes()); // exponent
+        data.extend_from_slice(&(NOW - 10).to_le_bytes()); // publish_time
+        data.extend_from_slice(&(NOW - 11).to_le_bytes()); // prev_publish_time
+        data.extend_from_slice(&100_000i64.to_le_bytes()); // ema_price
+        data.extend_from_slice(&60u64.to_le_bytes()); // ema_conf
+        data.extend_from_slice(&1u64.to_le_bytes()); // posted_slot
+        data
+    }
+
+    fn error_code(result: Result<OraclePrice>) -> u32 {
+        match result.unwrap_err() {
+            Error::AnchorError(e) => e.error_code_number,
+            e => panic!("unexpected error {e:?}"),
+        }
+    }
+
+    fn code(e: CustomErrorCode) -> u32 {
+        anchor_lang::error::ERROR_CODE_OFFSET + e as u32
+    }
+
+    #[test]
+    fn reads_fields_at_their_offsets() {
+        let price = parse_price_update(&fixture(), &FEED, NOW, 60, 0).unwrap();
+        assert_eq!(price.price, 100_020);
+        assert_eq!(price.conf, 50);
+        assert_eq!(price.exponent, -5);
+        assert_eq!(price.publish_time, NOW - 10);
+    }
+
+    #[test]
+    fn keeps_negative_and_positive_exponents() {
+        for exponent in [-8i32, 0, 3] {
+            let mut data = fixture();
+            data[41 + 48..41 + 52].copy_from_slice(&exponent.to_le_bytes());
+            let price = parse_price_update(&data, &FEED, NOW, 60, 0).unwrap();
+            assert_eq!(price.exponent, exponent);
+        }
+    }
+
+    #[test]
+    fn rejects_another_feed() {
+        assert_eq!(
+            error_code(parse_price_update(&fixture(), &[8; 32], NOW, 60, 0)),
+            code(CustomErrorCode::OracleFeedMismatch)
+        );
+    }
+
+    #[test]
+    fn rejects_partial_verification_and_bad_layouts() {
+        let mut partial = fixture();
+        partial[40] = 0;
+        let mut wrong_discriminator = fixture();
+        wrong_discriminator[0] ^= 1;
+        let truncated = fixture()[..8 + 32 + 1 + 59].to_vec();
+        let mut zero_price = fixture();
+        zero_price[41 + 32..41 + 40].copy_from_slice(&0i64.to_le_bytes());
+        for data in [partial, wrong_discriminator, truncated, zero_price] {
+            assert_eq!(
+                error_code(parse_price_update(&data, &FEED, NOW, 60, 0)),
+                code(CustomErrorCode::InvalidOracleAccount)
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_a_price_exactly_at_the_staleness_limit() {
+        assert!(parse_price_update(&fixture(), &FEED, NOW, 10, 0).is_ok());
+        assert_eq!(
+            error_code(parse_price_update(&fixture(), &FEED, NOW, 9, 0)),
+            code(CustomErrorCode::StaleOracle)
+        );
+    }
+
+    #[test]
+    fn bounds_the_confidence_interval() {
+        // 50 / 100_020 is just under 5 bps
+        assert!(parse_price_update(&fixture(), &FEED, NOW, 60, 5).is_ok());
+        assert_eq!(
+            error_code(parse_price_update(&fixture(), &FEED, NOW, 60, 4)),
+            code(CustomErrorCode::OracleConfidenceTooWide)
+        );
+    }
+}
@@ -1,12 +1,46 @@
 use crate::account_structs::*;
 use crate::error::*;
 use crate::events::*;
-use crate::guard::validate_program_update_authority;
-use crate::state::ProofNode;
+use crate::guard::{validate_config_admin, validate_program_update_authority};
+use crate::math::{safe_add, safe_mul_div, Rounding};
+use crate::oracle::read_price;
+use crate::state::{
+    AirdropRecipient, ClaimBitmap, ClaimRecord, Config, EpochClaim, FreezeScope, MerkleMode,
+    ProofNode, RedemptionRequest, RewardsEpoch, UserPosition, VestingRecord, BPS_DENOMINATOR,
+    CONFIG_VERSION, DEFAULT_MAX_ADMINISTRATORS, DEFAULT_MAX_VAULT_MINTS,
+    DEFAULT_REDEEM_AUTHORITY_MIN_LAMPORTS, MAX_ADMINISTRATORS_CAP, MAX_AIRDROP_BATCH,
+    MAX_EPOCH_CLAIMS, MAX_FREEZE_BATCH, MAX_PROOF_DEPTH, MAX_RECLAIM_BATCH, MAX_REDEEM_BATCH,
+    REDEMPTION_REQUEST_VERSION, REWARDS_EPOCH_VERSION,
+};
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hashv;
-use anchor_spl::token::spl_token::instruction::AuthorityType;
-use anchor_spl::token::{self, MintTo, Transfer};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::sysvar::instructions::get_instruction_relative;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::spl_token_2022::extension::{
+    default_account_state::DefaultAccountState, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_interface::spl_token_2022::state::AccountState;
+use anchor_spl::token_interface::spl_token_2022::{self, instruction::AuthorityType};
+use anchor_spl::token_interface::{
+    self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+// Domain separation prefixes so a leaf can never be mistaken for an internal node
+// (second-preimage protection). Off-chain tree builders must use the same prefixes.
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+// Per-step proof logging costs compute on every claim, so it is only compiled in with the
+// `verbose` feature
+macro_rules! proof_log {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "verbose")]
+        msg!($($arg)*);
+    };
+}
 
 pub fn initialize(
     ctx: Context<Initialize>,
@@ -20,19 +54,25 @@ pub fn initialize(
 
     validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
+    // A freshly created config has a default mint; anything else was initialized before
     require!(
-        freeze_administrators.len() <= 5,
-        CustomErrorCode::TooManyAdministrators
+        ctx.accounts.config.mint == Pubkey::default(),
+        CustomErrorCode::AlreadyInitialized
     );
 
+    validate_administrators(&freeze_administrators, DEFAULT_MAX_ADMINISTRATORS)?;
+    validate_administrators(&rewards_administrators, DEFAULT_MAX_ADMINISTRATORS)?;
+
+    require!(vault_mint != mint, CustomErrorCode::VaultMintConflict);
+
+    // Both must name the mint accounts passed in, which rules out the default pubkey
     require!(
-        rewards_administrators.len() <= 5,
-        CustomErrorCode::TooManyAdministrators
+        ctx.accounts.vault_mint.key() == vault_mint,
+        CustomErrorCode::InvalidVaultMint
     );
-
     require!(
-        vault_mint != mint,
-        CustomErrorCode::VaultAndMintCannotBeSame
+        ctx.accounts.mint.key() == mint,
+        CustomErrorCode::InvalidMint
     );
 
     let config = &mut ctx.accounts.config;
@@ -42,6 +82,16 @@ pub fn initialize(
     config.rewards_administrators = rewards_administrators;
     config.vault_authority = ctx.accounts.vault_token_account.owner;
     config.bump = ctx.bumps.config;
+    config.rate_numerator = 1;
+    config.rate_denominator = 1;
+    config.vault_decimals = ctx.accounts.vault_mint.decimals;
+    config.mint_decimals = ctx.accounts.mint.decimals;
+    config.version = CONFIG_VERSION;
+    config.max_administrators = DEFAULT_MAX_ADMINISTRATORS;
+    config.redeem_authority_min_lamports = DEFAULT_REDEEM_AUTHORITY_MIN_LAMPORTS;
+    config.next_epoch_index = 0;
+    config.max_vault_mints = DEFAULT_MAX_VAULT_MINTS;
+    config.round_down_on_redeem = true;
 
     // The redeem vault token account must be owned by the program-derived address (PDA)
     // and is a token account that holds the deposited vault tokens (e.g., USDC).
@@ -53,11 +103,11 @@ pub fn initialize(
             b"redeem_vault_authority",
             &[ctx.bumps.redeem_vault_authority],
         ];
-        let signer = &[&seeds[..]];
-        token::set_authority(
+        let signer = &[seeds];
+        token_interface::set_authority(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                token::SetAuthority {
+                ctx.accounts.vault_token_program.to_account_info(),
+                token_interface::SetAuthority {
                     account_or_mint: ctx.accounts.redeem_vault_token_account.to_account_info(),
                     current_authority: ctx.accounts.signer.to_account_info(),
                 },
@@ -68,401 +118,1004 @@ pub fn initialize(
         )?;
     }
 
+    // The account is now owned by the PDA; complete_redeem only pays out of this one
+    ctx.accounts.config.redeem_vault = ctx.accounts.redeem_vault_token_account.key();
+
+    // deposit and claim_rewards mint through the mint authority PDA. Hand it over if the
+    // signer still holds it, otherwise it must already be the PDA.
+    let mint_authority_pda = ctx.accounts.mint_authority.key();
+    let mint_authority: Option<Pubkey> = ctx.accounts.mint.mint_authority.into();
+    if mint_authority == Some(ctx.accounts.signer.key()) {
+        token_interface::set_authority(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::SetAuthority {
+                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                    current_authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+            AuthorityType::MintTokens,
+            Some(mint_authority_pda),
+        )?;
+    } else {
+        require!(
+            mint_authority == Some(mint_authority_pda),
+            CustomErrorCode::InvalidMintAuthority
+        );
+    }
+
+    // freeze_token_account relies on the mint's freeze authority being the PDA. Same
+    // handoff as above.
+    let freeze_authority_pda = ctx.accounts.freeze_authority_pda.key();
+    let freeze_authority: Option<Pubkey> = ctx.accounts.mint.freeze_authority.into();
+    if freeze_authority == Some(ctx.accounts.signer.key()) {
+        token_interface::set_authority(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::SetAuthority {
+                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                    current_authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+            AuthorityType::FreezeAccount,
+            Some(freeze_authority_pda),
+        )?;
+    } else {
+        require!(
+            freeze_authority == Some(freeze_authority_pda),
+            CustomErrorCode::InvalidFreezeAuthority
+        );
+    }
+
+    let config = &ctx.accounts.config;
+    emit!(Initialized {
+        config: config.key(),
+        vault: config.vault,
+        mint: config.mint,
+        freeze_administrators: config.freeze_administrators.clone(),
+        rewards_administrators: config.rewards_administrators.clone(),
+        vault_authority: config.vault_authority,
+        redeem_vault: config.redeem_vault,
+        redeem_vault_authority: ctx.accounts.redeem_vault_authority.key(),
+        mint_authority: mint_authority_pda,
+        freeze_authority: freeze_authority_pda,
+        version: config.version,
+    });
+
     Ok(())
 }
 
-pub fn pause(ctx: Context<Pause>, pause: bool) -> Result<()> {
+// Reallocate a config account created by an older program to fit the current layout for its
+// administrator limit and stamp the current layout version (only program update authority
+// can do this)
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
     // Validate that the signer is the program's update authority
     validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
-    let config = &mut ctx.accounts.config;
-    config.paused = pause;
+    let config_info = ctx.accounts.config.to_account_info();
+    let old_len = config_info.data_len();
+    // Read the stored version from the layout as written; its `version` byte may not exist yet
+    let (from_version, serialized_len) = {
+        let data = config_info.try_borrow_data()?;
+        let from_version = Config::stored_version(&data)?;
+        // Never downgrade an account written by a newer program
+        require!(
+            from_version <= CONFIG_VERSION,
+            CustomErrorCode::ConfigVersionMismatch
+        );
+        (from_version, Config::serialized_len(&data, from_version)?)
+    };
+
+    // The administrator limit sits behind the administrator lists, so it is only known once the
+    // account deserializes. Grow it to fit any limit first.
+    let provisional_len = old_len.max(Config::space(MAX_ADMINISTRATORS_CAP));
+    if provisional_len > old_len {
+        config_info.resize(provisional_len)?;
+    }
 
-    msg!("Program paused state set to: {}", pause);
+    let mut data = config_info.try_borrow_mut_data()?;
+    // A shrunk administrator list leaves stale bytes behind the serialized data; clear them so
+    // every field the stored version lacks reads as zero/false
+    data[serialized_len..].fill(0);
+    let mut config = Config::try_deserialize(&mut &data[..])?;
+    // The original layout recorded neither the exchange rate nor the mints' decimals, and every
+    // field appended since reads as zero; fill in the ones whose zero is not the right default
+    if from_version < 1 {
+        require_keys_eq!(
+            ctx.accounts.vault_mint.key(),
+            config.vault,
+            CustomErrorCode::InvalidVaultMint
+        );
+        require_keys_eq!(
+            ctx.accounts.mint.key(),
+            config.mint,
+            CustomErrorCode::InvalidMint
+        );
+        config.rate_numerator = 1;
+        config.rate_denominator = 1;
+        config.vault_decimals = ctx.accounts.vault_mint.decimals;
+        config.mint_decimals = ctx.accounts.mint.decimals;
+        // The original account was sized for the default administrator limit
+        config.max_administrators = DEFAULT_MAX_ADMINISTRATORS;
+        config.redeem_authority_min_lamports = DEFAULT_REDEEM_AUTHORITY_MIN_LAMPORTS;
+        config.max_vault_mints = DEFAULT_MAX_VAULT_MINTS;
+        // Redemptions always rounded down before the rounding policy was configurable
+        config.round_down_on_redeem = true;
+    }
+    config.version = CONFIG_VERSION;
+    config.try_serialize(&mut &mut data[..])?;
+    // resize and data_len borrow the data again
+    drop(data);
+
+    // Settle on the size the current layout needs for the stored limit, never shrinking
+    let new_len = old_len.max(Config::space(config.max_administrators));
+    if new_len != config_info.data_len() {
+        config_info.resize(new_len)?;
+    }
+    // Top up rent for the larger account
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(config_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.signer.to_account_info(),
+                    to: config_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    msg!(
+        "Config migrated from version {} to {} ({} -> {} bytes)",
+        from_version,
+        CONFIG_VERSION,
+        old_len,
+        config_info.data_len()
+    );
+    emit!(ConfigMigrated {
+        from_version,
+        to_version: CONFIG_VERSION,
+    });
     Ok(())
 }
 
-pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-    require!(!ctx.accounts.config.paused, CustomErrorCode::ProtocolPaused);
-    require!(amount > 0, CustomErrorCode::InvalidAmount);
+// Raise or lower the administrator list limit; Anchor reallocates the config account to fit
+// (only program update authority can do this)
+pub fn set_max_administrators(
+    ctx: Context<SetMaxAdministrators>,
+    max_administrators: u8,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
-    // Validate that vault_token_account is owned by the configured vault authority
+    let config = &mut ctx.accounts.config;
     require!(
-        ctx.accounts.vault_token_account.owner == ctx.accounts.config.vault_authority,
-        CustomErrorCode::InvalidVaultAuthority
+        max_administrators <= MAX_ADMINISTRATORS_CAP
+            && config.freeze_administrators.len() <= max_administrators as usize
+            && config.rewards_administrators.len() <= max_administrators as usize
+            && config.previous_rewards_administrators.len() <= max_administrators as usize,
+        CustomErrorCode::InvalidMaxAdministrators
     );
+    config.max_administrators = max_administrators;
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.user_vault_token_account.to_account_info(),
-        to: ctx.accounts.vault_token_account.to_account_info(),
-        authority: ctx.accounts.signer.to_account_info(),
-    };
-    token::transfer(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
-        amount,
-    )?;
-
-    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
-    let signer = &[&seeds[..]];
-    let cpi_accounts = MintTo {
-        mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.user_mint_token_account.to_account_info(),
-        authority: ctx.accounts.mint_authority.to_account_info(),
-    };
-    token::mint_to(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            signer,
-        ),
-        amount,
-    )?;
+    msg!("Maximum administrators set to {}", max_administrators);
+    Ok(())
+}
 
-    msg!("Emitting DepositEvent");
-    emit!(DepositEvent {
-        user: ctx.accounts.signer.key(),
-        amount,
-        mint: ctx.accounts.mint.key(),
-        vault: ctx.accounts.vault_token_account.mint,
-    });
-    msg!("Emitted DepositEvent");
+pub fn pause(ctx: Context<Pause>) -> Result<()> {
+    set_paused(ctx, true)
+}
 
-    Ok(())
+pub fn unpause(ctx: Context<Pause>) -> Result<()> {
+    set_paused(ctx, false)
 }
 
-pub fn request_redeem(ctx: Context<RequestRedeem>, amount: u64) -> Result<()> {
-    require!(!ctx.accounts.config.paused, CustomErrorCode::ProtocolPaused);
-    require!(amount > 0, CustomErrorCode::InvalidAmount);
+fn set_paused(ctx: Context<Pause>, paused: bool) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
-    // Check user's mint token balance
-    let user_balance = ctx.accounts.user_mint_token_account.amount;
-    require!(user_balance >= amount, CustomErrorCode::InsufficientBalance);
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
 
-    let vault_balance = ctx.accounts.redeem_vault_authority.lamports();
-    require!(
-        vault_balance > 100_000, // ~0.0001 SOL buffer
-        CustomErrorCode::InsufficientRedeemVaultFunds
-    );
+    msg!("Program paused state set to: {}", paused);
+    Ok(())
+}
 
-    // amount_to_redeem = min(user wYLDS balance, requested)
-    // We do this to prevent the program from over-burning when the
-    // redeem is completed. The complete redeem will also check the
-    // redeem amount against the user balance at the time of completion to
-    // prevent burn error.
-    let amount_to_redeem = std::cmp::min(user_balance, amount);
-    require!(amount_to_redeem > 0, CustomErrorCode::InvalidAmount);
+// Pause the program until a timestamp, after which it resumes on its own. 0 clears the
+// timed pause; the indefinite `pause` flag is unaffected.
+pub fn pause_until(ctx: Context<Pause>, pause_until_ts: i64) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
-    msg!("RequestRedeem user account balance: {}", user_balance);
-    msg!("Requested amount to redeem: {}", amount);
-    msg!("Actual amount to redeem: {}", amount_to_redeem);
+    let config = &mut ctx.accounts.config;
+    config.pause_until_ts = pause_until_ts;
 
-    // Set burn authority to the redeem vault authority PDA so it can burn tokens later
-    token::approve(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token::Approve {
-                to: ctx.accounts.user_mint_token_account.to_account_info(),
-                delegate: ctx.accounts.redeem_vault_authority.to_account_info(),
-                authority: ctx.accounts.signer.to_account_info(),
-            },
-        ),
-        amount_to_redeem,
-    )?;
+    msg!("Program paused until: {}", pause_until_ts);
+    Ok(())
+}
 
-    msg!("Emitting RedemptionRequested");
-    emit!(RedemptionRequested {
-        user: ctx.accounts.signer.key(),
-        amount: amount_to_redeem,
-        vault_mint: ctx.accounts.config.vault,
-        mint: ctx.accounts.config.mint,
-    });
-    msg!("Emitted RedemptionRequested");
+// Pause deposits and/or redemptions independently of the global pause flag
+pub fn set_pause_flags(ctx: Context<SetPauseFlags>, deposits: bool, redeems: bool) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
-    msg!("recording redemption request");
-    // Record the request (creates a lock on the user)
-    let request = &mut ctx.accounts.redemption_request;
-    request.user = ctx.accounts.signer.key();
-    request.amount = amount_to_redeem;
-    request.mint = ctx.accounts.config.mint;
-    request.bump = ctx.bumps.redemption_request;
+    let config = &mut ctx.accounts.config;
+    config.deposits_paused = deposits;
+    config.redeems_paused = redeems;
 
-    msg!("done with request redeem");
+    msg!("Deposits paused: {}, redeems paused: {}", deposits, redeems);
     Ok(())
 }
 
-pub fn complete_redeem(ctx: Context<CompleteRedeem>) -> Result<()> {
-    // Admin gate
+// Update the deposit-to-mint exchange rate (only the config admin can do this)
+pub fn update_exchange_rate(
+    ctx: Context<UpdateExchangeRate>,
+    rate_numerator: u64,
+    rate_denominator: u64,
+) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
     require!(
-        ctx.accounts
-            .config
-            .rewards_administrators
-            .contains(&ctx.accounts.admin.key()),
-        CustomErrorCode::InvalidRewardsAdministrator
+        rate_numerator > 0 && rate_denominator > 0,
+        CustomErrorCode::InvalidExchangeRate
     );
 
-    let req = &ctx.accounts.redemption_request;
-
-    // The request redeem function will set the redeem amount to the min
-    // of the requested amount and the user's mint balance at the request.
-    // This prevents program from burning more than their balance at the time.
-    // However, we also do the same here to prevent error in the situation where
-    // the user transfers mint out of their account before this complete request
-    // executes.
-    let user_mint_balance = ctx.accounts.user_mint_token_account.amount;
-    let amount_to_redeem = std::cmp::min(user_mint_balance, req.amount);
-    require!(amount_to_redeem > 0, CustomErrorCode::InvalidAmount);
-
-    // check vault has enough USDC
-    require!(
-        ctx.accounts.redeem_vault_token_account.amount >= amount_to_redeem,
-        CustomErrorCode::InsufficientVaultBalance
+    let config = &mut ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+    emit_parameter_change(
+        ConfigParameter::RateNumerator,
+        config.rate_numerator,
+        rate_numerator,
+        signer,
     );
+    emit_parameter_change(
+        ConfigParameter::RateDenominator,
+        config.rate_denominator,
+        rate_denominator,
+        signer,
+    );
+    config.rate_numerator = rate_numerator;
+    config.rate_denominator = rate_denominator;
 
-    // signer seeds for the PDA
-    let seeds: &[&[u8]] = &[
-        b"redeem_vault_authority",
-        &[ctx.bumps.redeem_vault_authority],
-    ];
-    let signer = &[&seeds[..]];
+    msg!(
+        "Exchange rate updated to {}/{}",
+        rate_numerator,
+        rate_denominator
+    );
+    Ok(())
+}
 
-    // Burn user's wYLDS using PDA as delegate
-    token::burn(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            token::Burn {
-                mint: ctx.accounts.mint.to_account_info(),
-                from: ctx.accounts.user_mint_token_account.to_account_info(),
-                authority: ctx.accounts.redeem_vault_authority.to_account_info(),
-            },
-            signer,
-        ),
-        amount_to_redeem,
+// Update the per-transaction deposit limits (only the config admin can do this)
+pub fn set_deposit_limits(
+    ctx: Context<SetDepositLimits>,
+    min_deposit: u64,
+    max_deposit: u64,
+) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
     )?;
 
-    // Transfer USDC from redeem vault to user (PDA is authority)
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            token::Transfer {
-                from: ctx.accounts.redeem_vault_token_account.to_account_info(),
-                to: ctx.accounts.user_vault_token_account.to_account_info(),
-                authority: ctx.accounts.redeem_vault_authority.to_account_info(),
-            },
-            signer,
-        ),
-        amount_to_redeem,
-    )?;
+    // max_deposit == 0 means no maximum
+    require!(
+        max_deposit == 0 || min_deposit <= max_deposit,
+        CustomErrorCode::InvalidAmount
+    );
 
-    msg!("Emitting RedeemCompleted");
-    emit!(RedeemCompleted {
-        user: ctx.accounts.user.key(),
-        admin: ctx.accounts.admin.key(),
-        amount: amount_to_redeem,
-        mint: ctx.accounts.mint.key(),
-        vault: ctx.accounts.redeem_vault_token_account.mint,
-    });
-    msg!("Emitted RedeemCompleted");
+    let config = &mut ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+    emit_parameter_change(
+        ConfigParameter::MinDeposit,
+        config.min_deposit,
+        min_deposit,
+        signer,
+    );
+    emit_parameter_change(
+        ConfigParameter::MaxDeposit,
+        config.max_deposit,
+        max_deposit,
+        signer,
+    );
+    config.min_deposit = min_deposit;
+    config.max_deposit = max_deposit;
 
-    // Anchor will auto-close redemption_request to `user` per the accounts attr
+    msg!(
+        "Deposit limits updated. Min: {}, max: {}",
+        min_deposit,
+        max_deposit
+    );
     Ok(())
 }
 
-// Set the mint token's freeze authority to the program PDA
-// Update the list of freeze administrators (only program update authority can do this)
-pub fn update_freeze_administrators(
-    ctx: Context<UpdateFreezeAdministrators>,
-    new_administrators: Vec<Pubkey>,
-) -> Result<()> {
-    // Validate that the signer is the program's update authority
-    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+// Update the cap on total minted supply (only the config admin can do this)
+pub fn set_mint_cap(ctx: Context<SetMintCap>, mint_cap: u64) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
 
     let config = &mut ctx.accounts.config;
-
-    require!(
-        new_administrators.len() <= 5,
-        CustomErrorCode::TooManyAdministrators
+    emit_parameter_change(
+        ConfigParameter::MintCap,
+        config.mint_cap,
+        mint_cap,
+        ctx.accounts.signer.key(),
     );
-
-    config.freeze_administrators = new_administrators;
+    config.mint_cap = mint_cap;
 
     msg!(
-        "Freeze administrators updated. New count: {}",
-        config.freeze_administrators.len()
+        "Mint cap set to: {} (total minted: {})",
+        mint_cap,
+        config.total_minted
     );
     Ok(())
 }
 
-// Set the mint token's rewards authority to the program PDA
-// Update the list of rewards administrators (only program update authority can do this)
-pub fn update_rewards_administrators(
-    ctx: Context<UpdateRewardsAdministrators>,
-    new_administrators: Vec<Pubkey>,
+// Update the lifetime deposit cap per user (only the config admin can do this)
+pub fn set_per_user_deposit_cap(
+    ctx: Context<SetPerUserDepositCap>,
+    per_user_deposit_cap: u64,
 ) -> Result<()> {
-    // Validate that the signer is the program's update authority
-    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
 
     let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::PerUserDepositCap,
+        config.per_user_deposit_cap,
+        per_user_deposit_cap,
+        ctx.accounts.signer.key(),
+    );
+    config.per_user_deposit_cap = per_user_deposit_cap;
+
+    msg!("Per-user deposit cap set to {}", per_user_deposit_cap);
+    Ok(())
+}
+
+// Update the deposit fee and the account that collects it (only the config admin can do this)
+pub fn set_deposit_fee(ctx: Context<SetDepositFee>, deposit_fee_bps: u16) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
 
     require!(
-        new_administrators.len() <= 5,
-        CustomErrorCode::TooManyAdministrators
+        deposit_fee_bps <= BPS_DENOMINATOR,
+        CustomErrorCode::InvalidFeeBps
     );
 
-    config.rewards_administrators = new_administrators;
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::DepositFeeBps,
+        config.deposit_fee_bps as u64,
+        deposit_fee_bps as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.deposit_fee_bps = deposit_fee_bps;
+    config.fee_vault = ctx.accounts.fee_vault_token_account.key();
 
     msg!(
-        "Rewards administrators updated. New count: {}",
-        config.freeze_administrators.len()
+        "Deposit fee set to {} bps, fee vault: {}",
+        deposit_fee_bps,
+        config.fee_vault
     );
     Ok(())
 }
 
-// Freeze a specific token account (only freeze administrators can do this)
-pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
-    let config = &ctx.accounts.config;
-    let signer = ctx.accounts.signer.key();
+// Update the redemption fee (only the config admin can do this)
+pub fn set_redeem_fee(ctx: Context<SetRedeemFee>, redeem_fee_bps: u16) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
 
-    // Verify signer is a freeze administrator
     require!(
-        config.freeze_administrators.contains(&signer),
-        CustomErrorCode::UnauthorizedFreezeAdministrator
+        redeem_fee_bps <= BPS_DENOMINATOR,
+        CustomErrorCode::InvalidFeeBps
     );
 
-    let freeze_authority_seeds: &[&[&[u8]]] =
-        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
-
-    let cpi_accounts = token::FreezeAccount {
-        account: ctx.accounts.token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
-    };
+    let config = &mut ctx.accounts.config;
+    // Fees are paid into the fee vault registered through set_deposit_fee
+    require!(
+        redeem_fee_bps == 0 || config.fee_vault != Pubkey::default(),
+        CustomErrorCode::InvalidFeeVault
+    );
+    emit_parameter_change(
+        ConfigParameter::RedeemFeeBps,
+        config.redeem_fee_bps as u64,
+        redeem_fee_bps as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.redeem_fee_bps = redeem_fee_bps;
 
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        cpi_accounts,
-        freeze_authority_seeds,
+    msg!("Redeem fee set to {} bps", redeem_fee_bps);
+    Ok(())
+}
+
+// Update the rewards claim fee (only the config admin can do this)
+pub fn set_claim_fee(ctx: Context<SetClaimFee>, claim_fee_bps: u16) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    require!(
+        claim_fee_bps <= BPS_DENOMINATOR,
+        CustomErrorCode::InvalidFeeBps
     );
 
-    token::freeze_account(cpi_ctx)?;
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::ClaimFeeBps,
+        config.claim_fee_bps as u64,
+        claim_fee_bps as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.claim_fee_bps = claim_fee_bps;
 
-    msg!(
-        "Token account {} frozen by administrator {}",
-        ctx.accounts.token_account.key(),
-        signer
+    msg!("Claim fee set to {} bps", claim_fee_bps);
+    Ok(())
+}
+
+// Update the redemption timelock (only the config admin can do this)
+pub fn set_redeem_delay(ctx: Context<SetRedeemDelay>, redeem_delay_seconds: i64) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    require!(redeem_delay_seconds >= 0, CustomErrorCode::InvalidAmount);
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::RedeemDelaySeconds,
+        config.redeem_delay_seconds as u64,
+        redeem_delay_seconds as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.redeem_delay_seconds = redeem_delay_seconds;
+
+    msg!("Redeem delay set to {} seconds", redeem_delay_seconds);
+    Ok(())
+}
+
+// Update the redemption request expiry (only the config admin can do this)
+pub fn set_redeem_expiry(ctx: Context<SetRedeemExpiry>, redeem_expiry_seconds: i64) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    require!(redeem_expiry_seconds >= 0, CustomErrorCode::InvalidAmount);
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::RedeemExpirySeconds,
+        config.redeem_expiry_seconds as u64,
+        redeem_expiry_seconds as u64,
+        ctx.accounts.signer.key(),
     );
+    config.redeem_expiry_seconds = redeem_expiry_seconds;
+
+    msg!("Redeem expiry set to {} seconds", redeem_expiry_seconds);
     Ok(())
 }
 
-// Thaw a specific token account (only freeze administrators can do this)
-pub fn thaw_token_account(ctx: Context<ThawTokenAccount>) -> Result<()> {
-    let config = &ctx.accounts.config;
-    let signer = ctx.accounts.signer.key();
+// Switch rewards between minting and the pre-funded rewards vault (only program update authority can do this)
+pub fn set_rewards_from_vault(ctx: Context<SetRewardsFromVault>, enabled: bool) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
-    // Verify signer is a freeze administrator
+    let config = &mut ctx.accounts.config;
+    config.rewards_from_vault = enabled;
+
+    msg!("Rewards from vault set to {}", enabled);
+    Ok(())
+}
+
+// Setters report each economic field they change. Unchanged fields are skipped to keep
+// multi-field setters from flooding the log.
+fn emit_parameter_change(
+    parameter: ConfigParameter,
+    old_value: u64,
+    new_value: u64,
+    signer: Pubkey,
+) {
+    if old_value != new_value {
+        emit!(ConfigParameterChanged {
+            parameter,
+            old_value,
+            new_value,
+            signer,
+        });
+    }
+}
+
+// Marks a fund-moving instruction as running and writes the flag to the account before any
+// CPI, so a nested entry sees it and fails. The caller clears it after its last CPI; a failed
+// instruction rolls the flag back with everything else.
+fn enter_guarded(config: &mut Account<Config>) -> Result<()> {
+    require!(!config.in_progress, CustomErrorCode::ReentrancyDetected);
+    config.in_progress = true;
+    config.exit(&crate::ID)
+}
+
+// Vault tokens owed for burning `amount` wYLDS, shared by complete_redeem and simulate_redeem.
+// wYLDS burned is converted back to USDC at the oracle price when one is configured,
+// otherwise at the inverse of the deposit rate
+fn redeem_vault_amount(
+    config: &Config,
+    amount: u64,
+    price_oracle: Option<&UncheckedAccount>,
+) -> Result<u64> {
+    if config.price_oracle == Pubkey::default() {
+        return config.mint_to_vault_amount(amount);
+    }
+    let oracle = price_oracle.ok_or(CustomErrorCode::InvalidOracleAccount)?;
     require!(
-        config.freeze_administrators.contains(&signer),
-        CustomErrorCode::UnauthorizedFreezeAdministrator
+        oracle.key() == config.price_oracle,
+        CustomErrorCode::InvalidOracleAccount
+    );
+    let price = read_price(
+        oracle,
+        &config.price_feed_id,
+        config.max_oracle_staleness,
+        config.max_oracle_conf_bps,
+    )?;
+    msg!(
+        "Oracle price {} +/- {} x 10^{} published at {}",
+        price.price,
+        price.conf,
+        price.exponent,
+        price.publish_time
     );
+    config.mint_to_vault_amount_at_price(amount, price.price, price.exponent)
+}
 
-    let freeze_authority_seeds: &[&[&[u8]]] =
-        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+// Drop the redeem vault authority's approval once a request is closed; `user_mint_token_account`
+// must be reloaded after any burn. Token-2022 lets a delegate revoke itself, but not on a frozen
+// account; SPL Token only accepts the owner. Where the revoke is skipped the approval stays until
+// the user revokes it. It is inert: the PDA only burns through an open redemption request.
+fn revoke_leftover_redeem_allowance<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    user_mint_token_account: &InterfaceAccount<'info, TokenAccount>,
+    redeem_vault_authority: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if token_program.key() != spl_token_2022::ID
+        || user_mint_token_account.is_frozen()
+        || user_mint_token_account.delegate != COption::Some(redeem_vault_authority.key())
+        || user_mint_token_account.delegated_amount == 0
+    {
+        return Ok(());
+    }
+    token_interface::revoke(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token_interface::Revoke {
+            source: user_mint_token_account.to_account_info(),
+            authority: redeem_vault_authority.clone(),
+        },
+        signer,
+    ))
+}
 
-    let cpi_accounts = token::ThawAccount {
-        account: ctx.accounts.token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
-    };
+// With default_frozen on, deposits only mint into wYLDS accounts a freeze administrator has
+// thawed; minting into a frozen account would otherwise fail inside the token program.
+fn require_kyc_approved(config: &Config, user_mint_token_account: &TokenAccount) -> Result<()> {
+    require!(
+        !config.default_frozen || !user_mint_token_account.is_frozen(),
+        CustomErrorCode::AccountNotKycApproved
+    );
+    Ok(())
+}
 
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        cpi_accounts,
-        freeze_authority_seeds,
+// Pause and size checks shared by every deposit path; `value` is in primary vault token units.
+fn check_deposit(config: &Config, value: u64) -> Result<()> {
+    require!(!config.is_paused()?, CustomErrorCode::ProgramPaused);
+    require!(!config.deposits_paused, CustomErrorCode::DepositsPaused);
+    require!(value > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        value >= config.min_deposit,
+        CustomErrorCode::DepositBelowMinimum
     );
+    require!(
+        config.max_deposit == 0 || value <= config.max_deposit,
+        CustomErrorCode::DepositAboveMaximum
+    );
+    Ok(())
+}
 
-    token::thaw_account(cpi_ctx)?;
+// Checks shared by deposit and deposit_with_split. Returns (fee, net_amount, mint_amount)
+// and counts the full mint_amount against the mint cap.
+fn prepare_deposit(config: &mut Config, amount: u64) -> Result<(u64, u64, u64)> {
+    check_deposit(config, amount)?;
 
-    msg!(
-        "Token account {} thawed by administrator {}",
-        ctx.accounts.token_account.key(),
-        signer
+    // The fee is skimmed in vault tokens; wYLDS is only minted for the net deposit
+    let fee = Config::fee_for(amount, config.deposit_fee_bps)?;
+    let net_amount = amount - fee;
+
+    let mint_amount = config.vault_to_mint_amount(net_amount)?;
+    require!(mint_amount > 0, CustomErrorCode::InvalidAmount);
+    config.record_mint(mint_amount)?;
+
+    Ok((fee, net_amount, mint_amount))
+}
+
+// Update the redeem vault reserve (only the config admin can do this)
+pub fn set_min_vault_reserve(
+    ctx: Context<SetMinVaultReserve>,
+    min_vault_reserve: u64,
+) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::MinVaultReserve,
+        config.min_vault_reserve,
+        min_vault_reserve,
+        ctx.accounts.signer.key(),
     );
+    config.min_vault_reserve = min_vault_reserve;
+
+    msg!("Minimum vault reserve set to {}", min_vault_reserve);
     Ok(())
 }
 
-pub fn create_rewards_epoch(
-    ctx: Context<CreateRewardsEpoch>,
-    index: u64,
-    merkle_root: [u8; 32],
-    total: u64,
+pub fn set_price_oracle(
+    ctx: Context<SetPriceOracle>,
+    price_oracle: Pubkey,
+    price_feed_id: [u8; 32],
+    max_oracle_staleness: i64,
+    max_oracle_conf_bps: u16,
 ) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    require!(max_oracle_staleness >= 0, CustomErrorCode::InvalidAmount);
     require!(
-        ctx.accounts
-            .config
-            .rewards_administrators
-            .contains(&ctx.accounts.admin.key()),
-        CustomErrorCode::InvalidRewardsAdministrator
+        max_oracle_conf_bps <= BPS_DENOMINATOR,
+        CustomErrorCode::InvalidAmount
+    );
+    // Every price update carries a feed id, so an oracle without one could never be read
+    require!(
+        price_oracle == Pubkey::default() || price_feed_id != [0; 32],
+        CustomErrorCode::OracleFeedMismatch
+    );
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::MaxOracleStaleness,
+        config.max_oracle_staleness as u64,
+        max_oracle_staleness as u64,
+        ctx.accounts.signer.key(),
+    );
+    emit_parameter_change(
+        ConfigParameter::MaxOracleConfBps,
+        config.max_oracle_conf_bps as u64,
+        max_oracle_conf_bps as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.price_oracle = price_oracle;
+    config.price_feed_id = price_feed_id;
+    config.max_oracle_staleness = max_oracle_staleness;
+    config.max_oracle_conf_bps = max_oracle_conf_bps;
+
+    msg!(
+        "Price oracle set to {} (max staleness {}s, max confidence {} bps)",
+        price_oracle,
+        max_oracle_staleness,
+        max_oracle_conf_bps
     );
-    let e = &mut ctx.accounts.epoch;
-    e.index = index;
-    e.merkle_root = merkle_root;
-    e.total = total;
-    e.created_ts = Clock::get()?.unix_timestamp;
     Ok(())
 }
 
-pub fn claim_rewards(ctx: Context<ClaimRewards>, amount: u64, proof: Vec<ProofNode>) -> Result<()> {
-    require!(!ctx.accounts.config.paused, CustomErrorCode::ProtocolPaused);
-    require!(amount > 0, CustomErrorCode::InvalidAmount);
-    // leaf = sha256(user || amount_le || epoch_index_le)
-    let mut data = Vec::with_capacity(32 + 8 + 8);
-    data.extend_from_slice(ctx.accounts.user.key.as_ref());
-    data.extend_from_slice(&amount.to_le_bytes());
-    data.extend_from_slice(&ctx.accounts.epoch.index.to_le_bytes());
-    let mut node = hashv(&[&data]).to_bytes();
+pub fn deposit(ctx: Context<Deposit>, amount: u64, memo: [u8; 32]) -> Result<()> {
+    let (fee, net_amount, mint_amount) = prepare_deposit(&mut ctx.accounts.config, amount)?;
+    let position = &mut ctx.accounts.user_position;
+    position.user = ctx.accounts.signer.key();
+    position.bump = ctx.bumps.user_position;
+    position.record_deposit(amount, ctx.accounts.config.per_user_deposit_cap)?;
+    position.record_deposit_time(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.config.deposit_cooldown_seconds,
+    )?;
+    require!(
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+    require!(
+        !ctx.accounts.config.allowlist_enabled || ctx.accounts.allowlist_entry.is_some(),
+        CustomErrorCode::AddressNotAllowlisted
+    );
+    require_kyc_approved(&ctx.accounts.config, &ctx.accounts.user_mint_token_account)?;
 
-    msg!("User Leaf node: {}", hex::encode(node));
+    // Validate that vault_token_account is owned by the configured vault authority
+    require_eq_logged!(
+        ctx.accounts.vault_token_account.owner,
+        ctx.accounts.config.vault_authority,
+        CustomErrorCode::InvalidVaultAuthority
+    );
 
-    // iterate through proof
-    for (i, step) in proof.iter().enumerate() {
-        let sib = &step.sibling;
+    enter_guarded(&mut ctx.accounts.config)?;
 
-        if sib.iter().all(|&b| b == 0) {
-            msg!("[{}] right: sibling is zero - hashing just the node", i);
-            node = hashv(&[&node]).to_bytes();
-            continue;
-        }
+    if fee > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_vault_token_account.to_account_info(),
+            mint: ctx.accounts.vault_mint.to_account_info(),
+            to: ctx.accounts.fee_vault_token_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.vault_token_program.to_account_info(),
+                cpi_accounts,
+            ),
+            fee,
+            ctx.accounts.config.vault_decimals,
+        )?;
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_vault_token_account.to_account_info(),
+        mint: ctx.accounts.vault_mint.to_account_info(),
+        to: ctx.accounts.vault_token_account.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.vault_token_program.to_account_info(),
+            cpi_accounts,
+        ),
+        net_amount,
+        ctx.accounts.config.vault_decimals,
+    )?;
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let signer = &[seeds];
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.user_mint_token_account.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        mint_amount,
+    )?;
+
+    ctx.accounts.config.in_progress = false;
+
+    // Only emitted once both the transfer and mint CPIs above have succeeded.
+    msg!("Emitting DepositEvent");
+    emit!(DepositEvent {
+        user: ctx.accounts.signer.key(),
+        amount,
+        mint: ctx.accounts.config.mint,
+        vault: ctx.accounts.config.vault,
+        memo,
+    });
+    msg!("Emitted DepositEvent");
+
+    Ok(())
+}
+
+// Update the vesting cliff for split deposits (only the config admin can do this)
+pub fn set_vesting_cliff(ctx: Context<SetVestingCliff>, vesting_cliff_seconds: i64) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    require!(vesting_cliff_seconds >= 0, CustomErrorCode::InvalidAmount);
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::VestingCliffSeconds,
+        config.vesting_cliff_seconds as u64,
+        vesting_cliff_seconds as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.vesting_cliff_seconds = vesting_cliff_seconds;
+
+    msg!("Vesting cliff set to {} seconds", vesting_cliff_seconds);
+    Ok(())
+}
+
+// Deposits the full amount but mints only immediate_bps of the wYLDS now; the remainder
+// is recorded in the user's VestingRecord and minted by claim_vested after the cliff.
+pub fn deposit_with_split(
+    ctx: Context<DepositWithSplit>,
+    amount: u64,
+    immediate_bps: u16,
+) -> Result<()> {
+    require!(
+        immediate_bps <= BPS_DENOMINATOR,
+        CustomErrorCode::InvalidSplitBps
+    );
+
+    let (fee, net_amount, mint_amount) = prepare_deposit(&mut ctx.accounts.config, amount)?;
+    let position = &mut ctx.accounts.user_position;
+    position.user = ctx.accounts.signer.key();
+    position.bump = ctx.bumps.user_position;
+    position.record_deposit(amount, ctx.accounts.config.per_user_deposit_cap)?;
+    position.record_deposit_time(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.config.deposit_cooldown_seconds,
+    )?;
+    require!(
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+    require!(
+        !ctx.accounts.config.allowlist_enabled || ctx.accounts.allowlist_entry.is_some(),
+        CustomErrorCode::AddressNotAllowlisted
+    );
+    require_kyc_approved(&ctx.accounts.config, &ctx.accounts.user_mint_token_account)?;
+    let immediate_amount = safe_mul_div(
+        mint_amount,
+        immediate_bps as u64,
+        BPS_DENOMINATOR as u64,
+        Rounding::Down,
+    )?;
+    let deferred_amount = mint_amount - immediate_amount;
+
+    enter_guarded(&mut ctx.accounts.config)?;
+
+    if fee > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_vault_token_account.to_account_info(),
+            mint: ctx.accounts.vault_mint.to_account_info(),
+            to: ctx.accounts.fee_vault_token_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.vault_token_program.to_account_info(),
+                cpi_accounts,
+            ),
+            fee,
+            ctx.accounts.config.vault_decimals,
+        )?;
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_vault_token_account.to_account_info(),
+        mint: ctx.accounts.vault_mint.to_account_info(),
+        to: ctx.accounts.vault_token_account.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.vault_token_program.to_account_info(),
+            cpi_accounts,
+        ),
+        net_amount,
+        ctx.accounts.config.vault_decimals,
+    )?;
+
+    if immediate_amount > 0 {
+        let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+        let signer = &[seeds];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.user_mint_token_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            immediate_amount,
+        )?;
+    }
 
-        if step.is_left {
-            // sibling is left, so hash(sib || node)
-            node = hashv(&[sib, &node]).to_bytes();
-            msg!("[{}] left: hash(sib,node) = {}", i, hex::encode(node));
+    if deferred_amount > 0 {
+        let user = ctx.accounts.signer.key();
+        let record_info = ctx.accounts.vesting_record.to_account_info();
+        let mut record = if record_info.owner == &crate::ID {
+            VestingRecord::try_deserialize(&mut &record_info.try_borrow_data()?[..])?
         } else {
-            // sibling is right, so hash(node || sib)
-            node = hashv(&[&node, sib]).to_bytes();
-            msg!("[{}] right: hash(node,sib) = {}", i, hex::encode(node));
+            create_program_account(
+                &record_info,
+                &ctx.accounts.signer.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &[b"vesting", user.as_ref(), &[ctx.bumps.vesting_record]],
+                VestingRecord::LEN,
+            )?;
+            VestingRecord {
+                user,
+                amount: 0,
+                unlock_ts: 0,
+                bump: ctx.bumps.vesting_record,
+            }
+        };
+        // A further split deposit adds to the pending amount and keeps the unlock time of the
+        // deposit that started it; the cliff only starts over once the record has been claimed
+        if record.amount == 0 {
+            record.unlock_ts = Clock::get()?
+                .unix_timestamp
+                .saturating_add(ctx.accounts.config.vesting_cliff_seconds);
         }
+        record.amount = safe_add(record.amount, deferred_amount)?;
+        record.try_serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+
+        emit!(VestingDeferred {
+            user,
+            amount: record.amount,
+            unlock_ts: record.unlock_ts,
+        });
     }
 
-    msg!("Computed root: {}", hex::encode(node));
-    msg!(
-        "Expected root: {}",
-        hex::encode(ctx.accounts.epoch.merkle_root)
+    ctx.accounts.config.in_progress = false;
+
+    emit!(DepositEvent {
+        user: ctx.accounts.signer.key(),
+        amount,
+        mint: ctx.accounts.config.mint,
+        vault: ctx.accounts.config.vault,
+        memo: [0; 32],
+    });
+
+    Ok(())
+}
+
+// Mints the deferred remainder of split deposits once the cliff has passed and closes
+// the vesting record. The amount was already counted in total_minted at deposit time.
+pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+    require!(
+        !ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
     );
 
     require!(
-        node == ctx.accounts.epoch.merkle_root,
-        CustomErrorCode::InvalidMerkleProof
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+
+    let record = &ctx.accounts.vesting_record;
+    require!(record.amount > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        Clock::get()?.unix_timestamp >= record.unlock_ts,
+        CustomErrorCode::VestingCliffNotReached
     );
+    let amount = record.amount;
 
-    // mint tokens (wYLDS) to user
     let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
-    let signer = &[&seeds[..]];
+    let signer = &[seeds];
     let cpi_accounts = MintTo {
         mint: ctx.accounts.mint.to_account_info(),
         to: ctx.accounts.user_mint_token_account.to_account_info(),
         authority: ctx.accounts.mint_authority.to_account_info(),
     };
-    token::mint_to(
+    token_interface::mint_to(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             cpi_accounts,
@@ -471,15 +1124,3749 @@ pub fn claim_rewards(ctx: Context<ClaimRewards>, amount: u64, proof: Vec<ProofNo
         amount,
     )?;
 
-    msg!("Emitting RewardsClaimed");
-    emit!(RewardsClaimed {
-        user: ctx.accounts.user.key(),
-        epoch: ctx.accounts.epoch.index,
+    emit!(VestedClaimed {
+        user: ctx.accounts.signer.key(),
         amount,
         mint: ctx.accounts.mint.key(),
-        vault: ctx.accounts.config.vault,
     });
-    msg!("Emitted RewardsClaimed");
+    Ok(())
+}
+
+// Turn the deposit allowlist on or off (only program update authority can do this)
+pub fn set_allowlist_enabled(ctx: Context<SetAllowlistEnabled>, enabled: bool) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
+    let config = &mut ctx.accounts.config;
+    config.allowlist_enabled = enabled;
+
+    msg!("Deposit allowlist enabled: {}", enabled);
     Ok(())
 }
+
+// Allow an address to deposit while the allowlist is enabled (rewards administrators only)
+pub fn add_to_allowlist(ctx: Context<AddToAllowlist>) -> Result<()> {
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let entry = &mut ctx.accounts.allowlist_entry;
+    entry.user = ctx.accounts.user.key();
+    entry.bump = ctx.bumps.allowlist_entry;
+
+    msg!("Added {} to the deposit allowlist", entry.user);
+    Ok(())
+}
+
+// Remove an address from the deposit allowlist (rewards administrators only)
+pub fn remove_from_allowlist(ctx: Context<RemoveFromAllowlist>) -> Result<()> {
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    msg!(
+        "Removed {} from the deposit allowlist",
+        ctx.accounts.allowlist_entry.user
+    );
+    Ok(())
+}
+
+// The blocklist PDA is seeds-checked by the accounts struct; it only exists (owned by
+// this program) while the address is blocked
+fn is_blocked(blocked_entry: &AccountInfo) -> bool {
+    blocked_entry.owner == &crate::ID
+}
+
+// Spend `count` freezes from the administrator's FreezeScope, if the upgrade authority has
+// given them one (seeds-checked by the accounts struct)
+fn consume_freeze_quota(freeze_scope: &AccountInfo, count: u32) -> Result<()> {
+    if freeze_scope.owner != &crate::ID {
+        return Ok(());
+    }
+    let mut scope = FreezeScope::try_deserialize(&mut &freeze_scope.try_borrow_data()?[..])?;
+    scope.remaining = scope
+        .remaining
+        .checked_sub(count)
+        .ok_or(CustomErrorCode::FreezeQuotaExceeded)?;
+    scope.try_serialize(&mut &mut freeze_scope.try_borrow_mut_data()?[..])
+}
+
+// Block an address from depositing, redeeming and claiming (freeze administrators only)
+pub fn block_address(ctx: Context<BlockAddress>) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(
+        ctx.accounts.config.freeze_administrators.contains(&admin),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+
+    let entry = &mut ctx.accounts.blocked_entry;
+    entry.user = ctx.accounts.user.key();
+    entry.bump = ctx.bumps.blocked_entry;
+
+    msg!("Address {} blocked by administrator {}", entry.user, admin);
+    emit!(AddressBlocked {
+        user: entry.user,
+        admin,
+    });
+    Ok(())
+}
+
+// Lift a block by closing the entry (freeze administrators only)
+pub fn unblock_address(ctx: Context<UnblockAddress>) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(
+        ctx.accounts.config.freeze_administrators.contains(&admin),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+
+    let user = ctx.accounts.blocked_entry.user;
+    msg!("Address {} unblocked by administrator {}", user, admin);
+    emit!(AddressUnblocked { user, admin });
+    Ok(())
+}
+
+pub fn request_redeem(
+    ctx: Context<RequestRedeem>,
+    amount: u64,
+    min_usdc_out: u64,
+    destination: Option<Pubkey>,
+    memo: [u8; 32],
+) -> Result<()> {
+    require!(
+        !ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
+    );
+    require!(
+        !ctx.accounts.config.redeems_paused,
+        CustomErrorCode::RedeemsPaused
+    );
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        amount >= ctx.accounts.config.min_redeem,
+        CustomErrorCode::RedeemBelowMinimum
+    );
+    require!(
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+
+    // A freshly created request has a default user; anything else is still pending
+    require!(
+        ctx.accounts.redemption_request.user == Pubkey::default(),
+        CustomErrorCode::RedemptionAlreadyPending
+    );
+
+    // Check user's mint token balance
+    let user_balance = ctx.accounts.user_mint_token_account.amount;
+    require!(user_balance >= amount, CustomErrorCode::InsufficientBalance);
+
+    let vault_balance = ctx.accounts.redeem_vault_authority.lamports();
+    require!(
+        vault_balance >= ctx.accounts.config.redeem_authority_min_lamports,
+        CustomErrorCode::InsufficientRedeemVaultFunds
+    );
+
+    // amount_to_redeem = min(user wYLDS balance, requested)
+    // We do this to prevent the program from over-burning when the
+    // redeem is completed. The complete redeem will also check the
+    // redeem amount against the user balance at the time of completion to
+    // prevent burn error.
+    let amount_to_redeem = std::cmp::min(user_balance, amount);
+    require!(amount_to_redeem > 0, CustomErrorCode::InvalidAmount);
+    // Reject requests that would round down to nothing at the current exchange rate
+    require!(
+        ctx.accounts.config.mint_to_vault_amount(amount_to_redeem)? > 0,
+        CustomErrorCode::InvalidAmount
+    );
+
+    msg!("RequestRedeem user account balance: {}", user_balance);
+    msg!("Requested amount to redeem: {}", amount);
+    msg!("Actual amount to redeem: {}", amount_to_redeem);
+
+    // Set burn authority to the redeem vault authority PDA so it can burn tokens later
+    token_interface::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::Approve {
+                to: ctx.accounts.user_mint_token_account.to_account_info(),
+                delegate: ctx.accounts.redeem_vault_authority.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        amount_to_redeem,
+    )?;
+
+    // Proceeds go to the user's own vault token ATA unless they name another account
+    let destination = destination.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &ctx.accounts.signer.key(),
+            &ctx.accounts.vault_mint.key(),
+            ctx.accounts.vault_mint.to_account_info().owner,
+        )
+    });
+
+    // Requests are completed in the order they were made
+    let queue_position = ctx.accounts.config.enqueue_redemption()?;
+
+    msg!("Emitting RedemptionRequested");
+    emit!(RedemptionRequested {
+        user: ctx.accounts.signer.key(),
+        amount: amount_to_redeem,
+        vault_mint: ctx.accounts.config.vault,
+        mint: ctx.accounts.config.mint,
+        destination,
+        memo,
+        queue_position,
+    });
+    msg!("Emitted RedemptionRequested");
+
+    msg!("recording redemption request");
+    // Record the request (creates a lock on the user)
+    let request = &mut ctx.accounts.redemption_request;
+    request.user = ctx.accounts.signer.key();
+    request.amount = amount_to_redeem;
+    request.mint = ctx.accounts.config.mint;
+    request.bump = ctx.bumps.redemption_request;
+    request.requested_ts = Clock::get()?.unix_timestamp;
+    request.vault_mint = ctx.accounts.config.vault;
+    request.min_usdc_out = min_usdc_out;
+    request.destination = destination;
+    request.memo = memo;
+    request.queue_position = queue_position;
+    request.version = REDEMPTION_REQUEST_VERSION;
+
+    msg!("done with request redeem");
+    Ok(())
+}
+
+pub fn complete_redeem(ctx: Context<CompleteRedeem>, fill_amount: Option<u64>) -> Result<()> {
+    require!(
+        !ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
+    );
+    require!(
+        !ctx.accounts.config.redeems_paused,
+        CustomErrorCode::RedeemsPaused
+    );
+
+    // Admin gate
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let (req, queued) = load_redemption_request(ctx.accounts)?;
+
+    // Redemptions are served first come, first served. A request from before the queue existed
+    // was made ahead of everything in it, so it is served as soon as it is completed.
+    let queue_position = req.queue_position;
+    if queued {
+        require_eq_logged!(
+            queue_position,
+            ctx.accounts.config.redeem_queue_head,
+            CustomErrorCode::RedeemOutOfOrder
+        );
+    }
+
+    let remaining = settle_redemption(ctx.accounts, &ctx.bumps, req, fill_amount)?;
+    // A partial fill keeps its place in the queue; a full one moves it along
+    if remaining == 0 {
+        ctx.accounts.config.dequeue_redemption(queue_position)?;
+    }
+    Ok(())
+}
+
+// Reads the request being completed, first bringing one written under an older layout up to
+// date the way migrate_redemption_request would, with the admin topping up its rent. A request
+// from before the timelock is dated so that the timelock has just elapsed. Also returns whether
+// the request already held a place in the FIFO queue.
+fn load_redemption_request(accounts: &mut CompleteRedeem) -> Result<(RedemptionRequest, bool)> {
+    let requested_ts = Clock::get()?
+        .unix_timestamp
+        .saturating_sub(accounts.config.redeem_delay_seconds);
+    let (req, old_len) = upgrade_redemption_request(
+        &accounts.redemption_request,
+        &mut accounts.config,
+        &accounts.vault_mint.to_account_info(),
+        &accounts.admin,
+        &accounts.system_program,
+        requested_ts,
+    )?;
+    require_keys_eq!(
+        req.user,
+        accounts.user.key(),
+        CustomErrorCode::RequestNotFound
+    );
+    let queued = !matches!(old_len, Some(len) if len < RedemptionRequest::QUEUE_POSITION_END);
+    Ok((req, queued))
+}
+
+// Grows a redemption request written under an older layout to RedemptionRequest::LEN, with
+// `payer` topping up its rent, and gives every field the old layout lacked the value an
+// up-to-date request would have had, dating it `requested_ts` if it had no request time. Returns
+// the request and, if it had to be brought up to date, the length it had before.
+fn upgrade_redemption_request<'info>(
+    request_info: &AccountInfo<'info>,
+    config: &mut Config,
+    vault_mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    requested_ts: i64,
+) -> Result<(RedemptionRequest, Option<usize>)> {
+    let old_len = request_info.data_len();
+    require!(
+        old_len <= RedemptionRequest::LEN,
+        CustomErrorCode::RedemptionRequestVersionMismatch
+    );
+    if old_len == RedemptionRequest::LEN {
+        let request =
+            RedemptionRequest::try_deserialize(&mut &request_info.try_borrow_data()?[..])?;
+        if request.version == REDEMPTION_REQUEST_VERSION {
+            return Ok((request, None));
+        }
+    } else {
+        // Top up rent for the larger account before growing it
+        let rent_due = Rent::get()?
+            .minimum_balance(RedemptionRequest::LEN)
+            .saturating_sub(request_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Transfer {
+                        from: payer.clone(),
+                        to: request_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        request_info.resize(RedemptionRequest::LEN)?;
+    }
+
+    let mut data = request_info.try_borrow_mut_data()?;
+    // Checks the discriminator; the fields added by the resize read as zero
+    let mut request = RedemptionRequest::try_deserialize(&mut &data[..])?;
+    require!(
+        request.version < REDEMPTION_REQUEST_VERSION,
+        CustomErrorCode::RedemptionRequestVersionMismatch
+    );
+
+    if old_len < RedemptionRequest::REQUESTED_TS_END {
+        request.requested_ts = requested_ts;
+    }
+    if old_len < RedemptionRequest::VAULT_MINT_END {
+        request.vault_mint = config.vault;
+    }
+    if old_len < RedemptionRequest::DESTINATION_END {
+        request.destination = get_associated_token_address_with_program_id(
+            &request.user,
+            &config.vault,
+            vault_mint.owner,
+        );
+    }
+    if old_len < RedemptionRequest::QUEUE_POSITION_END {
+        request.queue_position = config.enqueue_redemption()?;
+    }
+    // min_usdc_out and memo default to zero, which the resize already wrote
+    request.version = REDEMPTION_REQUEST_VERSION;
+    request.try_serialize(&mut &mut data[..])?;
+    Ok((request, Some(old_len)))
+}
+
+// Burns the requested wYLDS and pays the vault tokens out, shared by complete_redeem and
+// complete_redeem_priority. Closes the request once it is fully filled and returns the amount
+// still outstanding.
+fn settle_redemption<'info>(
+    accounts: &mut CompleteRedeem<'info>,
+    bumps: &CompleteRedeemBumps,
+    mut req: RedemptionRequest,
+    fill_amount: Option<u64>,
+) -> Result<u64> {
+    require_keys_eq!(req.mint, accounts.mint.key(), CustomErrorCode::InvalidMint);
+    require_keys_eq!(
+        req.destination,
+        accounts.user_vault_token_account.key(),
+        CustomErrorCode::InvalidRedeemDestination
+    );
+
+    // The vault asset must not have changed between request and completion
+    require_eq_logged!(
+        req.vault_mint,
+        accounts.config.vault,
+        CustomErrorCode::InvalidVaultMint
+    );
+
+    // Enforce the minimum waiting period between request and completion
+    require!(
+        Clock::get()?.unix_timestamp
+            >= req
+                .requested_ts
+                .saturating_add(accounts.config.redeem_delay_seconds),
+        CustomErrorCode::RedeemTimelockNotElapsed
+    );
+
+    // The request redeem function will set the redeem amount to the min
+    // of the requested amount and the user's mint balance at the request.
+    // This prevents program from burning more than their balance at the time.
+    // However, we also do the same here to prevent error in the situation where
+    // the user transfers mint out of their account before this complete request
+    // executes.
+    // A partial fill redeems exactly `fill_amount` and leaves the rest of the request open.
+    let user_mint_balance = accounts.user_mint_token_account.amount;
+    let amount_to_redeem = match fill_amount {
+        Some(fill) => {
+            require!(fill <= req.amount, CustomErrorCode::FillExceedsRequest);
+            require!(
+                fill <= user_mint_balance,
+                CustomErrorCode::InsufficientBalance
+            );
+            fill
+        }
+        None => std::cmp::min(user_mint_balance, req.amount),
+    };
+    require!(amount_to_redeem > 0, CustomErrorCode::InvalidAmount);
+    // A freeze between request and completion would otherwise fail inside the burn CPI
+    require!(
+        !accounts.user_mint_token_account.is_frozen(),
+        CustomErrorCode::UserTokenAccountFrozen
+    );
+
+    // A partial fill must pay at least its pro-rata share of the request's minimum
+    let min_out = match fill_amount {
+        Some(_) => safe_mul_div(
+            req.min_usdc_out,
+            amount_to_redeem,
+            req.amount,
+            Rounding::Down,
+        )?,
+        None => req.min_usdc_out,
+    };
+
+    let vault_amount = redeem_vault_amount(
+        &accounts.config,
+        amount_to_redeem,
+        accounts.price_oracle.as_ref(),
+    )?;
+    require!(vault_amount > 0, CustomErrorCode::InvalidAmount);
+
+    // check vault has enough USDC
+    require!(
+        accounts.redeem_vault_token_account.amount >= vault_amount,
+        CustomErrorCode::InsufficientVaultBalance
+    );
+    // Leaving exactly the reserve behind is allowed
+    require!(
+        accounts.redeem_vault_token_account.amount - vault_amount
+            >= accounts.config.min_vault_reserve,
+        CustomErrorCode::VaultReserveBreached
+    );
+
+    // signer seeds for the PDA
+    let seeds: &[&[u8]] = &[b"redeem_vault_authority", &[bumps.redeem_vault_authority]];
+    let signer = &[seeds];
+
+    enter_guarded(&mut accounts.config)?;
+
+    // Burn user's wYLDS using PDA as delegate
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            token_interface::Burn {
+                mint: accounts.mint.to_account_info(),
+                from: accounts.user_mint_token_account.to_account_info(),
+                authority: accounts.redeem_vault_authority.to_account_info(),
+            },
+            signer,
+        ),
+        amount_to_redeem,
+    )?;
+
+    accounts.config.record_burn(amount_to_redeem);
+
+    // Redeemed vault tokens free up room under the per-user deposit cap
+    if accounts.user_position.owner == &crate::ID {
+        let mut data = accounts.user_position.try_borrow_mut_data()?;
+        let mut position = UserPosition::try_deserialize(&mut &data[..])?;
+        position.record_redemption(vault_amount);
+        position.try_serialize(&mut &mut data[..])?;
+    }
+
+    // The full wYLDS amount is burned above; the fee share of the USDC stays with the protocol
+    let fee = Config::fee_for(vault_amount, accounts.config.redeem_fee_bps)?;
+    let net_amount = vault_amount - fee;
+    require!(net_amount >= min_out, CustomErrorCode::SlippageExceeded);
+
+    if fee > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.vault_token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.redeem_vault_token_account.to_account_info(),
+                    mint: accounts.vault_mint.to_account_info(),
+                    to: accounts.fee_vault_token_account.to_account_info(),
+                    authority: accounts.redeem_vault_authority.to_account_info(),
+                },
+                signer,
+            ),
+            fee,
+            accounts.config.vault_decimals,
+        )?;
+    }
+
+    // Transfer USDC from redeem vault to user (PDA is authority)
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.vault_token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.redeem_vault_token_account.to_account_info(),
+                mint: accounts.vault_mint.to_account_info(),
+                to: accounts.user_vault_token_account.to_account_info(),
+                authority: accounts.redeem_vault_authority.to_account_info(),
+            },
+            signer,
+        ),
+        net_amount,
+        accounts.config.vault_decimals,
+    )?;
+
+    accounts.config.in_progress = false;
+
+    // The request stays open until fully filled; a full completion always closes it
+    let remaining = match fill_amount {
+        Some(_) => req.amount - amount_to_redeem,
+        None => 0,
+    };
+
+    msg!("Emitting RedeemCompleted");
+    emit!(RedeemCompleted {
+        user: accounts.user.key(),
+        admin: accounts.admin.key(),
+        amount: amount_to_redeem,
+        fee,
+        mint: accounts.mint.key(),
+        vault: accounts.redeem_vault_token_account.mint,
+        remaining,
+    });
+    msg!("Emitted RedeemCompleted");
+
+    let request_info = accounts.redemption_request.to_account_info();
+    if remaining == 0 {
+        // Refund the request account rent to the original user
+        let user_info = accounts.user.to_account_info();
+        let rent = request_info.lamports();
+        **user_info.try_borrow_mut_lamports()? = safe_add(user_info.lamports(), rent)?;
+        **request_info.try_borrow_mut_lamports()? = 0;
+        request_info.assign(&system_program::ID);
+        request_info.resize(0)?;
+
+        accounts.user_mint_token_account.reload()?;
+        revoke_leftover_redeem_allowance(
+            &accounts.token_program,
+            &accounts.user_mint_token_account,
+            &accounts.redeem_vault_authority,
+            signer,
+        )?;
+    } else {
+        req.amount = remaining;
+        req.min_usdc_out = req.min_usdc_out.saturating_sub(min_out);
+        req.try_serialize(&mut &mut request_info.try_borrow_mut_data()?[..])?;
+    }
+    Ok(remaining)
+}
+
+// Cancel a pending redemption request (only the requesting user can do this)
+pub fn cancel_redeem(ctx: Context<CancelRedeem>) -> Result<()> {
+    // Remove the redeem vault authority's delegate approval from the user's wYLDS account. A
+    // frozen account cannot be revoked on; its approval is inert once the request is gone.
+    if !ctx.accounts.user_mint_token_account.is_frozen() {
+        token_interface::revoke(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::Revoke {
+                source: ctx.accounts.user_mint_token_account.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ))?;
+    }
+
+    ctx.accounts
+        .config
+        .dequeue_redemption(ctx.accounts.redemption_request.queue_position)?;
+
+    msg!("Emitting RedemptionCancelled");
+    emit!(RedemptionCancelled {
+        user: ctx.accounts.signer.key(),
+        amount: ctx.accounts.redemption_request.amount,
+    });
+    msg!("Emitted RedemptionCancelled");
+
+    // Anchor will auto-close redemption_request to `signer` per the accounts attr
+    Ok(())
+}
+
+// Expire a stale redemption request (only rewards administrators can do this)
+pub fn expire_redeem(ctx: Context<ExpireRedeem>) -> Result<()> {
+    // Admin gate
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let config = &mut ctx.accounts.config;
+    let req = &ctx.accounts.redemption_request;
+    require!(
+        config.redeem_expiry_seconds > 0
+            && Clock::get()?.unix_timestamp
+                >= req
+                    .requested_ts
+                    .saturating_add(config.redeem_expiry_seconds),
+        CustomErrorCode::RedemptionNotExpired
+    );
+    config.dequeue_redemption(req.queue_position)?;
+
+    // Frozen accounts keep the approval, so a frozen user's request can still be expired
+    let seeds: &[&[u8]] = &[
+        b"redeem_vault_authority",
+        &[ctx.bumps.redeem_vault_authority],
+    ];
+    revoke_leftover_redeem_allowance(
+        &ctx.accounts.token_program,
+        &ctx.accounts.user_mint_token_account,
+        &ctx.accounts.redeem_vault_authority,
+        &[seeds],
+    )?;
+
+    msg!("Emitting RedemptionExpired");
+    emit!(RedemptionExpired {
+        user: ctx.accounts.user.key(),
+        admin: ctx.accounts.admin.key(),
+        amount: req.amount,
+    });
+    msg!("Emitted RedemptionExpired");
+
+    // Anchor will auto-close redemption_request to `user` per the accounts attr
+    Ok(())
+}
+
+// Set the mint token's freeze authority to the program PDA
+// Update the list of freeze administrators (only program update authority can do this)
+pub fn update_freeze_administrators(
+    ctx: Context<UpdateFreezeAdministrators>,
+    new_administrators: Vec<Pubkey>,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+
+    validate_administrators(&new_administrators, config.max_administrators)?;
+
+    config.freeze_administrators = new_administrators;
+
+    msg!(
+        "Freeze administrators updated. New count: {}",
+        config.freeze_administrators.len()
+    );
+    Ok(())
+}
+
+// Set the mint token's rewards authority to the program PDA
+// Update the list of rewards administrators (only program update authority can do this)
+pub fn update_rewards_administrators(
+    ctx: Context<UpdateRewardsAdministrators>,
+    new_administrators: Vec<Pubkey>,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+
+    validate_administrators(&new_administrators, config.max_administrators)?;
+
+    config.start_rewards_admin_rotation()?;
+    config.rewards_administrators = new_administrators;
+
+    msg!(
+        "Rewards administrators updated. New count: {}",
+        config.rewards_administrators.len()
+    );
+
+    emit!(RewardsAdministratorsUpdated {
+        count: config.rewards_administrators.len() as u8,
+        administrators: config.rewards_administrators.clone(),
+    });
+    Ok(())
+}
+
+// Add a single freeze administrator (only program update authority can do this)
+pub fn add_freeze_administrator(
+    ctx: Context<UpdateFreezeAdministrators>,
+    administrator: Pubkey,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    let max_administrators = config.max_administrators;
+    add_administrator(
+        &mut config.freeze_administrators,
+        administrator,
+        max_administrators,
+    )?;
+
+    msg!(
+        "Freeze administrator {} added. New count: {}",
+        administrator,
+        config.freeze_administrators.len()
+    );
+    Ok(())
+}
+
+// Remove a single freeze administrator (only program update authority can do this)
+pub fn remove_freeze_administrator(
+    ctx: Context<UpdateFreezeAdministrators>,
+    administrator: Pubkey,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    remove_administrator(&mut config.freeze_administrators, administrator)?;
+
+    msg!(
+        "Freeze administrator {} removed. New count: {}",
+        administrator,
+        config.freeze_administrators.len()
+    );
+    Ok(())
+}
+
+// Add a single rewards administrator (only program update authority can do this)
+pub fn add_rewards_administrator(
+    ctx: Context<UpdateRewardsAdministrators>,
+    administrator: Pubkey,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    let max_administrators = config.max_administrators;
+    add_administrator(
+        &mut config.rewards_administrators,
+        administrator,
+        max_administrators,
+    )?;
+
+    msg!(
+        "Rewards administrator {} added. New count: {}",
+        administrator,
+        config.rewards_administrators.len()
+    );
+
+    emit!(RewardsAdministratorsUpdated {
+        count: config.rewards_administrators.len() as u8,
+        administrators: config.rewards_administrators.clone(),
+    });
+    Ok(())
+}
+
+// Remove a single rewards administrator (only program update authority can do this)
+pub fn remove_rewards_administrator(
+    ctx: Context<UpdateRewardsAdministrators>,
+    administrator: Pubkey,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    config.start_rewards_admin_rotation()?;
+    remove_administrator(&mut config.rewards_administrators, administrator)?;
+
+    msg!(
+        "Rewards administrator {} removed. New count: {}",
+        administrator,
+        config.rewards_administrators.len()
+    );
+
+    emit!(RewardsAdministratorsUpdated {
+        count: config.rewards_administrators.len() as u8,
+        administrators: config.rewards_administrators.clone(),
+    });
+    Ok(())
+}
+
+fn add_administrator(
+    administrators: &mut Vec<Pubkey>,
+    administrator: Pubkey,
+    max_administrators: u8,
+) -> Result<()> {
+    require!(
+        administrator != Pubkey::default(),
+        CustomErrorCode::InvalidAdministrator
+    );
+    require!(
+        !administrators.contains(&administrator),
+        CustomErrorCode::DuplicateAdministrator
+    );
+    require!(
+        administrators.len() < max_administrators as usize,
+        CustomErrorCode::TooManyAdministrators
+    );
+    administrators.push(administrator);
+    Ok(())
+}
+
+fn remove_administrator(administrators: &mut Vec<Pubkey>, administrator: Pubkey) -> Result<()> {
+    let position = administrators
+        .iter()
+        .position(|a| *a == administrator)
+        .ok_or(CustomErrorCode::AdministratorNotFound)?;
+    administrators.remove(position);
+    Ok(())
+}
+
+// Administrator lists hold at most `max_administrators` unique, non-default keys
+fn validate_administrators(administrators: &[Pubkey], max_administrators: u8) -> Result<()> {
+    require!(
+        administrators.len() <= max_administrators as usize,
+        CustomErrorCode::TooManyAdministrators
+    );
+    for (i, administrator) in administrators.iter().enumerate() {
+        require!(
+            *administrator != Pubkey::default(),
+            CustomErrorCode::InvalidAdministrator
+        );
+        require!(
+            !administrators[..i].contains(administrator),
+            CustomErrorCode::DuplicateAdministrator
+        );
+    }
+    Ok(())
+}
+
+// Freeze a specific token account (only freeze administrators can do this)
+pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+
+    // Verify signer is a freeze administrator
+    require!(
+        config.freeze_administrators.contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+    consume_freeze_quota(&ctx.accounts.freeze_scope, 1)?;
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+
+    let cpi_accounts = token_interface::FreezeAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        freeze_authority_seeds,
+    );
+
+    token_interface::freeze_account(cpi_ctx)?;
+
+    msg!(
+        "Token account {} frozen by administrator {}",
+        ctx.accounts.token_account.key(),
+        signer
+    );
+
+    emit!(TokenAccountFrozen {
+        token_account: ctx.accounts.token_account.key(),
+        admin: signer,
+        mint: ctx.accounts.mint.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Thaw a specific token account (only freeze administrators can do this)
+pub fn thaw_token_account(ctx: Context<ThawTokenAccount>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+
+    // Verify signer is a freeze administrator
+    require!(
+        config.freeze_administrators.contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+
+    let cpi_accounts = token_interface::ThawAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        freeze_authority_seeds,
+    );
+
+    token_interface::thaw_account(cpi_ctx)?;
+
+    msg!(
+        "Token account {} thawed by administrator {}",
+        ctx.accounts.token_account.key(),
+        signer
+    );
+
+    emit!(TokenAccountThawed {
+        token_account: ctx.accounts.token_account.key(),
+        admin: signer,
+        mint: ctx.accounts.mint.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Freeze every token account passed in remaining_accounts (only freeze administrators can do this)
+pub fn freeze_token_accounts_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FreezeTokenAccountsBatch<'info>>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+
+    // Verify signer is a freeze administrator once for the whole batch
+    require!(
+        config.freeze_administrators.contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+    let requested = ctx.remaining_accounts.len();
+    require!(
+        requested > 0 && requested <= MAX_FREEZE_BATCH,
+        CustomErrorCode::InvalidFreezeBatch
+    );
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+
+    let mut frozen: u8 = 0;
+    for token_account_info in ctx.remaining_accounts.iter() {
+        let token_account: InterfaceAccount<'info, TokenAccount> =
+            InterfaceAccount::try_from(token_account_info)?;
+        require_keys_eq!(
+            token_account.mint,
+            ctx.accounts.mint.key(),
+            CustomErrorCode::InvalidMint
+        );
+        // Already frozen accounts are skipped so one stale entry doesn't sink the batch
+        if token_account.is_frozen() {
+            continue;
+        }
+
+        token_interface::freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::FreezeAccount {
+                account: token_account_info.clone(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+            },
+            freeze_authority_seeds,
+        ))?;
+        frozen += 1;
+    }
+    consume_freeze_quota(&ctx.accounts.freeze_scope, frozen as u32)?;
+
+    msg!(
+        "{} of {} token accounts frozen by administrator {}",
+        frozen,
+        requested,
+        signer
+    );
+
+    emit!(TokenAccountsBatchFrozen {
+        admin: signer,
+        mint: ctx.accounts.mint.key(),
+        requested: requested as u8,
+        frozen,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Freeze a token account and record the compliance reason (only freeze administrators can do this)
+pub fn freeze_token_account_with_reason(
+    ctx: Context<FreezeTokenAccountWithReason>,
+    reason_code: u16,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+
+    // Verify signer is a freeze administrator
+    require!(
+        config.freeze_administrators.contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+    consume_freeze_quota(&ctx.accounts.freeze_scope, 1)?;
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+
+    let cpi_accounts = token_interface::FreezeAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        freeze_authority_seeds,
+    );
+
+    token_interface::freeze_account(cpi_ctx)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let record = &mut ctx.accounts.freeze_record;
+    record.token_account = ctx.accounts.token_account.key();
+    record.admin = signer;
+    record.reason_code = reason_code;
+    record.frozen_ts = now;
+    record.bump = ctx.bumps.freeze_record;
+
+    msg!(
+        "Token account {} frozen by administrator {} (reason {})",
+        ctx.accounts.token_account.key(),
+        signer,
+        reason_code
+    );
+
+    emit!(TokenAccountFrozenWithReason {
+        token_account: ctx.accounts.token_account.key(),
+        admin: signer,
+        mint: ctx.accounts.mint.key(),
+        reason_code,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+// Thaw a token account frozen with a reason and close its record (only freeze administrators can do this)
+pub fn thaw_token_account_with_reason(
+    ctx: Context<ThawTokenAccountWithReason>,
+    reason_code: u16,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+
+    // Verify signer is a freeze administrator
+    require!(
+        config.freeze_administrators.contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+
+    let cpi_accounts = token_interface::ThawAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        freeze_authority_seeds,
+    );
+
+    token_interface::thaw_account(cpi_ctx)?;
+
+    msg!(
+        "Token account {} thawed by administrator {} (reason {})",
+        ctx.accounts.token_account.key(),
+        signer,
+        reason_code
+    );
+
+    // The event carries the original freeze reason since the record is closed
+    emit!(TokenAccountThawedWithReason {
+        token_account: ctx.accounts.token_account.key(),
+        admin: signer,
+        mint: ctx.accounts.mint.key(),
+        reason_code,
+        freeze_reason_code: ctx.accounts.freeze_record.reason_code,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn create_rewards_epoch(
+    ctx: Context<CreateRewardsEpoch>,
+    index: u64,
+    merkle_root: [u8; 32],
+    total: u64,
+    claim_deadline_ts: i64,
+    merkle_mode: MerkleMode,
+    tree_depth: u8,
+) -> Result<()> {
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+    require_eq_logged!(
+        index,
+        ctx.accounts.config.next_epoch_index,
+        CustomErrorCode::NonSequentialEpoch
+    );
+    // No claim could ever supply a proof for a deeper tree
+    require!(
+        tree_depth as usize <= MAX_PROOF_DEPTH,
+        CustomErrorCode::ProofTooDeep
+    );
+    let clock = Clock::get()?;
+    let e = &mut ctx.accounts.epoch;
+    e.index = index;
+    e.merkle_root = merkle_root;
+    e.total = total;
+    e.created_ts = clock.unix_timestamp;
+    e.created_slot = clock.slot;
+    e.claimed = 0;
+    // In slot mode the deadline argument counts slots after creation; slots cannot be skewed
+    // by validators the way timestamps can
+    if ctx.accounts.config.slot_based_epoch_deadlines && claim_deadline_ts != 0 {
+        require!(claim_deadline_ts > 0, CustomErrorCode::InvalidAmount);
+        e.claim_deadline_ts = 0;
+        e.claim_deadline_slot = clock
+            .slot
+            .checked_add(claim_deadline_ts as u64)
+            .ok_or(CustomErrorCode::ArithmeticOverflow)?;
+    } else {
+        e.claim_deadline_ts = claim_deadline_ts;
+        e.claim_deadline_slot = 0;
+    }
+    e.merkle_mode = merkle_mode;
+    e.tree_depth = tree_depth;
+    e.swept = false;
+    e.frozen = false;
+    e.version = REWARDS_EPOCH_VERSION;
+
+    let config = &mut ctx.accounts.config;
+    config.next_epoch_index = safe_add(index, 1)?;
+    config.epochs_created = config.epochs_created.max(config.next_epoch_index);
+    Ok(())
+}
+
+// Forfeits whatever is left of an epoch's budget once its claim deadline has passed.
+// Sweeping only closes the epoch to claims and moves no tokens. With rewards_from_vault set,
+// the forfeited remainder stays in the rewards vault for the operator to recover.
+pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let epoch = &mut ctx.accounts.epoch;
+    require!(!epoch.swept, CustomErrorCode::EpochSwept);
+    require!(
+        epoch.deadline_passed(&Clock::get()?),
+        CustomErrorCode::EpochClaimsOpen
+    );
+
+    let amount = epoch.total.saturating_sub(epoch.claimed);
+    epoch.swept = true;
+
+    msg!(
+        "Swept {} unclaimed rewards from epoch {}",
+        amount,
+        epoch.index
+    );
+    emit!(UnclaimedSwept {
+        epoch: epoch.index,
+        admin: ctx.accounts.admin.key(),
+        amount,
+    });
+    Ok(())
+}
+
+pub fn claim_rewards(ctx: Context<ClaimRewards>, amount: u64, proof: Vec<ProofNode>) -> Result<()> {
+    let to = ctx.accounts.user_mint_token_account.to_account_info();
+    pay_claim(ctx.accounts, &ctx.bumps, amount, &proof, to)?;
+    Ok(())
+}
+
+// Verifies one epoch claim, records it against the signer and pays `to`, shared by
+// claim_rewards, claim_rewards_to and claim_and_restake. Returns the amount paid after the
+// claim fee.
+fn pay_claim<'info>(
+    accounts: &mut ClaimRewards<'info>,
+    bumps: &ClaimRewardsBumps,
+    amount: u64,
+    proof: &[ProofNode],
+    to: AccountInfo<'info>,
+) -> Result<u64> {
+    require!(
+        !accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
+    );
+    require!(
+        !is_blocked(&accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+
+    require!(
+        !has_claimed(&accounts.claim_record),
+        CustomErrorCode::RewardsAlreadyClaimed
+    );
+
+    let user = accounts.user.key();
+    verify_epoch_claim(
+        &mut accounts.epoch,
+        accounts.config.epochs_created,
+        &user,
+        amount,
+        proof,
+    )?;
+
+    let epoch_key = accounts.epoch.key();
+    create_claim_record(
+        &accounts.claim_record,
+        &accounts.user.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        &[
+            b"claim",
+            epoch_key.as_ref(),
+            user.as_ref(),
+            &[bumps.claim_record],
+        ],
+        amount,
+    )?;
+    mark_claim_bitmap(
+        &accounts.claim_bitmap,
+        &accounts.user.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        user,
+        bumps.claim_bitmap,
+        accounts.epoch.index,
+    )?;
+
+    // The claim fee is withheld: never minted, or left in the rewards vault
+    let fee = Config::fee_for(amount, accounts.config.claim_fee_bps)?;
+    let payout = amount - fee;
+
+    if accounts.config.rewards_from_vault {
+        // pay out of the pre-funded rewards vault; supply does not change
+        let rewards_vault = accounts
+            .rewards_vault_token_account
+            .as_ref()
+            .ok_or(CustomErrorCode::MissingRewardsVault)?;
+        require!(
+            rewards_vault.amount >= payout,
+            CustomErrorCode::InsufficientVaultBalance
+        );
+
+        let seeds: &[&[u8]] = &[b"rewards_vault_authority", &[bumps.rewards_vault_authority]];
+        let signer = &[seeds];
+        let cpi_accounts = TransferChecked {
+            from: rewards_vault.to_account_info(),
+            mint: accounts.mint.to_account_info(),
+            to: to.clone(),
+            authority: accounts.rewards_vault_authority.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            payout,
+            accounts.mint.decimals,
+        )?;
+    } else {
+        accounts.config.record_mint(payout)?;
+
+        // mint tokens (wYLDS) to user
+        let seeds: &[&[u8]] = &[b"mint_authority", &[bumps.mint_authority]];
+        let signer = &[seeds];
+        let cpi_accounts = MintTo {
+            mint: accounts.mint.to_account_info(),
+            to: to.clone(),
+            authority: accounts.mint_authority.to_account_info(),
+        };
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            payout,
+        )?;
+    }
+
+    msg!("Emitting RewardsClaimed");
+    emit!(RewardsClaimed {
+        user: accounts.user.key(),
+        epoch: accounts.epoch.index,
+        amount,
+        mint: accounts.mint.key(),
+        vault: accounts.config.vault,
+        fee,
+        destination: to.key(),
+    });
+    msg!("Emitted RewardsClaimed");
+
+    Ok(payout)
+}
+
+// Checked before any hashing so oversized proofs are rejected cheaply. A depth of 0 is a
+// single-leaf tree: the proof is empty and the leaf itself must equal the root.
+fn check_proof_length(proof_len: usize, tree_depth: u8) -> Result<()> {
+    require!(proof_len <= MAX_PROOF_DEPTH, CustomErrorCode::ProofTooDeep);
+    require!(
+        proof_len == tree_depth as usize,
+        CustomErrorCode::InvalidProofLength
+    );
+    Ok(())
+}
+
+// Verifies one (user, amount, proof) claim against an epoch and adds it to the epoch's
+// claimed total. Shared by claim_rewards and claim_rewards_multi.
+fn verify_epoch_claim(
+    epoch: &mut RewardsEpoch,
+    epochs_created: u64,
+    user: &Pubkey,
+    amount: u64,
+    proof: &[ProofNode],
+) -> Result<()> {
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    require_eq_logged!(
+        epoch.version,
+        REWARDS_EPOCH_VERSION,
+        CustomErrorCode::RewardsEpochVersionMismatch
+    );
+    require!(
+        epoch.index < epochs_created,
+        CustomErrorCode::InvalidRewardsEpoch
+    );
+    require!(!epoch.swept, CustomErrorCode::EpochSwept);
+    require!(!epoch.frozen, CustomErrorCode::EpochFrozen);
+    check_proof_length(proof.len(), epoch.tree_depth)?;
+
+    // A claim landing exactly on the deadline is still accepted
+    require!(
+        !epoch.deadline_passed(&Clock::get()?),
+        CustomErrorCode::EpochClaimExpired
+    );
+
+    // leaf = sha256(0x00 || user || amount_le || epoch_index_le)
+    let mut leaf = [0u8; 1 + 32 + 8 + 8];
+    leaf[0] = MERKLE_LEAF_PREFIX;
+    leaf[1..33].copy_from_slice(user.as_ref());
+    leaf[33..41].copy_from_slice(&amount.to_le_bytes());
+    leaf[41..].copy_from_slice(&epoch.index.to_le_bytes());
+    let mut node = hash(&leaf).to_bytes();
+
+    proof_log!("User Leaf node: {}", hex::encode(node));
+
+    // One stack buffer, 0x01 || left || right, is reused for every step
+    let mut buf = [0u8; 1 + 32 + 32];
+    buf[0] = MERKLE_NODE_PREFIX;
+
+    // iterate through proof
+    for step in proof {
+        let sib = &step.sibling;
+
+        if sib.iter().all(|&b| b == 0) {
+            proof_log!("right: sibling is zero - hashing just the node");
+            buf[1..33].copy_from_slice(&node);
+            node = hash(&buf[..33]).to_bytes();
+            continue;
+        }
+
+        // sibling goes left either by byte order (SortedPairs, is_left ignored) or by flag
+        let sib_left = if epoch.merkle_mode == MerkleMode::SortedPairs {
+            *sib < node
+        } else {
+            step.is_left
+        };
+        if sib_left {
+            // hash(0x01 || sib || node)
+            buf[1..33].copy_from_slice(sib);
+            buf[33..].copy_from_slice(&node);
+        } else {
+            // hash(0x01 || node || sib)
+            buf[1..33].copy_from_slice(&node);
+            buf[33..].copy_from_slice(sib);
+        }
+        node = hash(&buf).to_bytes();
+        proof_log!("sib_left={}: {}", sib_left, hex::encode(node));
+    }
+
+    proof_log!("Computed root: {}", hex::encode(node));
+    proof_log!("Expected root: {}", hex::encode(epoch.merkle_root));
+
+    require!(
+        node == epoch.merkle_root,
+        CustomErrorCode::InvalidMerkleProof
+    );
+
+    // Hard on-chain ceiling even if the off-chain tree allocates more than `total`
+    let claimed = safe_add(epoch.claimed, amount)?;
+    require!(claimed <= epoch.total, CustomErrorCode::EpochCapExceeded);
+    epoch.claimed = claimed;
+
+    Ok(())
+}
+
+// A claim record PDA owned by this program means the epoch was already claimed
+fn has_claimed(claim_record: &AccountInfo) -> bool {
+    claim_record.owner == &crate::ID
+}
+
+// Sets the bit for `index` in the user's ClaimBitmap PDA, creating the bitmap on first use and
+// growing it (payer tops up the rent) once epochs outrun its length.
+fn mark_claim_bitmap<'info>(
+    claim_bitmap: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    user: Pubkey,
+    bump: u8,
+    index: u64,
+) -> Result<()> {
+    let needed = ClaimBitmap::bytes_for(index);
+    let mut bitmap = if claim_bitmap.owner == &crate::ID {
+        ClaimBitmap::try_deserialize(&mut &claim_bitmap.try_borrow_data()?[..])?
+    } else {
+        create_program_account(
+            claim_bitmap,
+            payer,
+            system_program,
+            &[b"claim_bitmap", user.as_ref(), &[bump]],
+            ClaimBitmap::space(needed),
+        )?;
+        ClaimBitmap {
+            user,
+            bump,
+            bits: vec![0; needed],
+        }
+    };
+
+    if bitmap.bits.len() < needed {
+        let space = ClaimBitmap::space(needed);
+        let rent = Rent::get()?.minimum_balance(space);
+        let current = claim_bitmap.lamports();
+        if current < rent {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Transfer {
+                        from: payer.clone(),
+                        to: claim_bitmap.clone(),
+                    },
+                ),
+                rent - current,
+            )?;
+        }
+        claim_bitmap.resize(space)?;
+        bitmap.bits.resize(needed, 0);
+    }
+
+    bitmap.set_claimed(index);
+    bitmap.try_serialize(&mut &mut claim_bitmap.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+// Creates a program-owned PDA of `space` bytes paid by `payer`. Handles a PDA that was
+// pre-funded with lamports.
+fn create_program_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+    if current == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        )?;
+    } else {
+        // Someone pre-funded the PDA; create_account would fail, so top up and
+        // allocate/assign instead
+        if current < rent {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Transfer {
+                        from: payer.clone(),
+                        to: account.clone(),
+                    },
+                ),
+                rent - current,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Allocate {
+                    account_to_allocate: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Assign {
+                    account_to_assign: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            &crate::ID,
+        )?;
+    }
+    Ok(())
+}
+
+// Creates the ClaimRecord marker PDA by hand so an existing record surfaces as
+// RewardsAlreadyClaimed.
+fn create_claim_record<'info>(
+    claim_record: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    require!(
+        !has_claimed(claim_record),
+        CustomErrorCode::RewardsAlreadyClaimed
+    );
+    create_program_account(
+        claim_record,
+        payer,
+        system_program,
+        signer_seeds,
+        ClaimRecord::LEN,
+    )?;
+
+    let record = ClaimRecord {
+        amount,
+        claimed_ts: Clock::get()?.unix_timestamp,
+    };
+    record.try_serialize(&mut &mut claim_record.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+pub fn claim_rewards_multi<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimRewardsMulti<'info>>,
+    claims: Vec<EpochClaim>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
+    );
+    require!(!claims.is_empty(), CustomErrorCode::InvalidAmount);
+    require!(
+        claims.len() <= MAX_EPOCH_CLAIMS,
+        CustomErrorCode::TooManyEpochClaims
+    );
+    // remaining_accounts = [epoch_0, claim_record_0, epoch_1, claim_record_1, ...]
+    require!(
+        ctx.remaining_accounts.len() == claims.len() * 2,
+        CustomErrorCode::InvalidClaimAccounts
+    );
+    require!(
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+
+    let user = ctx.accounts.user.key();
+    let mut total: u64 = 0;
+
+    for (claim, accounts) in claims.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let epoch_info = &accounts[0];
+        let claim_record_info = &accounts[1];
+
+        let (epoch_pda, _) = Pubkey::find_program_address(
+            &[b"epoch", claim.epoch_index.to_le_bytes().as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            epoch_info.key(),
+            epoch_pda,
+            CustomErrorCode::InvalidClaimAccounts
+        );
+        let mut epoch: Account<'info, RewardsEpoch> = Account::try_from(epoch_info)?;
+
+        let (claim_record_pda, claim_record_bump) = Pubkey::find_program_address(
+            &[b"claim", epoch_pda.as_ref(), user.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            claim_record_info.key(),
+            claim_record_pda,
+            CustomErrorCode::InvalidClaimAccounts
+        );
+
+        verify_epoch_claim(
+            &mut epoch,
+            ctx.accounts.config.epochs_created,
+            &user,
+            claim.amount,
+            &claim.proof,
+        )?;
+
+        // A repeated epoch in the same batch fails here, since the first pass already
+        // assigned the record to this program
+        create_claim_record(
+            claim_record_info,
+            &ctx.accounts.user.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &[
+                b"claim",
+                epoch_pda.as_ref(),
+                user.as_ref(),
+                &[claim_record_bump],
+            ],
+            claim.amount,
+        )?;
+        mark_claim_bitmap(
+            &ctx.accounts.claim_bitmap,
+            &ctx.accounts.user.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            user,
+            ctx.bumps.claim_bitmap,
+            claim.epoch_index,
+        )?;
+        epoch.exit(&crate::ID)?;
+
+        // Same claim fee as claim_rewards, withheld per epoch
+        let fee = Config::fee_for(claim.amount, ctx.accounts.config.claim_fee_bps)?;
+        total = safe_add(total, claim.amount - fee)?;
+
+        emit!(RewardsClaimed {
+            user,
+            epoch: claim.epoch_index,
+            amount: claim.amount,
+            mint: ctx.accounts.mint.key(),
+            vault: ctx.accounts.config.vault,
+            fee,
+            destination: ctx.accounts.user_mint_token_account.key(),
+        });
+    }
+
+    // one payout for the whole batch
+    if ctx.accounts.config.rewards_from_vault {
+        let rewards_vault = ctx
+            .accounts
+            .rewards_vault_token_account
+            .as_ref()
+            .ok_or(CustomErrorCode::MissingRewardsVault)?;
+        require!(
+            rewards_vault.amount >= total,
+            CustomErrorCode::InsufficientVaultBalance
+        );
+
+        let seeds: &[&[u8]] = &[
+            b"rewards_vault_authority",
+            &[ctx.bumps.rewards_vault_authority],
+        ];
+        let signer = &[seeds];
+        let cpi_accounts = TransferChecked {
+            from: rewards_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.user_mint_token_account.to_account_info(),
+            authority: ctx.accounts.rewards_vault_authority.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            total,
+            ctx.accounts.mint.decimals,
+        )?;
+    } else {
+        ctx.accounts.config.record_mint(total)?;
+
+        let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+        let signer = &[seeds];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.user_mint_token_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            total,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Set the program allowed to mint through external_program_mint (only program update authority can do this)
+pub fn set_allow_mint_program_caller(
+    ctx: Context<SetAllowMintProgramCaller>,
+    caller: Pubkey,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    config.allow_mint_program_caller = caller;
+
+    msg!("Allowed external mint program caller set to: {}", caller);
+    Ok(())
+}
+
+pub fn external_program_mint(ctx: Context<ExternalProgramMint>, amount: u64) -> Result<()> {
+    require!(
+        !ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
+    );
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+
+    let config = &mut ctx.accounts.config;
+    require!(
+        config.allow_mint_program_caller != Pubkey::default(),
+        CustomErrorCode::InvalidMintProgramCaller
+    );
+    // Any account can be passed as the caller, so check who actually invoked us: at one CPI
+    // level deep, the top-level instruction being executed belongs to the calling program.
+    let current = get_instruction_relative(0, &ctx.accounts.instructions)?;
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT + 1
+            && current.program_id == config.allow_mint_program_caller,
+        CustomErrorCode::UnauthorizedMintCaller
+    );
+    let quota = &mut ctx.accounts.mint_caller_quota;
+    quota.record_mint(amount, Clock::get()?.unix_timestamp)?;
+    config.record_mint(amount)?;
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let signer = &[seeds];
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        amount,
+    )?;
+
+    msg!("Emitting ExternalProgramMintEvent");
+    emit!(ExternalProgramMintEvent {
+        caller: ctx.accounts.external_mint_program_caller.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        mint: ctx.accounts.mint.key(),
+        minted_in_window: ctx.accounts.mint_caller_quota.minted_in_window,
+        max_per_window: ctx.accounts.mint_caller_quota.max_per_window,
+    });
+    msg!("Emitted ExternalProgramMintEvent");
+
+    Ok(())
+}
+
+// Emits the user's pending redemption as a RedemptionStatus event. Intended to be
+// simulated by front-ends; fails with RequestNotFound when there is no request.
+pub fn get_redemption_status(ctx: Context<GetRedemptionStatus>) -> Result<()> {
+    let info = ctx.accounts.redemption_request.to_account_info();
+    require!(
+        info.owner == &crate::ID && !info.data_is_empty(),
+        CustomErrorCode::RequestNotFound
+    );
+    let req = RedemptionRequest::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+
+    let timelock_elapsed = Clock::get()?.unix_timestamp
+        >= req
+            .requested_ts
+            .saturating_add(ctx.accounts.config.redeem_delay_seconds);
+
+    emit!(RedemptionStatus {
+        user: req.user,
+        amount: req.amount,
+        requested_ts: req.requested_ts,
+        timelock_elapsed,
+    });
+    Ok(())
+}
+
+// Recover vault tokens from the redeem vault during a wind-down. Only the program
+// update authority can do this, and only while the program is paused.
+pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    require!(
+        ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramNotPaused
+    );
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.redeem_vault_token_account.amount >= amount,
+        CustomErrorCode::InsufficientVaultBalance
+    );
+
+    let seeds: &[&[u8]] = &[
+        b"redeem_vault_authority",
+        &[ctx.bumps.redeem_vault_authority],
+    ];
+    let signer = &[seeds];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.vault_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.redeem_vault_token_account.to_account_info(),
+                mint: ctx.accounts.vault_mint.to_account_info(),
+                to: ctx.accounts.destination_token_account.to_account_info(),
+                authority: ctx.accounts.redeem_vault_authority.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.config.vault_decimals,
+    )?;
+
+    msg!(
+        "Emergency withdrew {} to {}",
+        amount,
+        ctx.accounts.destination_token_account.key()
+    );
+    emit!(EmergencyWithdrawEvent {
+        admin: ctx.accounts.signer.key(),
+        destination: ctx.accounts.destination_token_account.key(),
+        amount,
+    });
+    Ok(())
+}
+
+// Hand the mint's mint authority from the program PDA to another key, e.g. the PDA of a
+// redeployed program. Only the program update authority can do this. Deposits and minted
+// rewards stop working here once it has run.
+pub fn transfer_mint_authority(
+    ctx: Context<TransferMintAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    require!(
+        new_authority != Pubkey::default(),
+        CustomErrorCode::InvalidNewAuthority
+    );
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let signer = &[seeds];
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::SetAuthority {
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+                current_authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            signer,
+        ),
+        AuthorityType::MintTokens,
+        Some(new_authority),
+    )?;
+
+    msg!(
+        "Mint authority for {} transferred to {}",
+        ctx.accounts.mint.key(),
+        new_authority
+    );
+    emit!(MintAuthorityTransferred {
+        mint: ctx.accounts.mint.key(),
+        old_authority: ctx.accounts.mint_authority.key(),
+        new_authority,
+        signer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Close expired redemption requests in bulk, refunding each request's rent to its user
+// (only rewards administrators can do this)
+pub fn reclaim_abandoned_redemptions<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReclaimAbandonedRedemptions<'info>>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = ctx.accounts.admin.key();
+
+    // Admin gate
+    require!(
+        config.is_rewards_administrator(&admin)?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+    let requested = ctx.remaining_accounts.len() / 3;
+    require!(
+        ctx.remaining_accounts.len().is_multiple_of(3)
+            && requested > 0
+            && requested <= MAX_RECLAIM_BATCH,
+        CustomErrorCode::InvalidReclaimBatch
+    );
+
+    let seeds: &[&[u8]] = &[
+        b"redeem_vault_authority",
+        &[ctx.bumps.redeem_vault_authority],
+    ];
+    let signer = &[seeds];
+    let now = Clock::get()?.unix_timestamp;
+    let mut reclaimed: u8 = 0;
+    for group in ctx.remaining_accounts.chunks_exact(3) {
+        let (request_info, user_info, user_mint_info) = (&group[0], &group[1], &group[2]);
+        require!(
+            request_info.owner == &crate::ID,
+            CustomErrorCode::RequestNotFound
+        );
+        let req = RedemptionRequest::try_deserialize(&mut &request_info.try_borrow_data()?[..])?;
+        require_keys_eq!(user_info.key(), req.user, CustomErrorCode::RequestNotFound);
+
+        // Requests that have not expired yet, or sit too far behind the queue head to close, are
+        // skipped so one such entry doesn't sink the batch
+        let expired = config.redeem_expiry_seconds > 0
+            && now
+                >= req
+                    .requested_ts
+                    .saturating_add(config.redeem_expiry_seconds);
+        if !expired || !config.can_dequeue_redemption(req.queue_position) {
+            continue;
+        }
+
+        // Passing requests in queue order lets the head move past all of them
+        config.dequeue_redemption(req.queue_position)?;
+
+        let user_mint_token_account: InterfaceAccount<'info, TokenAccount> =
+            InterfaceAccount::try_from(user_mint_info)?;
+        require_keys_eq!(
+            user_mint_token_account.mint,
+            config.mint,
+            CustomErrorCode::InvalidMint
+        );
+        require_keys_eq!(
+            user_mint_token_account.owner,
+            req.user,
+            CustomErrorCode::InvalidTokenOwner
+        );
+        revoke_leftover_redeem_allowance(
+            &ctx.accounts.token_program,
+            &user_mint_token_account,
+            &ctx.accounts.redeem_vault_authority,
+            signer,
+        )?;
+
+        let rent = request_info.lamports();
+        **user_info.try_borrow_mut_lamports()? = safe_add(user_info.lamports(), rent)?;
+        **request_info.try_borrow_mut_lamports()? = 0;
+        request_info.assign(&system_program::ID);
+        request_info.resize(0)?;
+
+        emit!(RedemptionExpired {
+            user: req.user,
+            admin,
+            amount: req.amount,
+        });
+        reclaimed += 1;
+    }
+
+    msg!(
+        "{} of {} redemption requests reclaimed by administrator {}",
+        reclaimed,
+        requested,
+        admin
+    );
+
+    emit!(RedemptionsReclaimed {
+        admin,
+        requested: requested as u8,
+        reclaimed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+// Point redemptions at a new redeem vault token account (only program update authority can
+// do this). The account must hold the vault mint and be owned by the redeem vault authority PDA.
+pub fn set_redeem_vault(ctx: Context<SetRedeemVault>) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    let old_redeem_vault = config.redeem_vault;
+    config.redeem_vault = ctx.accounts.redeem_vault_token_account.key();
+
+    msg!("Redeem vault set to {}", config.redeem_vault);
+    emit!(RedeemVaultUpdated {
+        old_redeem_vault,
+        new_redeem_vault: config.redeem_vault,
+        signer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Quote the payout complete_redeem would make for `amount` wYLDS right now, meant to be simulated
+pub fn simulate_redeem(ctx: Context<SimulateRedeem>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+
+    let config = &ctx.accounts.config;
+    let gross = redeem_vault_amount(config, amount, ctx.accounts.price_oracle.as_ref())?;
+    let fee = Config::fee_for(gross, config.redeem_fee_bps)?;
+
+    // Same balance and reserve checks as complete_redeem
+    let available = ctx.accounts.redeem_vault_token_account.amount;
+    let vault_can_fill =
+        gross > 0 && available >= gross && available - gross >= config.min_vault_reserve;
+
+    emit!(RedeemQuote {
+        amount,
+        gross,
+        fee,
+        net: gross - fee,
+        vault_can_fill,
+    });
+    Ok(())
+}
+
+// Claim an epoch's rewards and record the payout in the user's deposit position, valued in
+// vault tokens at the current exchange rate; redemptions draw it down first
+pub fn claim_and_restake(
+    ctx: Context<ClaimAndRestake>,
+    amount: u64,
+    proof: Vec<ProofNode>,
+) -> Result<()> {
+    let to = ctx.accounts.claim.user_mint_token_account.to_account_info();
+    let payout = pay_claim(
+        &mut ctx.accounts.claim,
+        &ctx.bumps.claim,
+        amount,
+        &proof,
+        to,
+    )?;
+
+    let config = &ctx.accounts.claim.config;
+    let backing = config.mint_to_vault_amount(payout)?;
+    let position = &mut ctx.accounts.user_position;
+    position.user = ctx.accounts.claim.user.key();
+    position.bump = ctx.bumps.user_position;
+    position.record_restake(backing)?;
+
+    emit!(RewardsRestaked {
+        user: position.user,
+        epoch: ctx.accounts.claim.epoch.index,
+        amount: payout,
+        backing,
+    });
+    Ok(())
+}
+
+// Report the vault tokens held across both vaults and the wYLDS supply, meant to be simulated
+pub fn get_tvl(ctx: Context<GetTvl>) -> Result<()> {
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    let redeem_vault_balance = ctx.accounts.redeem_vault_token_account.amount;
+
+    emit!(TvlSnapshot {
+        vault_balance,
+        redeem_vault_balance,
+        total_vault_tokens: safe_add(vault_balance, redeem_vault_balance)?,
+        mint_supply: ctx.accounts.mint.supply,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Move deposited vault tokens into the redeem vault to fund redemptions (only the deposit
+// vault's owner, config.vault_authority, can do this)
+pub fn rebalance_to_redeem_vault(ctx: Context<RebalanceToRedeemVault>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.vault_token_account.amount >= amount,
+        CustomErrorCode::InsufficientVaultBalance
+    );
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.vault_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.vault_mint.to_account_info(),
+                to: ctx.accounts.redeem_vault_token_account.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.config.vault_decimals,
+    )?;
+
+    msg!("Moved {} vault tokens to the redeem vault", amount);
+    emit!(VaultRebalanced {
+        amount,
+        from: ctx.accounts.vault_token_account.key(),
+        to: ctx.accounts.redeem_vault_token_account.key(),
+        signer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Rotate the owner deposits must be sent to (only program update authority can do this)
+pub fn set_vault_authority(ctx: Context<SetVaultAuthority>, new_authority: Pubkey) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    require!(
+        new_authority != Pubkey::default(),
+        CustomErrorCode::InvalidNewAuthority
+    );
+
+    let config = &mut ctx.accounts.config;
+    let old_authority = config.vault_authority;
+    config.vault_authority = new_authority;
+
+    msg!("Vault authority set to {}", new_authority);
+    emit!(VaultAuthorityUpdated {
+        old_authority,
+        new_authority,
+        signer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Update the minimum redemption amount (only the config admin can do this)
+pub fn set_min_redeem(ctx: Context<SetMinRedeem>, min_redeem: u64) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::MinRedeem,
+        config.min_redeem,
+        min_redeem,
+        ctx.accounts.signer.key(),
+    );
+    config.min_redeem = min_redeem;
+
+    msg!("Minimum redemption set to {}", min_redeem);
+    Ok(())
+}
+
+// Update the per-user cooldown between deposits (only the config admin can do this)
+pub fn set_deposit_cooldown(
+    ctx: Context<SetDepositCooldown>,
+    deposit_cooldown_seconds: i64,
+) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    require!(
+        deposit_cooldown_seconds >= 0,
+        CustomErrorCode::InvalidAmount
+    );
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::DepositCooldownSeconds,
+        config.deposit_cooldown_seconds as u64,
+        deposit_cooldown_seconds as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.deposit_cooldown_seconds = deposit_cooldown_seconds;
+
+    msg!(
+        "Deposit cooldown set to {} seconds",
+        deposit_cooldown_seconds
+    );
+    Ok(())
+}
+
+// Update the SOL buffer request_redeem requires on the redeem vault authority PDA
+// (only the config admin can do this)
+pub fn set_redeem_authority_min_lamports(
+    ctx: Context<SetRedeemAuthorityMinLamports>,
+    redeem_authority_min_lamports: u64,
+) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::RedeemAuthorityMinLamports,
+        config.redeem_authority_min_lamports,
+        redeem_authority_min_lamports,
+        ctx.accounts.signer.key(),
+    );
+    config.redeem_authority_min_lamports = redeem_authority_min_lamports;
+
+    msg!(
+        "Redeem vault authority minimum set to {} lamports",
+        redeem_authority_min_lamports
+    );
+    Ok(())
+}
+
+pub fn get_config(ctx: Context<GetConfig>) -> Result<()> {
+    let config = &ctx.accounts.config;
+
+    emit!(ConfigSnapshot {
+        version: config.version,
+        vault: config.vault,
+        mint: config.mint,
+        paused: config.is_paused()?,
+        deposits_paused: config.deposits_paused,
+        redeems_paused: config.redeems_paused,
+        rate_numerator: config.rate_numerator,
+        rate_denominator: config.rate_denominator,
+        deposit_fee_bps: config.deposit_fee_bps,
+        redeem_fee_bps: config.redeem_fee_bps,
+        claim_fee_bps: config.claim_fee_bps,
+        min_deposit: config.min_deposit,
+        max_deposit: config.max_deposit,
+        per_user_deposit_cap: config.per_user_deposit_cap,
+        min_redeem: config.min_redeem,
+        mint_cap: config.mint_cap,
+        total_minted: config.total_minted,
+        freeze_administrators: config.freeze_administrators.len() as u8,
+        rewards_administrators: config.rewards_administrators.len() as u8,
+        max_administrators: config.max_administrators,
+        epochs_created: config.epochs_created,
+    });
+    Ok(())
+}
+
+pub fn freeze_epoch(ctx: Context<FreezeEpoch>) -> Result<()> {
+    set_epoch_frozen(ctx, true)
+}
+
+pub fn unfreeze_epoch(ctx: Context<FreezeEpoch>) -> Result<()> {
+    set_epoch_frozen(ctx, false)
+}
+
+fn set_epoch_frozen(ctx: Context<FreezeEpoch>, frozen: bool) -> Result<()> {
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let epoch = &mut ctx.accounts.epoch;
+    epoch.frozen = frozen;
+
+    msg!("Epoch {} frozen: {}", epoch.index, frozen);
+    emit!(EpochFreezeUpdated {
+        epoch: epoch.index,
+        admin: ctx.accounts.admin.key(),
+        frozen,
+    });
+    Ok(())
+}
+
+// Replaces a frozen epoch's root before anyone has claimed against it, then re-opens it.
+// Once a claim exists the old root has minted rewards, so it can no longer change.
+pub fn update_rewards_epoch_root(
+    ctx: Context<UpdateRewardsEpochRoot>,
+    new_root: [u8; 32],
+    new_total: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts
+            .config
+            .is_rewards_administrator(&ctx.accounts.admin.key())?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let epoch = &mut ctx.accounts.epoch;
+    require!(epoch.frozen, CustomErrorCode::EpochNotFrozen);
+    require!(epoch.claimed == 0, CustomErrorCode::EpochHasClaims);
+
+    let old_root = epoch.merkle_root;
+    let old_total = epoch.total;
+    epoch.merkle_root = new_root;
+    epoch.total = new_total;
+    epoch.frozen = false;
+
+    msg!("Updated root of epoch {}", epoch.index);
+    emit!(EpochRootUpdated {
+        epoch: epoch.index,
+        admin: ctx.accounts.admin.key(),
+        old_root,
+        new_root,
+        old_total,
+        new_total,
+    });
+    Ok(())
+}
+
+// First half of the config admin handover (only program update authority can do this).
+// Proposing the default pubkey cancels a pending proposal.
+pub fn propose_config_admin(ctx: Context<ProposeConfigAdmin>, new_admin: Pubkey) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    config.pending_config_admin = new_admin;
+
+    msg!("Proposed config admin: {}", new_admin);
+    emit!(ConfigAdminProposed {
+        current: config.config_admin,
+        proposed: new_admin,
+        proposer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Second half of the handover, signed by the proposed key
+pub fn accept_config_admin(ctx: Context<AcceptConfigAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+    require!(
+        config.pending_config_admin != Pubkey::default() && config.pending_config_admin == signer,
+        CustomErrorCode::InvalidPendingConfigAdmin
+    );
+
+    let previous = config.config_admin;
+    config.config_admin = signer;
+    config.pending_config_admin = Pubkey::default();
+
+    msg!("Config admin is now {}", signer);
+    emit!(ConfigAdminAccepted {
+        previous,
+        config_admin: signer,
+    });
+    Ok(())
+}
+
+pub fn claim_rewards_to(
+    ctx: Context<ClaimRewardsTo>,
+    amount: u64,
+    proof: Vec<ProofNode>,
+) -> Result<()> {
+    let to = ctx.accounts.destination.to_account_info();
+    pay_claim(
+        &mut ctx.accounts.claim,
+        &ctx.bumps.claim,
+        amount,
+        &proof,
+        to,
+    )?;
+    Ok(())
+}
+
+// Liveness probe for monitoring, meant to be simulated; changes nothing
+pub fn heartbeat(ctx: Context<GetConfig>) -> Result<()> {
+    let clock = Clock::get()?;
+    emit!(Heartbeat {
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+        paused: ctx.accounts.config.is_paused()?,
+        mint_supply: ctx.accounts.mint.supply,
+    });
+    Ok(())
+}
+
+// Compare the vault tokens held in both vaults with what the wYLDS supply redeems for at the
+// current exchange rate, meant to be simulated. Uses the same accounts as get_tvl.
+pub fn check_backing(ctx: Context<GetTvl>) -> Result<()> {
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    let redeem_vault_balance = ctx.accounts.redeem_vault_token_account.amount;
+    let total_vault_tokens = safe_add(vault_balance, redeem_vault_balance)?;
+    let mint_supply = ctx.accounts.mint.supply;
+    let required_backing = ctx.accounts.config.mint_to_vault_amount(mint_supply)?;
+
+    // Nothing outstanding is trivially backed; the ratio is undefined
+    let backing_ratio_bps = if required_backing == 0 {
+        None
+    } else {
+        Some(safe_mul_div(
+            total_vault_tokens,
+            BPS_DENOMINATOR as u64,
+            required_backing,
+            Rounding::Down,
+        )?)
+    };
+
+    emit!(BackingReport {
+        vault_balance,
+        redeem_vault_balance,
+        total_vault_tokens,
+        mint_supply,
+        required_backing,
+        backing_ratio_bps,
+        fully_backed: total_vault_tokens >= required_backing,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Deposit on behalf of a user who approved the deposit delegate PDA for at least `amount` on
+// their vault token account. The token program draws the approval down by what is moved and
+// clears the delegate once it reaches zero, so an approval is never reused past its amount.
+pub fn deposit_delegated(ctx: Context<DepositDelegated>, amount: u64) -> Result<()> {
+    let (fee, net_amount, mint_amount) = prepare_deposit(&mut ctx.accounts.config, amount)?;
+    let user = ctx.accounts.user.key();
+    let position = &mut ctx.accounts.user_position;
+    position.user = user;
+    position.bump = ctx.bumps.user_position;
+    position.record_deposit(amount, ctx.accounts.config.per_user_deposit_cap)?;
+    position.record_deposit_time(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.config.deposit_cooldown_seconds,
+    )?;
+    require!(
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+    require!(
+        !ctx.accounts.config.allowlist_enabled || ctx.accounts.allowlist_entry.is_some(),
+        CustomErrorCode::AddressNotAllowlisted
+    );
+    require_kyc_approved(&ctx.accounts.config, &ctx.accounts.user_mint_token_account)?;
+
+    let source = &ctx.accounts.user_vault_token_account;
+    let delegate: Option<Pubkey> = source.delegate.into();
+    require!(
+        delegate == Some(ctx.accounts.deposit_delegate.key()) && source.delegated_amount >= amount,
+        CustomErrorCode::DelegateApprovalInsufficient
+    );
+
+    enter_guarded(&mut ctx.accounts.config)?;
+
+    let delegate_seeds: &[&[u8]] = &[b"deposit_delegate", &[ctx.bumps.deposit_delegate]];
+    let delegate_signer = &[delegate_seeds];
+    if fee > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.vault_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_vault_token_account.to_account_info(),
+                    mint: ctx.accounts.vault_mint.to_account_info(),
+                    to: ctx.accounts.fee_vault_token_account.to_account_info(),
+                    authority: ctx.accounts.deposit_delegate.to_account_info(),
+                },
+                delegate_signer,
+            ),
+            fee,
+            ctx.accounts.config.vault_decimals,
+        )?;
+    }
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.vault_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_vault_token_account.to_account_info(),
+                mint: ctx.accounts.vault_mint.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.deposit_delegate.to_account_info(),
+            },
+            delegate_signer,
+        ),
+        net_amount,
+        ctx.accounts.config.vault_decimals,
+    )?;
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let signer = &[seeds];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.user_mint_token_account.to_account_info(),
+                authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            signer,
+        ),
+        mint_amount,
+    )?;
+
+    ctx.accounts.config.in_progress = false;
+
+    emit!(DepositEvent {
+        user,
+        amount,
+        mint: ctx.accounts.config.mint,
+        vault: ctx.accounts.config.vault,
+        memo: [0; 32],
+    });
+    Ok(())
+}
+
+// Move the next rewards epoch index forward, e.g. past the epochs a deployment created before
+// indexes were enforced (only program update authority can do this). Never moves backwards,
+// so indexes stay contiguous from here on.
+pub fn set_next_epoch_index(ctx: Context<SetNextEpochIndex>, next_epoch_index: u64) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    require!(
+        next_epoch_index >= config.next_epoch_index,
+        CustomErrorCode::NonSequentialEpoch
+    );
+    emit_parameter_change(
+        ConfigParameter::NextEpochIndex,
+        config.next_epoch_index,
+        next_epoch_index,
+        ctx.accounts.signer.key(),
+    );
+    config.next_epoch_index = next_epoch_index;
+    config.epochs_created = config.epochs_created.max(next_epoch_index);
+
+    msg!("Next rewards epoch index set to {}", next_epoch_index);
+    Ok(())
+}
+
+// Mint small reward distributions straight to each recipient, without an epoch or Merkle
+// proof (only rewards administrators can do this)
+pub fn airdrop<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Airdrop<'info>>,
+    recipients: Vec<AirdropRecipient>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
+    );
+    let admin = ctx.accounts.admin.key();
+    require!(
+        ctx.accounts.config.is_rewards_administrator(&admin)?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+    require!(
+        !recipients.is_empty()
+            && recipients.len() <= MAX_AIRDROP_BATCH
+            && ctx.remaining_accounts.len() == recipients.len(),
+        CustomErrorCode::InvalidAirdropBatch
+    );
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let signer = &[seeds];
+    let mint = ctx.accounts.mint.key();
+
+    for (entry, destination_info) in recipients.iter().zip(ctx.remaining_accounts.iter()) {
+        require!(entry.amount > 0, CustomErrorCode::InvalidAmount);
+        let destination: InterfaceAccount<'info, TokenAccount> =
+            InterfaceAccount::try_from(destination_info)?;
+        require_keys_eq!(destination.mint, mint, CustomErrorCode::InvalidMint);
+        require_keys_eq!(
+            destination.owner,
+            entry.recipient,
+            CustomErrorCode::InvalidTokenOwner
+        );
+
+        ctx.accounts.config.record_mint(entry.amount)?;
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: destination_info.clone(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                signer,
+            ),
+            entry.amount,
+        )?;
+
+        emit!(RewardsAirdropped {
+            admin,
+            recipient: entry.recipient,
+            destination: destination_info.key(),
+            amount: entry.amount,
+            mint,
+        });
+    }
+
+    msg!("Airdropped to {} recipients", recipients.len());
+    Ok(())
+}
+
+// Give a freeze administrator a quota of freezes, or reset it to a full allotment (only
+// program update authority can do this)
+pub fn set_freeze_scope(ctx: Context<SetFreezeScope>, admin: Pubkey, quota: u32) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let scope = &mut ctx.accounts.freeze_scope;
+    scope.admin = admin;
+    scope.quota = quota;
+    scope.remaining = quota;
+    scope.bump = ctx.bumps.freeze_scope;
+
+    msg!("Freeze quota for {} set to {}", admin, quota);
+    emit!(FreezeScopeSet {
+        admin,
+        quota,
+        signer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Settle several redemption requests in full (only rewards administrators can do this).
+// remaining_accounts = (redemption_request, user, user_mint_token_account,
+// user_vault_token_account, user_position) groups.
+pub fn complete_redeem_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CompleteRedeemBatch<'info>>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.config.is_paused()?,
+        CustomErrorCode::ProgramPaused
+    );
+    require!(
+        !ctx.accounts.config.redeems_paused,
+        CustomErrorCode::RedeemsPaused
+    );
+
+    // Admin gate
+    let admin = ctx.accounts.admin.key();
+    require!(
+        ctx.accounts.config.is_rewards_administrator(&admin)?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+    let requested = ctx.remaining_accounts.len() / 5;
+    require!(
+        ctx.remaining_accounts.len().is_multiple_of(5)
+            && requested > 0
+            && requested <= MAX_REDEEM_BATCH,
+        CustomErrorCode::InvalidRedeemBatch
+    );
+
+    let seeds: &[&[u8]] = &[
+        b"redeem_vault_authority",
+        &[ctx.bumps.redeem_vault_authority],
+    ];
+    let signer = &[seeds];
+    let now = Clock::get()?.unix_timestamp;
+
+    enter_guarded(&mut ctx.accounts.config)?;
+
+    let mut completed: u8 = 0;
+    let mut skipped: Vec<Pubkey> = Vec::new();
+    let mut total_paid: u64 = 0;
+    // Groups must be the open queue positions in order, starting at the head
+    let mut expected_position = ctx.accounts.config.redeem_queue_head;
+    for group in ctx.remaining_accounts.chunks_exact(5) {
+        let (request_info, user_info, user_mint_info, user_vault_info, position_info) =
+            (&group[0], &group[1], &group[2], &group[3], &group[4]);
+
+        require!(
+            request_info.owner == &crate::ID,
+            CustomErrorCode::RequestNotFound
+        );
+        let req = RedemptionRequest::try_deserialize(&mut &request_info.try_borrow_data()?[..])?;
+        require_keys_eq!(user_info.key(), req.user, CustomErrorCode::RequestNotFound);
+        require_eq_logged!(
+            req.version,
+            REDEMPTION_REQUEST_VERSION,
+            CustomErrorCode::RedemptionRequestVersionMismatch
+        );
+        require_eq_logged!(
+            req.queue_position,
+            expected_position,
+            CustomErrorCode::RedeemOutOfOrder
+        );
+        expected_position = ctx
+            .accounts
+            .config
+            .next_open_queue_position(safe_add(expected_position, 1)?);
+        require_keys_eq!(
+            req.mint,
+            ctx.accounts.config.mint,
+            CustomErrorCode::InvalidMint
+        );
+        // The vault asset must not have changed between request and completion
+        require_keys_eq!(
+            req.vault_mint,
+            ctx.accounts.config.vault,
+            CustomErrorCode::InvalidVaultMint
+        );
+        require!(
+            now >= req
+                .requested_ts
+                .saturating_add(ctx.accounts.config.redeem_delay_seconds),
+            CustomErrorCode::RedeemTimelockNotElapsed
+        );
+        require_keys_eq!(
+            user_vault_info.key(),
+            req.destination,
+            CustomErrorCode::InvalidRedeemDestination
+        );
+        let (position_key, _) =
+            Pubkey::find_program_address(&[b"position", req.user.as_ref()], &crate::ID);
+        require_keys_eq!(
+            position_info.key(),
+            position_key,
+            CustomErrorCode::InvalidUserPosition
+        );
+
+        let user_mint_token_account: InterfaceAccount<'info, TokenAccount> =
+            InterfaceAccount::try_from(user_mint_info)?;
+        require_keys_eq!(
+            user_mint_token_account.mint,
+            ctx.accounts.config.mint,
+            CustomErrorCode::InvalidMint
+        );
+        require_keys_eq!(
+            user_mint_token_account.owner,
+            req.user,
+            CustomErrorCode::InvalidTokenOwner
+        );
+        require!(
+            !user_mint_token_account.is_frozen(),
+            CustomErrorCode::UserTokenAccountFrozen
+        );
+
+        // As in complete_redeem, never burn more than the user still holds
+        let amount_to_redeem = std::cmp::min(user_mint_token_account.amount, req.amount);
+        require!(amount_to_redeem > 0, CustomErrorCode::InvalidAmount);
+        let vault_amount = redeem_vault_amount(
+            &ctx.accounts.config,
+            amount_to_redeem,
+            ctx.accounts.price_oracle.as_ref(),
+        )?;
+        require!(vault_amount > 0, CustomErrorCode::InvalidAmount);
+
+        // A request the redeem vault cannot cover is left open for a later settlement, and
+        // everything queued behind it waits with it
+        let redeem_vault_balance = ctx.accounts.redeem_vault_token_account.amount;
+        if !skipped.is_empty()
+            || redeem_vault_balance < vault_amount
+            || redeem_vault_balance - vault_amount < ctx.accounts.config.min_vault_reserve
+        {
+            skipped.push(req.user);
+            continue;
+        }
+
+        let fee = Config::fee_for(vault_amount, ctx.accounts.config.redeem_fee_bps)?;
+        let net_amount = vault_amount - fee;
+        require!(
+            net_amount >= req.min_usdc_out,
+            CustomErrorCode::SlippageExceeded
+        );
+
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: user_mint_info.clone(),
+                    authority: ctx.accounts.redeem_vault_authority.to_account_info(),
+                },
+                signer,
+            ),
+            amount_to_redeem,
+        )?;
+        ctx.accounts.config.record_burn(amount_to_redeem);
+
+        // Redeemed vault tokens free up room under the per-user deposit cap
+        if position_info.owner == &crate::ID {
+            let mut data = position_info.try_borrow_mut_data()?;
+            let mut position = UserPosition::try_deserialize(&mut &data[..])?;
+            position.record_redemption(vault_amount);
+            position.try_serialize(&mut &mut data[..])?;
+        }
+
+        if fee > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.vault_token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.redeem_vault_token_account.to_account_info(),
+                        mint: ctx.accounts.vault_mint.to_account_info(),
+                        to: ctx.accounts.fee_vault_token_account.to_account_info(),
+                        authority: ctx.accounts.redeem_vault_authority.to_account_info(),
+                    },
+                    signer,
+                ),
+                fee,
+                ctx.accounts.config.vault_decimals,
+            )?;
+        }
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.vault_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.redeem_vault_token_account.to_account_info(),
+                    mint: ctx.accounts.vault_mint.to_account_info(),
+                    to: user_vault_info.clone(),
+                    authority: ctx.accounts.redeem_vault_authority.to_account_info(),
+                },
+                signer,
+            ),
+            net_amount,
+            ctx.accounts.config.vault_decimals,
+        )?;
+        // The fee vault may be the redeem vault itself, so re-read the balance
+        ctx.accounts.redeem_vault_token_account.reload()?;
+
+        let mut user_mint_token_account = user_mint_token_account;
+        user_mint_token_account.reload()?;
+        revoke_leftover_redeem_allowance(
+            &ctx.accounts.token_program,
+            &user_mint_token_account,
+            &ctx.accounts.redeem_vault_authority,
+            signer,
+        )?;
+
+        emit!(RedeemCompleted {
+            user: req.user,
+            admin,
+            amount: amount_to_redeem,
+            fee,
+            mint: ctx.accounts.mint.key(),
+            vault: ctx.accounts.redeem_vault_token_account.mint,
+            remaining: 0,
+        });
+
+        ctx.accounts.config.dequeue_redemption(req.queue_position)?;
+
+        // Refund the request account rent to the original user
+        let rent = request_info.lamports();
+        **user_info.try_borrow_mut_lamports()? = safe_add(user_info.lamports(), rent)?;
+        **request_info.try_borrow_mut_lamports()? = 0;
+        request_info.assign(&system_program::ID);
+        request_info.resize(0)?;
+
+        completed += 1;
+        total_paid = safe_add(total_paid, net_amount)?;
+    }
+
+    ctx.accounts.config.in_progress = false;
+
+    msg!(
+        "{} of {} redemption requests completed by administrator {}",
+        completed,
+        requested,
+        admin
+    );
+    emit!(BatchRedeemCompleted {
+        admin,
+        requested: requested as u8,
+        completed,
+        skipped,
+        total_paid,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+// Emit the epochs `user` has claimed as a bitmap (read-only; meant to be simulated). A user
+// with no bitmap yet reports an empty one.
+pub fn get_claim_bitmap(ctx: Context<GetClaimBitmap>, user: Pubkey) -> Result<()> {
+    let bits = if ctx.accounts.claim_bitmap.owner == &crate::ID {
+        ClaimBitmap::try_deserialize(&mut &ctx.accounts.claim_bitmap.try_borrow_data()?[..])?.bits
+    } else {
+        Vec::new()
+    };
+
+    emit!(ClaimBitmapSnapshot {
+        user,
+        bits,
+        epochs_created: ctx.accounts.config.epochs_created,
+    });
+    Ok(())
+}
+
+// Cap how much external mint caller program `caller` may mint per window, starting a fresh
+// window (only program update authority can do this)
+pub fn set_mint_caller_quota(
+    ctx: Context<SetMintCallerQuota>,
+    caller: Pubkey,
+    max_per_window: u64,
+    window_seconds: i64,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(window_seconds > 0, CustomErrorCode::InvalidMintQuotaWindow);
+
+    let quota = &mut ctx.accounts.mint_caller_quota;
+    quota.caller = caller;
+    quota.max_per_window = max_per_window;
+    quota.window_seconds = window_seconds;
+    quota.minted_in_window = 0;
+    quota.window_start_ts = Clock::get()?.unix_timestamp;
+    quota.bump = ctx.bumps.mint_caller_quota;
+
+    msg!(
+        "Mint quota for {} set to {} per {} seconds",
+        caller,
+        max_per_window,
+        window_seconds
+    );
+    emit!(MintCallerQuotaSet {
+        caller,
+        max_per_window,
+        window_seconds,
+        signer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Accept another vault mint for deposit_asset at its own exchange rate (only program update
+// authority can do this)
+pub fn add_vault_mint(
+    ctx: Context<AddVaultMint>,
+    rate_numerator: u64,
+    rate_denominator: u64,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    require!(
+        ctx.accounts.vault_mint.key() != config.vault,
+        CustomErrorCode::VaultMintAlreadyAccepted
+    );
+    require!(
+        rate_numerator > 0 && rate_denominator > 0,
+        CustomErrorCode::InvalidExchangeRate
+    );
+    require!(
+        config.vault_mint_count < config.max_vault_mints,
+        CustomErrorCode::TooManyVaultMints
+    );
+    config.vault_mint_count += 1;
+
+    let asset = &mut ctx.accounts.vault_asset;
+    asset.vault_mint = ctx.accounts.vault_mint.key();
+    asset.vault_token_account = ctx.accounts.vault_token_account.key();
+    asset.redeem_vault_token_account = ctx.accounts.redeem_vault_token_account.key();
+    asset.decimals = ctx.accounts.vault_mint.decimals;
+    asset.rate_numerator = rate_numerator;
+    asset.rate_denominator = rate_denominator;
+    asset.bump = ctx.bumps.vault_asset;
+
+    msg!(
+        "Vault mint {} accepted at {}/{}",
+        asset.vault_mint,
+        rate_numerator,
+        rate_denominator
+    );
+    emit!(VaultMintAdded {
+        vault_mint: asset.vault_mint,
+        vault_token_account: asset.vault_token_account,
+        redeem_vault_token_account: asset.redeem_vault_token_account,
+        rate_numerator,
+        rate_denominator,
+        signer: ctx.accounts.signer.key(),
+    });
+    Ok(())
+}
+
+// Stop accepting a vault mint added by add_vault_mint (only program update authority can do
+// this). Tokens already in its vault token account stay with the vault authority.
+pub fn remove_vault_mint(ctx: Context<RemoveVaultMint>) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    config.vault_mint_count = config.vault_mint_count.saturating_sub(1);
+
+    let vault_mint = ctx.accounts.vault_asset.vault_mint;
+    msg!("Vault mint {} removed", vault_mint);
+    emit!(VaultMintRemoved {
+        vault_mint,
+        signer: ctx.accounts.signer.key(),
+    });
+    // Anchor will auto-close vault_asset to `signer` per the accounts attr
+    Ok(())
+}
+
+// Update the limit on additional vault mints (only program update authority can do this)
+pub fn set_max_vault_mints(ctx: Context<SetMaxVaultMints>, max_vault_mints: u8) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    require!(
+        max_vault_mints >= config.vault_mint_count,
+        CustomErrorCode::TooManyVaultMints
+    );
+    emit_parameter_change(
+        ConfigParameter::MaxVaultMints,
+        config.max_vault_mints as u64,
+        max_vault_mints as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.max_vault_mints = max_vault_mints;
+
+    msg!("Maximum additional vault mints set to {}", max_vault_mints);
+    Ok(())
+}
+
+// Deposit one of the additional vault mints and mint wYLDS at that asset's exchange rate.
+// The deposit limits, per-user cap and cooldown are denominated in the primary vault token, so
+// the deposit counts against them at what its wYLDS would redeem for there. The deposit fee is
+// taken at the same rate but stays in the asset's vault token account; config.fee_vault only
+// holds the primary vault token.
+pub fn deposit_asset(ctx: Context<DepositAsset>, amount: u64, memo: [u8; 32]) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let asset = &ctx.accounts.vault_asset;
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    let value = config.mint_to_vault_amount(asset.vault_to_mint_amount(
+        amount,
+        config.mint_decimals,
+        Rounding::Down,
+    )?)?;
+    check_deposit(config, value)?;
+
+    let fee = Config::fee_for(amount, config.deposit_fee_bps)?;
+    let mint_amount =
+        asset.vault_to_mint_amount(amount - fee, config.mint_decimals, config.mint_rounding())?;
+    require!(mint_amount > 0, CustomErrorCode::InvalidAmount);
+    ctx.accounts.config.record_mint(mint_amount)?;
+
+    let position = &mut ctx.accounts.user_position;
+    position.user = ctx.accounts.signer.key();
+    position.bump = ctx.bumps.user_position;
+    position.record_deposit(value, ctx.accounts.config.per_user_deposit_cap)?;
+    position.record_deposit_time(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.config.deposit_cooldown_seconds,
+    )?;
+    require!(
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+    require!(
+        !ctx.accounts.config.allowlist_enabled || ctx.accounts.allowlist_entry.is_some(),
+        CustomErrorCode::AddressNotAllowlisted
+    );
+    require_kyc_approved(&ctx.accounts.config, &ctx.accounts.user_mint_token_account)?;
+
+    enter_guarded(&mut ctx.accounts.config)?;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_vault_token_account.to_account_info(),
+        mint: ctx.accounts.vault_mint.to_account_info(),
+        to: ctx.accounts.vault_token_account.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.vault_token_program.to_account_info(),
+            cpi_accounts,
+        ),
+        amount,
+        ctx.accounts.vault_asset.decimals,
+    )?;
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let signer = &[seeds];
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.user_mint_token_account.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        mint_amount,
+    )?;
+
+    ctx.accounts.config.in_progress = false;
+
+    msg!("Emitting DepositEvent");
+    emit!(DepositEvent {
+        user: ctx.accounts.signer.key(),
+        amount,
+        mint: ctx.accounts.config.mint,
+        vault: ctx.accounts.vault_asset.vault_mint,
+        memo,
+    });
+    msg!("Emitted DepositEvent");
+    Ok(())
+}
+
+// Burn the signer's wYLDS and pay it out in one of the additional vault mints at that asset's
+// rate, from the asset's own redeem vault. The primary redeem vault and its queue are never
+// touched, so this settles at once. The redeem fee is taken at the same rate and stays in the
+// asset's redeem vault.
+pub fn redeem_asset(ctx: Context<RedeemAsset>, amount: u64, min_vault_out: u64) -> Result<()> {
+    let config = &ctx.accounts.config;
+    require!(!config.is_paused()?, CustomErrorCode::ProgramPaused);
+    require!(!config.redeems_paused, CustomErrorCode::RedeemsPaused);
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        amount >= config.min_redeem,
+        CustomErrorCode::RedeemBelowMinimum
+    );
+    require!(
+        !is_blocked(&ctx.accounts.blocked_entry),
+        CustomErrorCode::AddressBlocked
+    );
+
+    let vault_amount = ctx.accounts.vault_asset.mint_to_vault_amount(
+        amount,
+        config.mint_decimals,
+        config.redeem_rounding(),
+    )?;
+    require!(vault_amount > 0, CustomErrorCode::InvalidAmount);
+    let fee = Config::fee_for(vault_amount, config.redeem_fee_bps)?;
+    let net_amount = vault_amount - fee;
+    require!(
+        net_amount >= min_vault_out,
+        CustomErrorCode::SlippageExceeded
+    );
+    require!(
+        ctx.accounts.redeem_vault_token_account.amount >= net_amount,
+        CustomErrorCode::InsufficientVaultBalance
+    );
+
+    enter_guarded(&mut ctx.accounts.config)?;
+
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.user_mint_token_account.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    ctx.accounts.config.record_burn(amount);
+
+    // The per-user cap counts deposits in primary vault tokens (see deposit_asset)
+    if ctx.accounts.user_position.owner == &crate::ID {
+        let value = ctx.accounts.config.mint_to_vault_amount(amount)?;
+        let mut data = ctx.accounts.user_position.try_borrow_mut_data()?;
+        let mut position = UserPosition::try_deserialize(&mut &data[..])?;
+        position.record_redemption(value);
+        position.try_serialize(&mut &mut data[..])?;
+    }
+
+    let seeds: &[&[u8]] = &[
+        b"redeem_vault_authority",
+        &[ctx.bumps.redeem_vault_authority],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.vault_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.redeem_vault_token_account.to_account_info(),
+                mint: ctx.accounts.vault_mint.to_account_info(),
+                to: ctx.accounts.user_vault_token_account.to_account_info(),
+                authority: ctx.accounts.redeem_vault_authority.to_account_info(),
+            },
+            &[seeds],
+        ),
+        net_amount,
+        ctx.accounts.vault_asset.decimals,
+    )?;
+
+    ctx.accounts.config.in_progress = false;
+
+    emit!(AssetRedeemed {
+        user: ctx.accounts.signer.key(),
+        amount,
+        vault_amount: net_amount,
+        fee,
+        vault_mint: ctx.accounts.vault_asset.vault_mint,
+    });
+    Ok(())
+}
+
+// Choose the rounding direction of exchange-rate conversions (only the config admin can do
+// this). Rounding mint down and redemption down keeps deposit/redeem cycles from leaking value.
+pub fn set_rounding_policy(
+    ctx: Context<SetRoundingPolicy>,
+    round_up_on_mint: bool,
+    round_down_on_redeem: bool,
+) -> Result<()> {
+    validate_config_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.program_data,
+        &ctx.accounts.signer,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::RoundUpOnMint,
+        config.round_up_on_mint as u64,
+        round_up_on_mint as u64,
+        ctx.accounts.signer.key(),
+    );
+    emit_parameter_change(
+        ConfigParameter::RoundDownOnRedeem,
+        config.round_down_on_redeem as u64,
+        round_down_on_redeem as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.round_up_on_mint = round_up_on_mint;
+    config.round_down_on_redeem = round_down_on_redeem;
+
+    msg!(
+        "Rounding policy set: round up on mint {}, round down on redeem {}",
+        round_up_on_mint,
+        round_down_on_redeem
+    );
+    Ok(())
+}
+
+// Permanently retire the signer's own wYLDS; unlike a redemption, no vault tokens are paid out
+pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.user_mint_token_account.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    ctx.accounts.config.record_burn(amount);
+
+    msg!("Emitting TokensBurned");
+    emit!(TokensBurned {
+        user: ctx.accounts.signer.key(),
+        amount,
+        mint: ctx.accounts.mint.key(),
+        total_minted: ctx.accounts.config.total_minted,
+    });
+    msg!("Emitted TokensBurned");
+    Ok(())
+}
+
+// Move collected fees from the fee vault to a treasury account (only program update authority
+// can do this). The fee vault must be owned by the fee authority PDA.
+pub fn sweep_fees(ctx: Context<SweepFees>, amount: u64) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.fee_vault_token_account.amount >= amount,
+        CustomErrorCode::InsufficientVaultBalance
+    );
+
+    let seeds: &[&[u8]] = &[b"fee_authority", &[ctx.bumps.fee_authority]];
+    let signer = &[seeds];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.fee_vault_token_account.to_account_info(),
+                mint: ctx.accounts.vault_mint.to_account_info(),
+                to: ctx.accounts.treasury_token_account.to_account_info(),
+                authority: ctx.accounts.fee_authority.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.config.vault_decimals,
+    )?;
+
+    msg!(
+        "Swept {} in fees to {}",
+        amount,
+        ctx.accounts.treasury_token_account.key()
+    );
+    emit!(FeesSwept {
+        admin: ctx.accounts.signer.key(),
+        fee_vault: ctx.accounts.fee_vault_token_account.key(),
+        treasury: ctx.accounts.treasury_token_account.key(),
+        amount,
+    });
+    Ok(())
+}
+
+// Choose whether new rewards epochs take slot-based or timestamp-based claim deadlines (only
+// program update authority can do this). Existing epochs keep the mode they were created with.
+pub fn set_slot_based_epoch_deadlines(
+    ctx: Context<SetSlotBasedEpochDeadlines>,
+    slot_based_epoch_deadlines: bool,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::SlotBasedEpochDeadlines,
+        config.slot_based_epoch_deadlines as u64,
+        slot_based_epoch_deadlines as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.slot_based_epoch_deadlines = slot_based_epoch_deadlines;
+
+    msg!(
+        "Slot-based epoch deadlines set to {}",
+        slot_based_epoch_deadlines
+    );
+    Ok(())
+}
+
+// Move the redemption queue head forward, e.g. past a request that can neither be completed nor
+// closed (only program update authority can do this). Positions closed out of order are skipped
+// without it. Never moves backwards or past the tail.
+pub fn set_redeem_queue_head(
+    ctx: Context<SetRedeemQueueHead>,
+    redeem_queue_head: u64,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.config;
+    require!(
+        redeem_queue_head >= config.redeem_queue_head
+            && redeem_queue_head <= config.redeem_queue_tail,
+        CustomErrorCode::InvalidRedeemQueueHead
+    );
+    emit_parameter_change(
+        ConfigParameter::RedeemQueueHead,
+        config.redeem_queue_head,
+        redeem_queue_head,
+        ctx.accounts.signer.key(),
+    );
+    config.move_redeem_queue_head(redeem_queue_head)?;
+
+    msg!("Redeem queue head set to {}", config.redeem_queue_head);
+    Ok(())
+}
+
+// Complete any pending redemption request in full regardless of its queue position, e.g. one
+// stuck behind the head (only program update authority can do this). Its position is closed like
+// any other; requests ahead of it keep their place and the head skips it once it gets there.
+pub fn complete_redeem_priority(ctx: Context<CompleteRedeemPriority>) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.redeem.admin)?;
+
+    let redeem = &mut ctx.accounts.redeem;
+    require!(!redeem.config.is_paused()?, CustomErrorCode::ProgramPaused);
+    require!(
+        !redeem.config.redeems_paused,
+        CustomErrorCode::RedeemsPaused
+    );
+
+    let (req, _) = load_redemption_request(redeem)?;
+    let queue_position = req.queue_position;
+    let amount = req.amount;
+    let previous_head = redeem.config.redeem_queue_head;
+
+    settle_redemption(redeem, &ctx.bumps.redeem, req, None)?;
+
+    let config = &mut ctx.accounts.redeem.config;
+    config.dequeue_redemption(queue_position)?;
+
+    msg!(
+        "Redemption at queue position {} completed out of order; head {} -> {}",
+        queue_position,
+        previous_head,
+        config.redeem_queue_head
+    );
+    emit!(RedeemSkipped {
+        user: ctx.accounts.redeem.user.key(),
+        signer: ctx.accounts.redeem.admin.key(),
+        amount,
+        queue_position,
+        previous_head,
+        new_head: ctx.accounts.redeem.config.redeem_queue_head,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Turn default-frozen enforcement on or off (only program update authority can do this).
+// Turning it on requires a Token-2022 mint whose DefaultAccountState is frozen.
+pub fn set_default_frozen(ctx: Context<SetDefaultFrozen>, default_frozen: bool) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    require!(
+        !default_frozen || mint_defaults_frozen(&ctx.accounts.mint)?,
+        CustomErrorCode::MintNotDefaultFrozen
+    );
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::DefaultFrozen,
+        config.default_frozen as u64,
+        default_frozen as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.default_frozen = default_frozen;
+
+    msg!("Default-frozen enforcement set to {}", default_frozen);
+    Ok(())
+}
+
+// True when `mint` is a Token-2022 mint whose new accounts start frozen
+fn mint_defaults_frozen(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let info = mint.to_account_info();
+    if info.owner != &spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state
+        .get_extension::<DefaultAccountState>()
+        .is_ok_and(|ext| ext.state == AccountState::Frozen as u8))
+}
+
+// Thaw a user's wYLDS account once they have passed KYC (only freeze administrators can do
+// this, and only while default_frozen is on)
+pub fn thaw_after_kyc(ctx: Context<ThawAfterKyc>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+
+    require!(
+        config.default_frozen,
+        CustomErrorCode::DefaultFrozenDisabled
+    );
+    // Verify signer is a freeze administrator
+    require!(
+        config.freeze_administrators.contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+    token_interface::thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token_interface::ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+        },
+        freeze_authority_seeds,
+    ))?;
+
+    msg!(
+        "Token account {} approved after KYC by administrator {}",
+        ctx.accounts.token_account.key(),
+        signer
+    );
+    emit!(AccountKycApproved {
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.token_account.owner,
+        admin: signer,
+        mint: ctx.accounts.mint.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Top up the redeem vault authority's SOL buffer checked by request_redeem (anyone can do this)
+pub fn fund_redeem_authority(ctx: Context<FundRedeemAuthority>, lamports: u64) -> Result<()> {
+    require!(lamports > 0, CustomErrorCode::InvalidAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.signer.to_account_info(),
+                to: ctx.accounts.redeem_vault_authority.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+
+    let balance = ctx.accounts.redeem_vault_authority.lamports();
+    msg!(
+        "Redeem vault authority funded with {} lamports, balance {}",
+        lamports,
+        balance
+    );
+    emit!(RedeemAuthorityFunded {
+        funder: ctx.accounts.signer.key(),
+        lamports,
+        balance,
+    });
+    Ok(())
+}
+
+// Move SOL above the configured buffer out of the redeem vault authority (only program update
+// authority can do this). The PDA also stays rent exempt.
+pub fn withdraw_redeem_authority_excess(
+    ctx: Context<WithdrawRedeemAuthorityExcess>,
+    lamports: u64,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(lamports > 0, CustomErrorCode::InvalidAmount);
+
+    let floor = ctx
+        .accounts
+        .config
+        .redeem_authority_min_lamports
+        .max(Rent::get()?.minimum_balance(0));
+    let current = ctx.accounts.redeem_vault_authority.lamports();
+    require!(
+        current.saturating_sub(floor) >= lamports,
+        CustomErrorCode::RedeemAuthorityBufferBreached
+    );
+
+    let seeds: &[&[u8]] = &[
+        b"redeem_vault_authority",
+        &[ctx.bumps.redeem_vault_authority],
+    ];
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.redeem_vault_authority.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            &[seeds],
+        ),
+        lamports,
+    )?;
+
+    let balance = ctx.accounts.redeem_vault_authority.lamports();
+    msg!(
+        "Withdrew {} lamports from the redeem vault authority, balance {}",
+        lamports,
+        balance
+    );
+    emit!(RedeemAuthorityExcessWithdrawn {
+        signer: ctx.accounts.signer.key(),
+        recipient: ctx.accounts.recipient.key(),
+        lamports,
+        balance,
+    });
+    Ok(())
+}
+
+// Bring a redemption request written under an older layout up to date (only rewards
+// administrators can do this). The account grows to RedemptionRequest::LEN, with the admin
+// topping up its rent, and every field the old layout lacked gets the value an up-to-date
+// request would have had: the migration time as request time, no slippage floor, the configured
+// vault mint, the user's vault token ATA as destination and no memo. Requests made before the
+// FIFO queue join it at the tail in the order they are migrated. complete_redeem does the same
+// for a request it is handed, so this is only needed ahead of the other redemption paths.
+pub fn migrate_redemption_request(ctx: Context<MigrateRedemptionRequest>) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(
+        ctx.accounts.config.is_rewards_administrator(&admin)?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let (request, old_len) = upgrade_redemption_request(
+        &ctx.accounts.redemption_request,
+        &mut ctx.accounts.config,
+        &ctx.accounts.vault_mint.to_account_info(),
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        // The timelock and expiry run from the migration, as the original request time is unknown
+        Clock::get()?.unix_timestamp,
+    )?;
+    let old_len = old_len.ok_or(CustomErrorCode::RedemptionRequestVersionMismatch)?;
+    require_keys_eq!(
+        request.user,
+        ctx.accounts.user.key(),
+        CustomErrorCode::RequestNotFound
+    );
+
+    msg!(
+        "Redemption request for {} migrated from {} bytes by administrator {}",
+        request.user,
+        old_len,
+        admin
+    );
+    emit!(RedemptionRequestMigrated {
+        user: request.user,
+        admin,
+        old_len: old_len as u64,
+        version: REDEMPTION_REQUEST_VERSION,
+        queue_position: request.queue_position,
+    });
+    Ok(())
+}
+
+// Set the grace period during which rewards administrators replaced by a rotation are still
+// accepted (only program update authority can do this). 0 ends grace at the next rotation.
+pub fn set_rewards_admin_grace_period(
+    ctx: Context<SetRewardsAdminGracePeriod>,
+    grace_seconds: i64,
+) -> Result<()> {
+    // Validate that the signer is the program's update authority
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(grace_seconds >= 0, CustomErrorCode::InvalidAmount);
+
+    let config = &mut ctx.accounts.config;
+    emit_parameter_change(
+        ConfigParameter::RewardsAdminGraceSeconds,
+        config.rewards_admin_grace_seconds as u64,
+        grace_seconds as u64,
+        ctx.accounts.signer.key(),
+    );
+    config.rewards_admin_grace_seconds = grace_seconds;
+
+    msg!(
+        "Rewards administrator grace period set to {}s",
+        grace_seconds
+    );
+    Ok(())
+}
+
+// Reallocate a rewards epoch written under an older layout to RewardsEpoch::LEN and fill the
+// fields it lacked (only rewards administrators can do this). `claimed` is only used for epochs
+// from before the claimed total was tracked, `tree_depth` and `merkle_root` only for epochs from
+// before the proof depth was stored.
+pub fn migrate_rewards_epoch(
+    ctx: Context<MigrateRewardsEpoch>,
+    index: u64,
+    merkle_root: [u8; 32],
+    tree_depth: u8,
+    claimed: u64,
+) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(
+        ctx.accounts.config.is_rewards_administrator(&admin)?,
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let epoch_info = ctx.accounts.epoch.to_account_info();
+    let old_len = epoch_info.data_len();
+    require!(
+        old_len <= RewardsEpoch::LEN,
+        CustomErrorCode::RewardsEpochVersionMismatch
+    );
+    if old_len < RewardsEpoch::LEN {
+        // Top up rent for the larger account before growing it
+        let rent_due = Rent::get()?
+            .minimum_balance(RewardsEpoch::LEN)
+            .saturating_sub(epoch_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: epoch_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        epoch_info.resize(RewardsEpoch::LEN)?;
+    }
+
+    let mut data = epoch_info.try_borrow_mut_data()?;
+    // Checks the discriminator; the fields added by the resize read as zero
+    let mut epoch = RewardsEpoch::try_deserialize(&mut &data[..])?;
+    require!(
+        epoch.version < REWARDS_EPOCH_VERSION,
+        CustomErrorCode::RewardsEpochVersionMismatch
+    );
+    require_eq_logged!(epoch.index, index, CustomErrorCode::InvalidRewardsEpoch);
+
+    if old_len < RewardsEpoch::CLAIMED_END {
+        require!(claimed <= epoch.total, CustomErrorCode::InvalidAmount);
+        epoch.claimed = claimed;
+    }
+    if old_len < RewardsEpoch::TREE_DEPTH_END {
+        require!(
+            tree_depth as usize <= MAX_PROOF_DEPTH,
+            CustomErrorCode::ProofTooDeep
+        );
+        epoch.tree_depth = tree_depth;
+        // Roots built before leaves and nodes were domain-separated no longer verify, so the
+        // administrator supplies the rebuilt root (or the stored one if it is already current)
+        epoch.merkle_root = merkle_root;
+    }
+    // No deadline, Flagged proofs (the original proof walk), not swept or frozen and an unknown
+    // creation slot all read as zero, which the resize already wrote
+    epoch.version = REWARDS_EPOCH_VERSION;
+    epoch.try_serialize(&mut &mut data[..])?;
+
+    msg!(
+        "Rewards epoch {} migrated from {} bytes by administrator {}",
+        index,
+        old_len,
+        admin
+    );
+    emit!(RewardsEpochMigrated {
+        index,
+        admin,
+        old_len: old_len as u64,
+        version: REWARDS_EPOCH_VERSION,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proof_length_must_match_tree_depth() {
+        assert!(check_proof_length(0, 0).is_ok());
+        assert!(check_proof_length(MAX_PROOF_DEPTH, MAX_PROOF_DEPTH as u8).is_ok());
+        assert_eq!(
+            check_proof_length(3, 4).unwrap_err(),
+            CustomErrorCode::InvalidProofLength.into()
+        );
+    }
+
+    #[test]
+    fn proof_deeper_than_the_ceiling_is_rejected() {
+        // Rejected as too deep even against an epoch claiming a matching depth
+        let too_deep = vec![
+            ProofNode {
+                sibling: [0u8; 32],
+                is_left: false,
+            };
+            MAX_PROOF_DEPTH + 1
+        ];
+        assert_eq!(too_deep.len(), 33);
+        assert_eq!(
+            check_proof_length(too_deep.len(), 33).unwrap_err(),
+            CustomErrorCode::ProofTooDeep.into()
+        );
+    }
+}
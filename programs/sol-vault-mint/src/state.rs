@@ -1,5 +1,39 @@
+use crate::error::*;
+use crate::math::{mul_div_u128, safe_add, safe_mul_div, Rounding};
 use anchor_lang::prelude::*;
 
+/// Basis point denominator used for fee configuration (10_000 = 100%).
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// Current `Config` layout version.
+pub const CONFIG_VERSION: u8 = 1;
+
+/// Current `RedemptionRequest` layout version.
+pub const REDEMPTION_REQUEST_VERSION: u8 = 1;
+
+/// Current `RewardsEpoch` layout version.
+pub const REWARDS_EPOCH_VERSION: u8 = 1;
+
+/// Size of a config account written before `version` existed: the original layout, allocated
+/// for five administrators per list. No versioned layout has this size at any administrator limit.
+pub const LEGACY_CONFIG_LEN: usize =
+    8 + 32 + 32 + (4 + (32 * 5)) + (4 + (32 * 5)) + 32 + 32 + 1 + 1;
+
+/// Queue positions past the redemption queue head whose out-of-order closing `Config` can
+/// record, one bit each.
+pub const REDEEM_QUEUE_WINDOW: u64 = 1024;
+
+/// Administrator list limit for new and migrated configs.
+pub const DEFAULT_MAX_ADMINISTRATORS: u8 = 5;
+/// Upper bound for `set_max_administrators`.
+pub const MAX_ADMINISTRATORS_CAP: u8 = 32;
+/// Additional vault mints `add_vault_mint` accepts in new and migrated configs.
+pub const DEFAULT_MAX_VAULT_MINTS: u8 = 2;
+
+/// SOL buffer (~0.0001 SOL) `request_redeem` requires on the redeem vault authority PDA in
+/// new and migrated configs.
+pub const DEFAULT_REDEEM_AUTHORITY_MIN_LAMPORTS: u64 = 100_000;
+
 #[account]
 pub struct Config {
     pub vault: Pubkey,
@@ -10,29 +44,620 @@ pub struct Config {
     pub redeem_vault: Pubkey,
     pub bump: u8,
     pub paused: bool,
+    /// Program whose PDA may mint via `external_program_mint` (default = disabled).
+    pub allow_mint_program_caller: Pubkey,
+    pub deposits_paused: bool,
+    pub redeems_paused: bool,
+    /// Exchange rate applied on deposit: minted = deposited * rate_numerator / rate_denominator.
+    /// Redemptions apply the inverse.
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    /// Decimals of the vault and mint tokens, captured at initialize. Conversions scale by
+    /// 10^(mint_decimals - vault_decimals).
+    pub vault_decimals: u8,
+    pub mint_decimals: u8,
+    /// Per-transaction deposit limits in vault token units. A `max_deposit` of 0 means no maximum.
+    pub min_deposit: u64,
+    pub max_deposit: u64,
+    /// Running total of mint tokens issued by this program, net of redemptions.
+    pub total_minted: u64,
+    /// Ceiling on `total_minted`. 0 means unlimited.
+    pub mint_cap: u64,
+    /// Fee taken from deposits in basis points, paid in vault tokens to `fee_vault`.
+    pub deposit_fee_bps: u16,
+    /// Vault token account that collects protocol fees.
+    pub fee_vault: Pubkey,
+    /// Fee taken from redemptions in basis points, retained in vault tokens in `fee_vault`.
+    pub redeem_fee_bps: u16,
+    /// Minimum number of seconds between `request_redeem` and `complete_redeem`.
+    pub redeem_delay_seconds: i64,
+    /// Seconds after which a pending redemption request may be expired by an admin. 0 disables.
+    pub redeem_expiry_seconds: i64,
+    /// When true, rewards are paid from the pre-funded rewards vault (owned by the
+    /// `rewards_vault_authority` PDA) instead of being minted.
+    pub rewards_from_vault: bool,
+    /// Seconds after a `deposit_with_split` before its deferred mint can be claimed.
+    pub vesting_cliff_seconds: i64,
+    /// When true, only users with an `AllowlistEntry` PDA may deposit.
+    pub allowlist_enabled: bool,
+    /// Vault tokens that must remain in the redeem vault after every `complete_redeem`.
+    pub min_vault_reserve: u64,
+    /// Price feed used by `complete_redeem` to price wYLDS in vault tokens. Default = 1:1 via
+    /// the exchange rate.
+    pub price_oracle: Pubkey,
+    /// Maximum age, in seconds, of the oracle price accepted by `complete_redeem`.
+    pub max_oracle_staleness: i64,
+    /// Layout version of this account. Set to `CONFIG_VERSION` by `initialize` and
+    /// `migrate_config`; 0 means the account predates versioning and must be migrated.
+    pub version: u8,
+    /// Maximum length of each administrator list; the account is sized for it.
+    pub max_administrators: u8,
+    /// Set while deposit or complete_redeem is running so a nested entry (e.g. via a
+    /// Token-2022 transfer hook) is rejected.
+    pub in_progress: bool,
+    /// Lifetime cap on vault tokens deposited per user, tracked in `UserPosition`.
+    /// 0 means unlimited.
+    pub per_user_deposit_cap: u64,
+    /// Unix timestamp until which the program is paused, set by `pause_until`.
+    /// 0 (or any past timestamp) means no timed pause.
+    pub pause_until_ts: i64,
+    /// Share of each rewards claim, in basis points, withheld from the payout. 0 = no fee.
+    pub claim_fee_bps: u16,
+    /// Smallest wYLDS amount `request_redeem` accepts. 0 = no minimum.
+    pub min_redeem: u64,
+    /// Minimum seconds between two deposits by the same user. 0 disables the cooldown.
+    pub deposit_cooldown_seconds: i64,
+    /// Lamports the redeem vault authority PDA must hold for `request_redeem` to proceed.
+    pub redeem_authority_min_lamports: u64,
+    /// Key holding the economic-parameter setters once accepted; default means the program
+    /// update authority still holds them.
+    pub config_admin: Pubkey,
+    /// Key proposed by propose_config_admin, waiting to sign accept_config_admin.
+    pub pending_config_admin: Pubkey,
+    /// One past the highest rewards epoch index created; claims against an epoch at or
+    /// above it are rejected.
+    pub epochs_created: u64,
+    /// Index the next create_rewards_epoch must use, keeping epoch indexes contiguous.
+    pub next_epoch_index: u64,
+    /// Vault mints accepted by deposit_asset besides `vault`, each with a VaultAsset PDA.
+    pub vault_mint_count: u8,
+    /// Limit on `vault_mint_count`.
+    pub max_vault_mints: u8,
+    /// Round wYLDS minted on deposit up instead of down (in the depositor's favor).
+    pub round_up_on_mint: bool,
+    /// Round vault tokens paid on redemption down (in the protocol's favor) instead of up.
+    pub round_down_on_redeem: bool,
+    /// New rewards epochs take their claim deadline as a slot count instead of a timestamp.
+    pub slot_based_epoch_deadlines: bool,
+    /// Queue position of the oldest open redemption request; only it may be completed.
+    pub redeem_queue_head: u64,
+    /// Queue position the next redemption request is assigned.
+    pub redeem_queue_tail: u64,
+    /// New wYLDS accounts start frozen (Token-2022 DefaultAccountState) and deposits only mint
+    /// into accounts a freeze administrator has thawed with `thaw_after_kyc`.
+    pub default_frozen: bool,
+    /// End of the grace period for `previous_rewards_administrators`.
+    pub grace_until_ts: i64,
+    /// Grace period each rewards administrator rotation starts; 0 disables it.
+    pub rewards_admin_grace_seconds: i64,
+    /// Pyth feed id the `price_oracle` account must carry.
+    pub price_feed_id: [u8; 32],
+    /// Widest oracle confidence interval accepted, in basis points of the price; 0 = no limit.
+    pub max_oracle_conf_bps: u16,
+    /// Positions within `REDEEM_QUEUE_WINDOW` of `redeem_queue_head` whose requests were closed
+    /// out of order, one bit per position modulo the window. The head skips them.
+    pub redeem_queue_closed: [u8; (REDEEM_QUEUE_WINDOW / 8) as usize],
+    /// Rewards administrators replaced by the last rotation; still accepted until `grace_until_ts`.
+    /// Kept last so every field before it sits at a fixed offset from the original layout.
+    pub previous_rewards_administrators: Vec<Pubkey>,
 }
 
 impl Config {
-    // The vectors have a max length of 5 each and must include the Borsh overhead of 4 bytes for
-    // the length prefix.
-    pub const LEN: usize = 8 + 32 + 32 + (4 + (32 * 5)) + (4 + (32 * 5)) + 32 + 32 + 1 + 1;
+    // Size of a config account at the default administrator limit.
+    pub const LEN: usize = Self::space(DEFAULT_MAX_ADMINISTRATORS);
+
+    // The vectors have a max length of `max_administrators` each and must include the Borsh
+    // overhead of 4 bytes for the length prefix.
+    //
+    // Migration note: new fields are appended to the end of the struct. Config accounts created
+    // before a field existed are too small to hold it and must be reallocated to
+    // `Config::space(max_administrators)` with `migrate_config` before the upgraded program can
+    // deserialize them. Shrinking an administrator list leaves stale bytes behind the serialized
+    // data, so `migrate_config` zeroes everything past `serialized_len` for the stored version
+    // before the appended fields are read. Fields appended before a release go into
+    // `CONFIG_TAIL_LEN`; once a layout has shipped, a further field needs a `CONFIG_VERSION` bump
+    // and its own length in `serialized_len`.
+    pub const fn space(max_administrators: u8) -> usize {
+        let admins = max_administrators as usize;
+        8 // discriminator
+        + 32 // vault
+        + 32 // mint
+        + (4 + (32 * admins)) // freeze_administrators
+        + (4 + (32 * admins)) // rewards_administrators
+        + 32 // vault_authority
+        + 32 // redeem_vault
+        + 1 // bump
+        + 1 // paused
+        + 32 // allow_mint_program_caller
+        + 1 // deposits_paused
+        + 1 // redeems_paused
+        + 8 // rate_numerator
+        + 8 // rate_denominator
+        + 1 // vault_decimals
+        + 1 // mint_decimals
+        + 8 // min_deposit
+        + 8 // max_deposit
+        + 8 // total_minted
+        + 8 // mint_cap
+        + 2 // deposit_fee_bps
+        + 32 // fee_vault
+        + 2 // redeem_fee_bps
+        + 8 // redeem_delay_seconds
+        + 8 // redeem_expiry_seconds
+        + 1 // rewards_from_vault
+        + 8 // vesting_cliff_seconds
+        + 1 // allowlist_enabled
+        + 8 // min_vault_reserve
+        + 32 // price_oracle
+        + 8 // max_oracle_staleness
+        + 1 // version
+        + 1 // max_administrators
+        + 1 // in_progress
+        + 8 // per_user_deposit_cap
+        + 8 // pause_until_ts
+        + 2 // claim_fee_bps
+        + 8 // min_redeem
+        + 8 // deposit_cooldown_seconds
+        + 8 // redeem_authority_min_lamports
+        + 32 // config_admin
+        + 32 // pending_config_admin
+        + 8 // epochs_created
+        + 8 // next_epoch_index
+        + 1 // vault_mint_count
+        + 1 // max_vault_mints
+        + 1 // round_up_on_mint
+        + 1 // round_down_on_redeem
+        + 1 // slot_based_epoch_deadlines
+        + 8 // redeem_queue_head
+        + 8 // redeem_queue_tail
+        + 1 // default_frozen
+        + 8 // grace_until_ts
+        + 8 // rewards_admin_grace_seconds
+        + 32 // price_feed_id
+        + 2 // max_oracle_conf_bps
+        + (REDEEM_QUEUE_WINDOW / 8) as usize // redeem_queue_closed
+        + (4 + (32 * admins)) // previous_rewards_administrators
+    }
+
+    /// Layout version of the config in `data`, the account as the previous program left it.
+    /// Accounts still at `LEGACY_CONFIG_LEN` predate the field and are version 0.
+    pub fn stored_version(data: &[u8]) -> Result<u8> {
+        if data.len() == LEGACY_CONFIG_LEN {
+            return Ok(0);
+        }
+        let offset = Self::serialized_len(data, 0)? + TAIL_VERSION_OFFSET;
+        data.get(offset)
+            .copied()
+            .ok_or(CustomErrorCode::ConfigVersionMismatch.into())
+    }
+
+    /// Number of bytes a config written at `version` occupies in `data`. Anything past it is
+    /// either unallocated padding or left over from a longer administrator list.
+    pub fn serialized_len(data: &[u8], version: u8) -> Result<usize> {
+        // discriminator, vault, mint, freeze_administrators, rewards_administrators,
+        // vault_authority, redeem_vault, bump, paused
+        let mut len = 8 + 32 + 32;
+        len += pubkey_vec_len(data, len)?;
+        len += pubkey_vec_len(data, len)?;
+        len += 32 + 32 + 1 + 1;
+        match version {
+            0 => {}
+            // the fixed-size tail, then previous_rewards_administrators
+            1 => {
+                len += CONFIG_TAIL_LEN;
+                len += pubkey_vec_len(data, len)?;
+            }
+            _ => return err!(CustomErrorCode::ConfigVersionMismatch),
+        }
+        require!(len <= data.len(), CustomErrorCode::ConfigVersionMismatch);
+        Ok(len)
+    }
+
+    /// True while the program is paused, either indefinitely by `pause` or until
+    /// `pause_until_ts` by `pause_until`.
+    pub fn is_paused(&self) -> Result<bool> {
+        Ok(self.paused || Clock::get()?.unix_timestamp < self.pause_until_ts)
+    }
+
+    /// True for a current rewards administrator, or for one replaced by the last rotation while
+    /// its grace period lasts, so transactions already in flight during a key rotation land.
+    pub fn is_rewards_administrator(&self, key: &Pubkey) -> Result<bool> {
+        if self.rewards_administrators.contains(key) {
+            return Ok(true);
+        }
+        Ok(self.previous_rewards_administrators.contains(key)
+            && Clock::get()?.unix_timestamp <= self.grace_until_ts)
+    }
+
+    /// Snapshots the current rewards administrators before the list changes and starts their
+    /// grace period.
+    pub fn start_rewards_admin_rotation(&mut self) -> Result<()> {
+        if self.rewards_admin_grace_seconds == 0 {
+            self.previous_rewards_administrators.clear();
+            self.grace_until_ts = 0;
+            return Ok(());
+        }
+        self.previous_rewards_administrators = self.rewards_administrators.clone();
+        self.grace_until_ts = Clock::get()?
+            .unix_timestamp
+            .checked_add(self.rewards_admin_grace_seconds)
+            .ok_or(CustomErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Returns the fee owed on `amount` at `fee_bps` basis points (rounded down).
+    pub fn fee_for(amount: u64, fee_bps: u16) -> Result<u64> {
+        safe_mul_div(
+            amount,
+            fee_bps as u64,
+            BPS_DENOMINATOR as u64,
+            Rounding::Down,
+        )
+    }
+
+    /// Records newly minted supply, rejecting it if it would exceed `mint_cap`.
+    pub fn record_mint(&mut self, amount: u64) -> Result<()> {
+        let total_minted = self
+            .total_minted
+            .checked_add(amount)
+            .ok_or(CustomErrorCode::MintCapExceeded)?;
+        require!(
+            self.mint_cap == 0 || total_minted <= self.mint_cap,
+            CustomErrorCode::MintCapExceeded
+        );
+        self.total_minted = total_minted;
+        Ok(())
+    }
+
+    /// Records burned supply. Saturates at zero since supply minted before tracking existed
+    /// was never counted.
+    pub fn record_burn(&mut self, amount: u64) {
+        self.total_minted = self.total_minted.saturating_sub(amount);
+    }
+
+    /// Hands out the next redemption queue position.
+    pub fn enqueue_redemption(&mut self) -> Result<u64> {
+        let position = self.redeem_queue_tail;
+        self.redeem_queue_tail = safe_add(position, 1)?;
+        Ok(position)
+    }
+
+    /// Takes a closed request out of the queue. Closing the head advances it past every
+    /// position already closed behind it; closing a later request records its position for
+    /// the head to skip once it gets there.
+    pub fn dequeue_redemption(&mut self, position: u64) -> Result<()> {
+        if position < self.redeem_queue_head {
+            return Ok(());
+        }
+        require!(
+            self.can_dequeue_redemption(position),
+            CustomErrorCode::RedeemQueueWindowExceeded
+        );
+        self.set_queue_position_closed(position, true);
+        self.skip_closed_queue_positions()
+    }
+
+    /// False for a request too far behind the head for its closing to be recorded.
+    pub fn can_dequeue_redemption(&self, position: u64) -> bool {
+        position < self.redeem_queue_head.saturating_add(REDEEM_QUEUE_WINDOW)
+    }
+
+    /// First position at or after `position` whose request has not been closed.
+    pub fn next_open_queue_position(&self, mut position: u64) -> u64 {
+        while position < self.redeem_queue_tail
+            && (position < self.redeem_queue_head || self.is_queue_position_closed(position))
+        {
+            position += 1;
+        }
+        position
+    }
+
+    /// Moves the head forward to `head`, forgetting closed positions it passes, then past any
+    /// closed positions right behind it.
+    pub fn move_redeem_queue_head(&mut self, head: u64) -> Result<()> {
+        let passed = (head - self.redeem_queue_head).min(REDEEM_QUEUE_WINDOW);
+        for position in self.redeem_queue_head..self.redeem_queue_head + passed {
+            self.set_queue_position_closed(position, false);
+        }
+        self.redeem_queue_head = head;
+        self.skip_closed_queue_positions()
+    }
+
+    fn skip_closed_queue_positions(&mut self) -> Result<()> {
+        while self.redeem_queue_head < self.redeem_queue_tail
+            && self.is_queue_position_closed(self.redeem_queue_head)
+        {
+            self.set_queue_position_closed(self.redeem_queue_head, false);
+            self.redeem_queue_head = safe_add(self.redeem_queue_head, 1)?;
+        }
+        Ok(())
+    }
+
+    fn is_queue_position_closed(&self, position: u64) -> bool {
+        let (byte, mask) = queue_bit(position);
+        self.redeem_queue_closed[byte] & mask != 0
+    }
+
+    fn set_queue_position_closed(&mut self, position: u64, closed: bool) {
+        let (byte, mask) = queue_bit(position);
+        if closed {
+            self.redeem_queue_closed[byte] |= mask;
+        } else {
+            self.redeem_queue_closed[byte] &= !mask;
+        }
+    }
+
+    /// Returns the (numerator, denominator) that converts vault token base units into mint token
+    /// base units, folding the decimal difference between the two mints into the exchange rate.
+    fn deposit_ratio(&self) -> Result<(u128, u128)> {
+        require!(
+            self.rate_numerator > 0 && self.rate_denominator > 0,
+            CustomErrorCode::InvalidExchangeRate
+        );
+        let mut numerator = self.rate_numerator as u128;
+        let mut denominator = self.rate_denominator as u128;
+        if self.mint_decimals >= self.vault_decimals {
+            numerator = numerator
+                .checked_mul(decimal_factor(self.mint_decimals - self.vault_decimals)?)
+                .ok_or(CustomErrorCode::InvalidDecimalScaling)?;
+        } else {
+            denominator = denominator
+                .checked_mul(decimal_factor(self.vault_decimals - self.mint_decimals)?)
+                .ok_or(CustomErrorCode::InvalidDecimalScaling)?;
+        }
+        Ok((numerator, denominator))
+    }
+
+    /// Rounding applied to wYLDS minted on deposit.
+    pub fn mint_rounding(&self) -> Rounding {
+        if self.round_up_on_mint {
+            Rounding::Up
+        } else {
+            Rounding::Down
+        }
+    }
+
+    /// Rounding applied to vault tokens paid on redemption.
+    pub fn redeem_rounding(&self) -> Rounding {
+        if self.round_down_on_redeem {
+            Rounding::Down
+        } else {
+            Rounding::Up
+        }
+    }
+
+    /// Converts a vault token (e.g. USDC) amount into the mint token (e.g. wYLDS) amount.
+    pub fn vault_to_mint_amount(&self, amount: u64) -> Result<u64> {
+        let (numerator, denominator) = self.deposit_ratio()?;
+        let minted = mul_div_u128(amount as u128, numerator, denominator, self.mint_rounding())
+            .map_err(|_| CustomErrorCode::InvalidDecimalScaling)?;
+        u64::try_from(minted).map_err(|_| CustomErrorCode::InvalidDecimalScaling.into())
+    }
+
+    /// Converts a mint token (e.g. wYLDS) amount back into the vault token (e.g. USDC) amount.
+    pub fn mint_to_vault_amount(&self, amount: u64) -> Result<u64> {
+        let (numerator, denominator) = self.deposit_ratio()?;
+        let returned = mul_div_u128(
+            amount as u128,
+            denominator,
+            numerator,
+            self.redeem_rounding(),
+        )
+        .map_err(|_| CustomErrorCode::InvalidDecimalScaling)?;
+        u64::try_from(returned).map_err(|_| CustomErrorCode::InvalidDecimalScaling.into())
+    }
+
+    /// Converts a mint token amount into vault tokens at an oracle price of
+    /// `price * 10^exponent` vault tokens per mint token, adjusting for the decimal difference.
+    pub fn mint_to_vault_amount_at_price(
+        &self,
+        amount: u64,
+        price: i64,
+        exponent: i32,
+    ) -> Result<u64> {
+        require!(price > 0, CustomErrorCode::InvalidOracleAccount);
+        let scale = exponent
+            .checked_add(self.vault_decimals as i32 - self.mint_decimals as i32)
+            .ok_or(CustomErrorCode::InvalidDecimalScaling)?;
+        let factor = decimal_factor(
+            u8::try_from(scale.unsigned_abs())
+                .map_err(|_| CustomErrorCode::InvalidDecimalScaling)?,
+        )?;
+        let mut returned = (amount as u128)
+            .checked_mul(price as u128)
+            .ok_or(CustomErrorCode::InvalidDecimalScaling)?;
+        if scale >= 0 {
+            returned = returned
+                .checked_mul(factor)
+                .ok_or(CustomErrorCode::InvalidDecimalScaling)?;
+        } else {
+            returned = mul_div_u128(returned, 1, factor, self.redeem_rounding())?;
+        }
+        u64::try_from(returned).map_err(|_| CustomErrorCode::InvalidDecimalScaling.into())
+    }
+}
+
+// Offset of `version` within the bytes version 1 appends after `paused`: the fields added
+// before versioning existed come first
+const TAIL_VERSION_OFFSET: usize = 32 // allow_mint_program_caller
+    + 1 // deposits_paused
+    + 1 // redeems_paused
+    + 8 // rate_numerator
+    + 8 // rate_denominator
+    + 1 // vault_decimals
+    + 1 // mint_decimals
+    + 8 // min_deposit
+    + 8 // max_deposit
+    + 8 // total_minted
+    + 8 // mint_cap
+    + 2 // deposit_fee_bps
+    + 32 // fee_vault
+    + 2 // redeem_fee_bps
+    + 8 // redeem_delay_seconds
+    + 8 // redeem_expiry_seconds
+    + 1 // rewards_from_vault
+    + 8 // vesting_cliff_seconds
+    + 1 // allowlist_enabled
+    + 8 // min_vault_reserve
+    + 32 // price_oracle
+    + 8; // max_oracle_staleness
+
+// Fixed-size bytes version 1 appends after `paused`, up to the trailing
+// `previous_rewards_administrators`
+const CONFIG_TAIL_LEN: usize = TAIL_VERSION_OFFSET
+    + 1 // version
+    + 1 // max_administrators
+    + 1 // in_progress
+    + 8 // per_user_deposit_cap
+    + 8 // pause_until_ts
+    + 2 // claim_fee_bps
+    + 8 // min_redeem
+    + 8 // deposit_cooldown_seconds
+    + 8 // redeem_authority_min_lamports
+    + 32 // config_admin
+    + 32 // pending_config_admin
+    + 8 // epochs_created
+    + 8 // next_epoch_index
+    + 1 // vault_mint_count
+    + 1 // max_vault_mints
+    + 1 // round_up_on_mint
+    + 1 // round_down_on_redeem
+    + 1 // slot_based_epoch_deadlines
+    + 8 // redeem_queue_head
+    + 8 // redeem_queue_tail
+    + 1 // default_frozen
+    + 8 // grace_until_ts
+    + 8 // rewards_admin_grace_seconds
+    + 32 // price_feed_id
+    + 2 // max_oracle_conf_bps
+    + (REDEEM_QUEUE_WINDOW / 8) as usize; // redeem_queue_closed
+
+// Byte and bit of `redeem_queue_closed` that track `position`
+fn queue_bit(position: u64) -> (usize, u8) {
+    let slot = (position % REDEEM_QUEUE_WINDOW) as usize;
+    (slot / 8, 1 << (slot % 8))
+}
+
+// Borsh size of the `Vec<Pubkey>` whose length prefix starts at `offset`
+fn pubkey_vec_len(data: &[u8], offset: usize) -> Result<usize> {
+    let prefix = data
+        .get(offset..offset + 4)
+        .ok_or(CustomErrorCode::ConfigVersionMismatch)?;
+    let count = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+    require!(
+        count <= MAX_ADMINISTRATORS_CAP as usize,
+        CustomErrorCode::ConfigVersionMismatch
+    );
+    Ok(4 + 32 * count)
+}
+
+fn decimal_factor(exponent: u8) -> Result<u128> {
+    10u128
+        .checked_pow(exponent as u32)
+        .ok_or(CustomErrorCode::InvalidDecimalScaling.into())
 }
 
 #[account]
 pub struct RewardsEpoch {
     pub index: u64,            // epoch id
-    pub merkle_root: [u8; 32], // sha256 root (sortPairs)
-    pub total: u64,            // optional: sum of all allocations
+    pub merkle_root: [u8; 32], // sha256 root, built per `merkle_mode`
+    pub total: u64,            // sum of all allocations, caps `claimed`
     pub created_ts: i64,
+    pub claimed: u64, // running sum of claimed allocations, claim fees included
+    pub claim_deadline_ts: i64, // claims rejected after this time, 0 = no deadline
+    pub merkle_mode: MerkleMode, // how proof steps order node and sibling
+    pub tree_depth: u8, // exact proof length every claim must supply, 0 = single leaf
+    pub swept: bool,  // unclaimed remainder forfeited, no further claims
+    pub frozen: bool, // claims halted by a rewards administrator
+    pub created_slot: u64,
+    pub claim_deadline_slot: u64, // slot-based deadline, used instead of claim_deadline_ts when set
+    pub version: u8,              // layout version, older epochs go through migrate_rewards_epoch
 }
 impl RewardsEpoch {
-    pub const LEN: usize = 8 + 8 + 32 + 8 + 8;
+    pub const LEN: usize = 8 + 8 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 1 + 8 + 8 + 1;
+
+    // End offsets of fields appended after the original (index, merkle_root, total, created_ts)
+    // layout that migrate_rewards_epoch cannot leave at zero. An epoch whose data stops short of
+    // one of these predates that field.
+    pub const CLAIMED_END: usize = 8 + 8 + 32 + 8 + 8 + 8;
+    pub const TREE_DEPTH_END: usize = Self::CLAIMED_END + 8 + 1 + 1;
+
+    /// True strictly after the deadline; a claim at the deadline slot/timestamp is still
+    /// accepted. Epochs without a deadline never close.
+    pub fn deadline_passed(&self, clock: &Clock) -> bool {
+        if self.claim_deadline_slot != 0 {
+            clock.slot > self.claim_deadline_slot
+        } else {
+            self.claim_deadline_ts != 0 && clock.unix_timestamp > self.claim_deadline_ts
+        }
+    }
 }
 
+/// Tree construction used for an epoch's Merkle root.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleMode {
+    /// Each proof step's `is_left` flag says which side the sibling is on.
+    Flagged,
+    /// Each step hashes the pair in ascending byte order (OpenZeppelin / merkletreejs
+    /// `sortPairs`); `is_left` is ignored.
+    SortedPairs,
+}
+
+/// Only its existence is checked: the program creates it on claim and never deserializes it,
+/// so the empty 8-byte markers written before `amount` and `claimed_ts` existed still block a
+/// second claim and need no migration.
 #[account]
-pub struct ClaimRecord {} // empty marker account, existence = already claimed
+pub struct ClaimRecord {
+    pub amount: u64,     // allocation claimed for the epoch
+    pub claimed_ts: i64, // existence = already claimed
+}
 impl ClaimRecord {
-    pub const LEN: usize = 8;
+    pub const LEN: usize = 8 + 8 + 8;
+}
+
+/// Per-user index of claimed epochs: bit `i % 8` of `bits[i / 8]` is set once epoch `i` is
+/// claimed. Claims made before the bitmap existed are only recorded by their `ClaimRecord`,
+/// which stays the double-claim guard.
+#[account]
+pub struct ClaimBitmap {
+    pub user: Pubkey,
+    pub bump: u8,
+    pub bits: Vec<u8>,
+}
+
+impl ClaimBitmap {
+    /// The bitmap grows in steps of this many bytes (256 epochs).
+    pub const CHUNK: usize = 32;
+
+    pub const fn space(bytes: usize) -> usize {
+        8 + 32 + 1 + 4 + bytes
+    }
+
+    /// Bytes needed to hold the bit for `index`, rounded up to whole chunks.
+    pub fn bytes_for(index: u64) -> usize {
+        (index as usize / 8 / Self::CHUNK + 1) * Self::CHUNK
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.bits
+            .get(index as usize / 8)
+            .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+    }
+
+    /// Marks `index` claimed; `bits` must already cover it.
+    pub fn set_claimed(&mut self, index: u64) {
+        self.bits[index as usize / 8] |= 1 << (index % 8);
+    }
 }
 
 #[account]
@@ -41,10 +666,291 @@ pub struct RedemptionRequest {
     pub amount: u64,
     pub mint: Pubkey,
     pub bump: u8,
+    pub requested_ts: i64,
+    pub vault_mint: Pubkey,
+    /// Minimum vault tokens the user accepts at completion, after the redeem fee and any
+    /// oracle pricing. 0 disables the check.
+    pub min_usdc_out: u64,
+    /// Vault token account that receives the proceeds; fixed at request time so the completing
+    /// admin cannot redirect them.
+    pub destination: Pubkey,
+    /// Client reference for off-chain reconciliation; all zeros means none.
+    pub memo: [u8; 32],
+    /// Position in the FIFO redemption queue, from `config.redeem_queue_tail`.
+    pub queue_position: u64,
+    /// Layout version; bumped whenever a field is appended. Older requests are brought up to
+    /// date with `migrate_redemption_request`.
+    pub version: u8,
 }
 
 impl RedemptionRequest {
-    pub const LEN: usize = 8 + 32 + 8 + 32 + 1;
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 1 + 8 + 32 + 8 + 32 + 32 + 8 + 1;
+
+    // End offsets of the fields appended after the original (user, amount, mint, bump) layout.
+    // A request whose data stops short of one of these predates that field.
+    pub const REQUESTED_TS_END: usize = 8 + 32 + 8 + 32 + 1 + 8;
+    pub const VAULT_MINT_END: usize = Self::REQUESTED_TS_END + 32;
+    pub const MIN_USDC_OUT_END: usize = Self::VAULT_MINT_END + 8;
+    pub const DESTINATION_END: usize = Self::MIN_USDC_OUT_END + 32;
+    pub const MEMO_END: usize = Self::DESTINATION_END + 32;
+    pub const QUEUE_POSITION_END: usize = Self::MEMO_END + 8;
+}
+
+#[account]
+pub struct VestingRecord {
+    pub user: Pubkey,
+    pub amount: u64,    // deferred mint tokens, already counted in total_minted
+    pub unlock_ts: i64, // claim_vested allowed from this time
+    pub bump: u8,
+}
+
+impl VestingRecord {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct UserPosition {
+    pub user: Pubkey,
+    pub total_deposited: u64, // vault tokens deposited, net of redemptions
+    pub bump: u8,
+    pub last_deposit_ts: i64,  // 0 until the first deposit
+    pub restaked_rewards: u64, // vault token value of rewards restaked, outside the deposit cap
+}
+
+impl UserPosition {
+    pub const LEN: usize = 8 + 32 + 8 + 1 + 8 + 8;
+
+    /// Rejects a deposit made less than `cooldown_seconds` after the previous one (0 disables
+    /// the check) and records `now` as the latest deposit.
+    pub fn record_deposit_time(&mut self, now: i64, cooldown_seconds: i64) -> Result<()> {
+        require!(
+            cooldown_seconds == 0 || now >= self.last_deposit_ts.saturating_add(cooldown_seconds),
+            CustomErrorCode::DepositCooldownActive
+        );
+        self.last_deposit_ts = now;
+        Ok(())
+    }
+
+    /// Adds a deposit, rejecting it if it would exceed `cap` (0 = unlimited).
+    pub fn record_deposit(&mut self, amount: u64, cap: u64) -> Result<()> {
+        let total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(CustomErrorCode::UserDepositCapExceeded)?;
+        require!(
+            cap == 0 || total_deposited <= cap,
+            CustomErrorCode::UserDepositCapExceeded
+        );
+        self.total_deposited = total_deposited;
+        Ok(())
+    }
+
+    /// Adds restaked rewards. They are yield rather than deposits, so they neither count
+    /// towards the per-user cap nor show up in `total_deposited`.
+    pub fn record_restake(&mut self, backing: u64) -> Result<()> {
+        self.restaked_rewards = self
+            .restaked_rewards
+            .checked_add(backing)
+            .ok_or(CustomErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Removes a redemption, drawing down restaked rewards before deposits so that redeeming
+    /// rewards never frees room under the per-user cap. Saturates at zero since deposits made
+    /// before positions were tracked were never counted.
+    pub fn record_redemption(&mut self, amount: u64) {
+        let from_rewards = amount.min(self.restaked_rewards);
+        self.restaked_rewards -= from_rewards;
+        self.total_deposited = self.total_deposited.saturating_sub(amount - from_rewards);
+    }
+}
+
+#[account]
+pub struct AllowlistEntry {
+    pub user: Pubkey,
+    pub bump: u8,
+}
+
+impl AllowlistEntry {
+    pub const LEN: usize = 8 + 32 + 1;
+}
+
+#[account]
+pub struct BlockedEntry {
+    pub user: Pubkey,
+    pub bump: u8,
+}
+
+impl BlockedEntry {
+    pub const LEN: usize = 8 + 32 + 1;
+}
+
+/// Why and by whom a token account was frozen; lives while the account is frozen through
+/// `freeze_token_account_with_reason`.
+#[account]
+pub struct FreezeRecord {
+    pub token_account: Pubkey,
+    pub admin: Pubkey,
+    pub reason_code: u16,
+    pub frozen_ts: i64,
+    pub bump: u8,
+}
+
+impl FreezeRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 8 + 1;
+}
+
+/// Caps how many token accounts one freeze administrator may freeze until the upgrade
+/// authority resets it. Administrators without one are unlimited.
+#[account]
+pub struct FreezeScope {
+    pub admin: Pubkey,
+    pub quota: u32,     // allotment restored by each set_freeze_scope
+    pub remaining: u32, // freezes left in the current allotment
+    pub bump: u8,
+}
+
+impl FreezeScope {
+    pub const LEN: usize = 8 + 32 + 4 + 4 + 1;
+}
+
+/// An additional deposit asset accepted by `deposit_asset`, with its own vault token account
+/// and exchange rate. `redeem_asset` pays it back out of its own redeem vault.
+#[account]
+pub struct VaultAsset {
+    pub vault_mint: Pubkey,
+    /// Owned by `Config::vault_authority`; receives this asset's deposits.
+    pub vault_token_account: Pubkey,
+    /// Owned by the redeem vault authority PDA; pays this asset's redemptions.
+    pub redeem_vault_token_account: Pubkey,
+    pub decimals: u8,
+    /// minted = deposited * rate_numerator / rate_denominator, adjusted for decimals.
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub bump: u8,
+}
+
+impl VaultAsset {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 1 + 8 + 8 + 1;
+
+    /// Converts an amount of this asset into mint tokens with `mint_decimals` decimals.
+    pub fn vault_to_mint_amount(
+        &self,
+        amount: u64,
+        mint_decimals: u8,
+        rounding: Rounding,
+    ) -> Result<u64> {
+        let (numerator, denominator) = self.deposit_ratio(mint_decimals)?;
+        let minted = mul_div_u128(amount as u128, numerator, denominator, rounding)
+            .map_err(|_| CustomErrorCode::InvalidDecimalScaling)?;
+        u64::try_from(minted).map_err(|_| CustomErrorCode::InvalidDecimalScaling.into())
+    }
+
+    /// Converts mint tokens with `mint_decimals` decimals back into an amount of this asset.
+    pub fn mint_to_vault_amount(
+        &self,
+        amount: u64,
+        mint_decimals: u8,
+        rounding: Rounding,
+    ) -> Result<u64> {
+        let (numerator, denominator) = self.deposit_ratio(mint_decimals)?;
+        let returned = mul_div_u128(amount as u128, denominator, numerator, rounding)
+            .map_err(|_| CustomErrorCode::InvalidDecimalScaling)?;
+        u64::try_from(returned).map_err(|_| CustomErrorCode::InvalidDecimalScaling.into())
+    }
+
+    fn deposit_ratio(&self, mint_decimals: u8) -> Result<(u128, u128)> {
+        require!(
+            self.rate_numerator > 0 && self.rate_denominator > 0,
+            CustomErrorCode::InvalidExchangeRate
+        );
+        let mut numerator = self.rate_numerator as u128;
+        let mut denominator = self.rate_denominator as u128;
+        if mint_decimals >= self.decimals {
+            numerator = numerator
+                .checked_mul(decimal_factor(mint_decimals - self.decimals)?)
+                .ok_or(CustomErrorCode::InvalidDecimalScaling)?;
+        } else {
+            denominator = denominator
+                .checked_mul(decimal_factor(self.decimals - mint_decimals)?)
+                .ok_or(CustomErrorCode::InvalidDecimalScaling)?;
+        }
+        Ok((numerator, denominator))
+    }
+}
+
+/// Caps how much wYLDS an external mint caller program may mint per window, so an exploited
+/// partner program cannot mint without bound. The window restarts with the first mint after it
+/// has elapsed.
+#[account]
+pub struct MintCallerQuota {
+    pub caller: Pubkey,
+    pub max_per_window: u64,
+    pub window_seconds: i64,
+    pub minted_in_window: u64,
+    pub window_start_ts: i64,
+    pub bump: u8,
+}
+
+impl MintCallerQuota {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1;
+
+    /// Counts `amount` against the current window, starting a new window first if the last
+    /// one has elapsed.
+    pub fn record_mint(&mut self, amount: u64, now: i64) -> Result<()> {
+        if now >= self.window_start_ts.saturating_add(self.window_seconds) {
+            self.window_start_ts = now;
+            self.minted_in_window = 0;
+        }
+        let minted = self
+            .minted_in_window
+            .checked_add(amount)
+            .ok_or(CustomErrorCode::MintQuotaExceeded)?;
+        require!(
+            minted <= self.max_per_window,
+            CustomErrorCode::MintQuotaExceeded
+        );
+        self.minted_in_window = minted;
+        Ok(())
+    }
+}
+
+/// Maximum number of token accounts `freeze_token_accounts_batch` accepts in one call,
+/// bounded by compute.
+pub const MAX_FREEZE_BATCH: usize = 16;
+
+/// Maximum number of epochs `claim_rewards_multi` accepts in one call, bounded by compute.
+pub const MAX_EPOCH_CLAIMS: usize = 8;
+
+/// Maximum number of redemption requests `reclaim_abandoned_redemptions` closes in one call,
+/// bounded by transaction size (two accounts per request).
+pub const MAX_RECLAIM_BATCH: usize = 8;
+
+/// Maximum number of redemption requests `complete_redeem_batch` settles in one call, bounded by
+/// transaction size (five accounts per request).
+pub const MAX_REDEEM_BATCH: usize = 4;
+
+/// Hard ceiling on Merkle proof length, whatever an epoch's `tree_depth`, so an oversized proof
+/// is rejected before any hashing instead of exhausting compute. 32 levels covers 2^32 leaves.
+pub const MAX_PROOF_DEPTH: usize = 32;
+
+/// Maximum number of recipients `airdrop` accepts in one call, bounded by transaction size
+/// (one token account and 40 bytes of instruction data per recipient).
+pub const MAX_AIRDROP_BATCH: usize = 10;
+
+/// One recipient of an `airdrop`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AirdropRecipient {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// One epoch's allocation in a `claim_rewards_multi` batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EpochClaim {
+    pub epoch_index: u64,
+    pub amount: u64,
+    pub proof: Vec<ProofNode>,
 }
 
 /// One Merkle proof element.
@@ -53,3 +959,223 @@ pub struct ProofNode {
     pub sibling: [u8; 32],
     pub is_left: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A zeroed config sized for `max_administrators`, with the discriminator in place
+    fn zeroed_config() -> Config {
+        let mut data = vec![0u8; Config::space(DEFAULT_MAX_ADMINISTRATORS)];
+        data[..8].copy_from_slice(Config::DISCRIMINATOR);
+        Config::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    // Length prefixes for `admins` entries in every administrator list, elsewhere zero
+    fn layout_with_admins(admins: u8) -> Vec<u8> {
+        let mut data = vec![0u8; 4096];
+        let count = (admins as u32).to_le_bytes();
+        let mut offset = 8 + 32 + 32;
+        data[offset..offset + 4].copy_from_slice(&count);
+        offset += 4 + 32 * admins as usize;
+        data[offset..offset + 4].copy_from_slice(&count);
+        let offset = Config::serialized_len(&data, 0).unwrap() + CONFIG_TAIL_LEN;
+        data[offset..offset + 4].copy_from_slice(&count);
+        data
+    }
+
+    #[test]
+    fn serialized_len_matches_space_for_the_current_version() {
+        for admins in 0..=MAX_ADMINISTRATORS_CAP {
+            let data = layout_with_admins(admins);
+            assert_eq!(
+                Config::serialized_len(&data, CONFIG_VERSION).unwrap(),
+                Config::space(admins)
+            );
+        }
+    }
+
+    #[test]
+    fn the_current_layout_never_has_the_legacy_size() {
+        for admins in 0..=MAX_ADMINISTRATORS_CAP {
+            let data = layout_with_admins(admins);
+            assert_ne!(
+                Config::serialized_len(&data, CONFIG_VERSION).unwrap(),
+                LEGACY_CONFIG_LEN
+            );
+        }
+    }
+
+    fn epoch_with_deadline(claim_deadline_ts: i64, claim_deadline_slot: u64) -> RewardsEpoch {
+        RewardsEpoch {
+            index: 0,
+            merkle_root: [0; 32],
+            total: 0,
+            created_ts: 0,
+            claimed: 0,
+            claim_deadline_ts,
+            merkle_mode: MerkleMode::Flagged,
+            tree_depth: 0,
+            swept: false,
+            frozen: false,
+            created_slot: 0,
+            claim_deadline_slot,
+            version: REWARDS_EPOCH_VERSION,
+        }
+    }
+
+    fn clock_at(slot: u64, unix_timestamp: i64) -> Clock {
+        Clock {
+            slot,
+            unix_timestamp,
+            ..Clock::default()
+        }
+    }
+
+    #[test]
+    fn claims_at_the_deadline_timestamp_are_still_open() {
+        let epoch = epoch_with_deadline(1_000, 0);
+        assert!(!epoch.deadline_passed(&clock_at(0, 999)));
+        assert!(!epoch.deadline_passed(&clock_at(0, 1_000)));
+        assert!(epoch.deadline_passed(&clock_at(0, 1_001)));
+    }
+
+    #[test]
+    fn claims_at_the_deadline_slot_are_still_open() {
+        // The slot deadline wins over the timestamp when both are set
+        let epoch = epoch_with_deadline(1, 50);
+        assert!(!epoch.deadline_passed(&clock_at(50, 1_000)));
+        assert!(epoch.deadline_passed(&clock_at(51, 0)));
+    }
+
+    #[test]
+    fn epochs_without_a_deadline_never_close() {
+        let epoch = epoch_with_deadline(0, 0);
+        assert!(!epoch.deadline_passed(&clock_at(u64::MAX, i64::MAX)));
+    }
+
+    #[test]
+    fn stale_bytes_behind_a_shrunk_list_are_not_counted() {
+        let mut config = zeroed_config();
+        config.version = CONFIG_VERSION;
+        config.mint_cap = 7;
+        config.price_feed_id = [9; 32];
+        config.redeem_queue_closed = [0xFF; (REDEEM_QUEUE_WINDOW / 8) as usize];
+        config.rewards_administrators = vec![Pubkey::new_unique(); 3];
+        let mut data = vec![0u8; Config::space(DEFAULT_MAX_ADMINISTRATORS)];
+        config.try_serialize(&mut &mut data[..]).unwrap();
+
+        config.rewards_administrators.truncate(1);
+        config.try_serialize(&mut &mut data[..]).unwrap();
+        let serialized = 8 + config.try_to_vec().unwrap().len();
+
+        assert_eq!(Config::stored_version(&data).unwrap(), CONFIG_VERSION);
+        assert_eq!(
+            Config::serialized_len(&data, CONFIG_VERSION).unwrap(),
+            serialized
+        );
+        assert!(data[serialized..].iter().any(|&byte| byte != 0));
+    }
+
+    #[test]
+    fn legacy_config_reads_appended_fields_as_zero_once_the_tail_is_cleared() {
+        // Original layout: two freeze administrators, then a shrink left stale bytes behind
+        let mut data = vec![0xAB; LEGACY_CONFIG_LEN];
+        data[..8].copy_from_slice(Config::DISCRIMINATOR);
+        data[8..40].fill(1); // vault
+        data[40..72].fill(2); // mint
+        data[72..76].copy_from_slice(&2u32.to_le_bytes());
+        data[76..140].fill(3);
+        data[140..144].copy_from_slice(&0u32.to_le_bytes());
+        data[144..208].fill(4); // vault_authority, redeem_vault
+        data[208] = 254; // bump
+        data[209] = 0; // paused
+
+        assert_eq!(Config::stored_version(&data).unwrap(), 0);
+        let serialized = Config::serialized_len(&data, 0).unwrap();
+        assert_eq!(serialized, 210);
+
+        data.resize(Config::space(MAX_ADMINISTRATORS_CAP), 0);
+        data[serialized..].fill(0);
+        let config = Config::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(config.vault, Pubkey::new_from_array([1; 32]));
+        assert_eq!(config.freeze_administrators.len(), 2);
+        assert!(config.rewards_administrators.is_empty());
+        assert_eq!(config.bump, 254);
+        assert_eq!(config.version, 0);
+        assert_eq!(config.rate_numerator, 0);
+        assert_eq!(config.mint_cap, 0);
+        assert_eq!(config.config_admin, Pubkey::default());
+        assert!(config.previous_rewards_administrators.is_empty());
+    }
+
+    #[test]
+    fn the_head_skips_requests_closed_behind_it() {
+        let mut config = zeroed_config();
+        config.redeem_queue_head = 5;
+        config.redeem_queue_tail = 9;
+
+        config.dequeue_redemption(6).unwrap();
+        config.dequeue_redemption(7).unwrap();
+        assert_eq!(config.redeem_queue_head, 5);
+        assert_eq!(config.next_open_queue_position(6), 8);
+
+        config.dequeue_redemption(5).unwrap();
+        assert_eq!(config.redeem_queue_head, 8);
+        assert!(config.redeem_queue_closed.iter().all(|byte| *byte == 0));
+
+        // The queue drains completely once the last open request closes
+        config.dequeue_redemption(8).unwrap();
+        assert_eq!(config.redeem_queue_head, config.redeem_queue_tail);
+    }
+
+    #[test]
+    fn moving_the_head_forgets_the_closed_positions_it_passes() {
+        let mut config = zeroed_config();
+        config.redeem_queue_tail = 4;
+        config.dequeue_redemption(1).unwrap();
+        config.dequeue_redemption(3).unwrap();
+
+        config.move_redeem_queue_head(2).unwrap();
+        assert_eq!(config.redeem_queue_head, 2);
+        config.move_redeem_queue_head(3).unwrap();
+        assert_eq!(config.redeem_queue_head, 4);
+        assert!(config.redeem_queue_closed.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn requests_beyond_the_window_cannot_be_closed_out_of_order() {
+        let mut config = zeroed_config();
+        config.redeem_queue_tail = REDEEM_QUEUE_WINDOW + 1;
+        assert!(config.can_dequeue_redemption(REDEEM_QUEUE_WINDOW - 1));
+        assert!(!config.can_dequeue_redemption(REDEEM_QUEUE_WINDOW));
+        assert!(config.dequeue_redemption(REDEEM_QUEUE_WINDOW).is_err());
+    }
+
+    #[test]
+    fn redemptions_draw_down_restaked_rewards_before_deposits() {
+        let mut position = UserPosition {
+            user: Pubkey::default(),
+            total_deposited: 100,
+            bump: 0,
+            last_deposit_ts: 0,
+            restaked_rewards: 30,
+        };
+
+        position.record_redemption(20);
+        assert_eq!(
+            (position.restaked_rewards, position.total_deposited),
+            (10, 100)
+        );
+        position.record_redemption(50);
+        assert_eq!(
+            (position.restaked_rewards, position.total_deposited),
+            (0, 60)
+        );
+        position.record_redemption(u64::MAX);
+        assert_eq!(
+            (position.restaked_rewards, position.total_deposited),
+            (0, 0)
+        );
+    }
+}
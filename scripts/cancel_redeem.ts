@@ -0,0 +1,63 @@
+import * as anchor from "@coral-xyz/anchor";
+import yargs from "yargs";
+import {Program} from "@coral-xyz/anchor";
+import {HastraSolVaultMint} from "../target/types/hastra_sol_vault_mint";
+import {getAssociatedTokenAddress} from "@solana/spl-token";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+const program = anchor.workspace.HastraSolVaultMint as Program<HastraSolVaultMint>;
+
+const args = yargs(process.argv.slice(2))
+    .option("mint", {
+        type: "string",
+        description: "The mint token (e.g. wYLDS) of the pending redemption.",
+        required: true,
+    })
+    .parseSync();
+
+const main = async () => {
+    const signer = provider.wallet.publicKey;
+
+    // Derive PDAs
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("config")],
+        program.programId
+    );
+
+    // Derive the redemption request PDA
+    const [redemptionRequestPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("redemption_request"), signer.toBuffer()],
+        program.programId
+    );
+
+    // Program args
+    const mint = new anchor.web3.PublicKey(args.mint);
+
+    // Get user's mint token account
+    const userMintTokenAccount = await getAssociatedTokenAddress(
+        mint,
+        signer,
+    );
+
+    console.log(`Signer: ${signer.toBase58()}`);
+    console.log(`Mint (e.g. wYLDS): ${mint.toBase58()}`);
+    console.log(`User Mint Token Account: ${userMintTokenAccount.toBase58()}`);
+    console.log(`Config PDA: ${configPda.toBase58()}`);
+    console.log(`Redemption Request PDA: ${redemptionRequestPda.toBase58()}`);
+
+    const tx = await program.methods
+        .cancelRedeem()
+        .accountsStrict({
+            signer: signer,
+            redemptionRequest: redemptionRequestPda,
+            userMintTokenAccount: userMintTokenAccount,
+            config: configPda,
+            tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        }).rpc();
+
+    console.log("Transaction:", tx);
+};
+
+main().catch(console.error);
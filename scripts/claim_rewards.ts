@@ -19,6 +19,12 @@ const args = yargs(process.argv.slice(2))
         description: "Epoch index",
         required: true,
     })
+    .option("sort_pairs", {
+        type: "boolean",
+        description: "Build the tree with sorted pairs (epoch merkle_mode = SortedPairs) instead of is_left flags",
+        required: false,
+        default: false,
+    })
     .option("reward_allocations", {
         type: "string",
         description: "Allocations object: {allocations: [{\"account\": \"3m7...sKf\", \"amount\": 1000}, ...]}",
@@ -34,13 +40,18 @@ const args = yargs(process.argv.slice(2))
         description: "Amount to claim from this epoch index",
         required: false,
     })
+    .option("rewards_vault_token_account", {
+        type: "string",
+        description: "Rewards vault token account, required when the program pays rewards from the vault",
+        required: false,
+    })
     .parseSync();
 
 const program: Program<HastraSolVaultMint> = new anchor.Program(idl as anchor.Idl, provider) as Program<HastraSolVaultMint>;
 
 const main = async () => {
     const epochIndex = args.epoch;
-    const { tree } = allocationsToMerkleTree(args.reward_allocations, epochIndex);
+    const { tree } = allocationsToMerkleTree(args.reward_allocations, epochIndex, args.sort_pairs);
 
     const leaf = makeLeaf(provider.wallet.publicKey, args.amount ?? 0, epochIndex);
 
@@ -85,6 +96,16 @@ const main = async () => {
         program.programId
     );
 
+    const [rewardsVaultAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("rewards_vault_authority")],
+        program.programId
+    );
+
+    const [blockedEntryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("blocked"), provider.wallet.publicKey.toBuffer()],
+        program.programId
+    );
+
     const mint = new anchor.web3.PublicKey(args.mint);
     // Calculate the Associated Token Account address
     const tokenAccount = getAssociatedTokenAddressSync(
@@ -100,8 +121,13 @@ const main = async () => {
             epoch: epochPda,
             claimRecord: claimPda,
             mintAuthority: mintAuthorityPda,
+            rewardsVaultAuthority: rewardsVaultAuthorityPda,
+            rewardsVaultTokenAccount: args.rewards_vault_token_account
+                ? new PublicKey(args.rewards_vault_token_account)
+                : null,
             mint: mint,
             userMintTokenAccount: tokenAccount,
+            blockedEntry: blockedEntryPda,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID
         })
@@ -31,6 +31,11 @@ const args = yargs(process.argv.slice(2))
         description: "Token account that will hold vaulted asset (e.g. USDC) used for redemptions.",
         required: true,
     })
+    .option("fill_amount", {
+        type: "number",
+        description: "Redeem only this much of the request, leaving the rest open. Defaults to the full request.",
+        required: false,
+    })
     .parseSync();
 
 const main = async () => {
@@ -51,14 +56,33 @@ const main = async () => {
         program.programId
     );
 
+    const [userPositionPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), user.toBuffer()],
+        program.programId
+    );
     const [redeemVaultAuthorityPda] = PublicKey.findProgramAddressSync(
         [Buffer.from("redeem_vault_authority")],
         program.programId
     );
 
+    // Either mint may live under Token-2022; the owning program of each mint account is its token program
+    const tokenProgram = (await provider.connection.getAccountInfo(mint)).owner;
+    const vaultTokenProgram = (await provider.connection.getAccountInfo(vaultMint)).owner;
+
     // Get token accounts
-    const userMintTokenAccount = await getAssociatedTokenAddress(mint, user);
-    const userVaultTokenAccount = await getAssociatedTokenAddress(vaultMint, user);
+    const userMintTokenAccount = await getAssociatedTokenAddress(mint, user, false, tokenProgram);
+    // Proceeds can only go to the destination fixed on the request
+    const userVaultTokenAccount = (await program.account.redemptionRequest.fetch(redemptionRequestPda)).destination;
+
+    // The fee vault is only validated when a redeem fee is configured
+    const config = await program.account.config.fetch(configPda);
+    const feeVaultTokenAccount = config.feeVault.equals(PublicKey.default)
+        ? redeemVaultTokenAccount
+        : config.feeVault;
+    // The oracle account is only passed when a price oracle is configured
+    const priceOracle = config.priceOracle.equals(PublicKey.default)
+        ? null
+        : config.priceOracle;
 
     console.log(`Admin:                         ${admin.toBase58()}`);
     console.log(`User:                          ${user.toBase58()}`);
@@ -68,12 +92,15 @@ const main = async () => {
     console.log(`Vault Mint:                    ${vaultMint.toBase58()}`);
     console.log(`Config PDA:                    ${configPda.toBase58()}`);
     console.log(`Redeem Vault Token Account:    ${redeemVaultTokenAccount.toBase58()}`);
+    console.log(`Fee Vault Token Account:       ${feeVaultTokenAccount.toBase58()}`);
     console.log(`Redemption Request PDA:        ${redemptionRequestPda.toBase58()}`);
     console.log(`Redeem Vault Authority PDA:    ${redeemVaultAuthorityPda.toBase58()}`);
-    console.log(`Token Program:                 ${anchor.utils.token.TOKEN_PROGRAM_ID.toBase58()}`);
+    console.log(`Token Program:                 ${tokenProgram.toBase58()}`);
+    console.log(`Vault Token Program:           ${vaultTokenProgram.toBase58()}`);
 
     const tx = await program.methods
-        .completeRedeem() // Amount is calculated in the function
+        // Without a fill amount, the amount is calculated in the function
+        .completeRedeem(args.fill_amount === undefined ? null : new anchor.BN(args.fill_amount))
         .accountsStrict({
             admin: admin,
             user: user,
@@ -81,10 +108,15 @@ const main = async () => {
             userVaultTokenAccount: userVaultTokenAccount,
             redemptionRequest: redemptionRequestPda,
             redeemVaultTokenAccount: redeemVaultTokenAccount,
+            feeVaultTokenAccount: feeVaultTokenAccount,
             redeemVaultAuthority: redeemVaultAuthorityPda,
             mint: mint,
+            vaultMint: vaultMint,
+            userPosition: userPositionPda,
+            priceOracle: priceOracle,
             config: configPda,
-            tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+            tokenProgram: tokenProgram,
+            vaultTokenProgram: vaultTokenProgram,
         })
         .rpc();
 
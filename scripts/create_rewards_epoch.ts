@@ -18,11 +18,23 @@ const args = yargs(process.argv.slice(2))
         description: "Epoch index",
         required: true,
     })
+    .option("sort_pairs", {
+        type: "boolean",
+        description: "Build the tree with sorted pairs (epoch merkle_mode = SortedPairs) instead of is_left flags",
+        required: false,
+        default: false,
+    })
     .option("reward_allocations", {
         type: "json",
         description: "Allocations object: {allocations: [{\"account\": \"3m7...sKf\", \"amount\": 1000}, ...]}",
         required: true,
     })
+    .option("claim_deadline_ts", {
+        type: "number",
+        description: "Unix timestamp after which claims are rejected (0 = no deadline)",
+        required: false,
+        default: 0,
+    })
     .option("just_print", {
         type: "boolean",
         description: "If true, just print the leaves and root without creating the epoch on-chain",
@@ -33,7 +45,7 @@ const args = yargs(process.argv.slice(2))
 
 const main = async () => {
     const epochIndex = args.epoch;
-    const { tree, leaves, allocations } = allocationsToMerkleTree(args.reward_allocations, epochIndex);
+    const { tree, leaves, allocations } = allocationsToMerkleTree(args.reward_allocations, epochIndex, args.sort_pairs);
     const root = tree.getRoot();
 
     if (args.just_print) {
@@ -61,7 +73,14 @@ const main = async () => {
     );
 
     const tx = await program.methods
-        .createRewardsEpoch(new anchor.BN(epochIndex), Array.from(root), total)
+        .createRewardsEpoch(
+            new anchor.BN(epochIndex),
+            Array.from(root),
+            total,
+            new anchor.BN(args.claim_deadline_ts),
+            args.sort_pairs ? {sortedPairs: {}} : {flagged: {}},
+            tree.getDepth(),
+        )
         .accountsStrict({
             config: configPda,
             admin: provider.wallet.publicKey,
@@ -8,14 +8,35 @@ export const idl = require("../target/idl/hastra_sol_vault_mint.json");
 export const ZERO32 = Buffer.alloc(0);
 export const sha256 = (x: Buffer) => createHash("sha256").update(x).digest();
 
+// Domain separation prefixes; must match MERKLE_LEAF_PREFIX / MERKLE_NODE_PREFIX on-chain
+export const LEAF_PREFIX = Buffer.from([0x00]);
+export const NODE_PREFIX = Buffer.from([0x01]);
+
+// Internal node hash: sha256(0x01 || left || right)
+export const hashNode = (x: Buffer) => sha256(Buffer.concat([NODE_PREFIX, x]));
+
 export const makeLeaf = (user: PublicKey, amount: anchor.BN | number, epoch: number): Buffer => {
     return sha256(Buffer.concat([
+        LEAF_PREFIX,
         user.toBuffer(),
         (anchor.BN.isBN(amount) ? amount : new anchor.BN(amount)).toArrayLike(Buffer, "le", 8),
         new anchor.BN(epoch).toArrayLike(Buffer, "le", 8),
     ]));
 }
 
+// Fixed 32-byte memo for deposit / request_redeem; all zeros means none
+export const encodeMemo = (memo?: string): number[] => {
+    const bytes = Buffer.alloc(32);
+    if (memo) {
+        const encoded = Buffer.from(memo, "utf8");
+        if (encoded.length > 32) {
+            throw new Error("memo must be at most 32 bytes");
+        }
+        encoded.copy(bytes);
+    }
+    return Array.from(bytes);
+}
+
 export const nextPowerOf2Math = (n: number): number => {
     if (n <= 0) {
         return 1; // Or handle as an error
@@ -35,7 +56,7 @@ export const padToPowerOfTwo = (leaves: Buffer<ArrayBufferLike>[])=> {
     return padded;
 }
 
-export const allocationsToMerkleTree = (allocationString: string, epochIndex: number) => {
+export const allocationsToMerkleTree = (allocationString: string, epochIndex: number, sortPairs: boolean = false) => {
     const allocations: {user: PublicKey, amount: anchor.BN}[] = (JSON.parse(allocationString).allocations as {account: string, amount: number}[]).map((a: {account: string, amount: number}) => {
         return {user: new PublicKey(a.account), amount: new anchor.BN(a.amount)};
     });
@@ -50,8 +71,8 @@ export const allocationsToMerkleTree = (allocationString: string, epochIndex: nu
         console.log(`${i}: ${leaf.toString("hex")}`);
     });
 
-    const tree = new MerkleTree(leaves, sha256, {
-        sortPairs: false,
+    const tree = new MerkleTree(leaves, hashNode, {
+        sortPairs,
     });
 
     console.log(`\nTree:`);
@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import yargs from "yargs";
 import {Program} from "@coral-xyz/anchor";
 import {HastraSolVaultMint} from "../target/types/hastra_sol_vault_mint";
+import {encodeMemo} from "./cryptolib";
 
 const provider = anchor.AnchorProvider.env();
 anchor.setProvider(provider);
@@ -34,6 +35,16 @@ const args = yargs(process.argv.slice(2))
         description: "User's mint token account address where the minted tokens will be sent to. Must be associated token account for the mint token (e.g. wYLDS)",
         required: true,
     })
+    .option("fee_vault_token_account", {
+        type: "string",
+        description: "Fee vault token account configured with set_deposit_fee. Defaults to the vault token account when no deposit fee is configured",
+        required: false,
+    })
+    .option("memo", {
+        type: "string",
+        description: "Optional reference (up to 32 bytes UTF-8) recorded in the emitted event for reconciliation",
+        required: false,
+    })
     .parseSync();
 
 const main = async () => {
@@ -50,32 +61,71 @@ const main = async () => {
         program.programId
     );
 
+    // Only passed when it exists, i.e. the signer is allowlisted
+    const [allowlistEntryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("allow"), signer.toBuffer()],
+        program.programId
+    );
+    const allowlistEntry = await provider.connection.getAccountInfo(allowlistEntryPda)
+        ? allowlistEntryPda
+        : null;
+
+    const [userPositionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), signer.toBuffer()],
+        program.programId
+    );
+    const [blockedEntryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("blocked"), signer.toBuffer()],
+        program.programId
+    );
+
     // Program args
     const mint = new anchor.web3.PublicKey(args.mint);
     const amount = new anchor.BN(args.amount);
+    const memo = encodeMemo(args.memo);
     const vaultTokenAccount = new anchor.web3.PublicKey(args.vault_token_account);
     const userVaultTokenAccount = new anchor.web3.PublicKey(args.user_vault_token_account);
     const userMintTokenAccount = new anchor.web3.PublicKey(args.user_mint_token_account);
+    const feeVaultTokenAccount = args.fee_vault_token_account
+        ? new anchor.web3.PublicKey(args.fee_vault_token_account)
+        : vaultTokenAccount;
+
+    // Either mint may live under Token-2022; the owning program of each mint account is its token program
+    const config = await program.account.config.fetch(configPda);
+    const vaultMint = config.vault;
+    const tokenProgram = (await provider.connection.getAccountInfo(mint)).owner;
+    const vaultTokenProgram = (await provider.connection.getAccountInfo(vaultMint)).owner;
 
     console.log("Mint (token to be minted e.g. wYLDS)", mint.toBase58());
     console.log("Amount:", amount.toString());
     console.log("Vault Token Account (e.g. USDC)", vaultTokenAccount.toBase58());
     console.log("User Vault Token Account:", userVaultTokenAccount.toBase58());
     console.log("User Mint Token Account:", userMintTokenAccount.toBase58());
+    console.log("Fee Vault Token Account:", feeVaultTokenAccount.toBase58());
     console.log("Config PDA:", configPda.toBase58());
     console.log("Mint Authority PDA:", mintAuthorityPda.toBase58());
+    console.log("Vault Mint:", vaultMint.toBase58());
+    console.log("Token Program:", tokenProgram.toBase58());
+    console.log("Vault Token Program:", vaultTokenProgram.toBase58());
 
     const tx = await program.methods
-        .deposit(amount)
+        .deposit(amount, memo)
         .accountsStrict({
             config: configPda,
             vaultTokenAccount: vaultTokenAccount,
+            vaultMint: vaultMint,
             mint: mint,
             mintAuthority: mintAuthorityPda,
             signer: signer,
             userVaultTokenAccount: userVaultTokenAccount,
+            feeVaultTokenAccount: feeVaultTokenAccount,
             userMintTokenAccount: userMintTokenAccount,
-            tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID
+            allowlistEntry: allowlistEntry,
+            blockedEntry: blockedEntryPda,
+            userPosition: userPositionPda,
+            tokenProgram: tokenProgram,
+            vaultTokenProgram: vaultTokenProgram,
+            systemProgram: anchor.web3.SystemProgram.programId,
         }).rpc();
 
     console.log("Transaction:", tx);
@@ -88,6 +88,10 @@ const main = async () => {
         throw new Error(`Number of rewards administrators (${rewardsAdministrators.length}) exceeds maximum 5`);
     }
 
+    // Either mint may live under Token-2022; the owning program of each mint account is its token program
+    const tokenProgram = (await provider.connection.getAccountInfo(mint)).owner;
+    const vaultTokenProgram = (await provider.connection.getAccountInfo(vault)).owner;
+
     console.log("Program ID:", program.programId.toBase58());
     console.log("Vault (accepted token):", vault.toBase58());
     console.log("Mint (token to be minted):", mint.toBase58());
@@ -111,6 +115,8 @@ const main = async () => {
             redeemVaultTokenAccount: redeemVaultTokenAccount,
             mint: mint,
             programData: programData,
+            tokenProgram: tokenProgram,
+            vaultTokenProgram: vaultTokenProgram,
         }).rpc()
         .then((tx) => {
             console.log("Transaction:", tx);
@@ -0,0 +1,68 @@
+import * as anchor from "@coral-xyz/anchor";
+import yargs from "yargs";
+import { Program } from "@coral-xyz/anchor";
+import { HastraSolVaultMint } from "../target/types/hastra_sol_vault_mint";
+import {
+    PublicKey,
+} from "@solana/web3.js";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+const program = anchor.workspace.HastraSolVaultMint as Program<HastraSolVaultMint>;
+
+const args = yargs(process.argv.slice(2))
+    .option("vault_mint", {
+        type: "string",
+        description: "The configured vault mint (e.g. USDC); its decimals are recorded for version 0 configs.",
+        required: true,
+    })
+    .option("mint", {
+        type: "string",
+        description: "The configured mint token (e.g. wYLDS); its decimals are recorded for version 0 configs.",
+        required: true,
+    })
+    .parseSync();
+
+const main = async () => {
+    const [configPda, bump] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("config")],
+        program.programId
+    );
+    // bpf_loader_upgradeable program id
+    const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+        "BPFLoaderUpgradeab1e11111111111111111111111"
+    );
+    // derive ProgramData PDA
+    const [programData] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        BPF_LOADER_UPGRADEABLE_ID
+    );
+
+    console.log("Program ID:", program.programId.toBase58());
+    console.log("Config PDA:", configPda.toBase58());
+
+    // Run after upgrading the program; grows the config account and stamps the layout version
+    await program.methods
+        .migrateConfig()
+        .accounts({
+            programData: programData,
+            vaultMint: new PublicKey(args.vault_mint),
+            mint: new PublicKey(args.mint),
+        })
+        .rpc()
+        .then((tx) => {
+            console.log("Transaction:", tx);
+        })
+        .catch(
+            (err) => {
+                if (err.getLogs) {
+                    console.dir(err.getLogs);
+                }
+                console.error("Transaction failed:", err);
+                throw err;
+            }
+        )
+};
+
+main().catch(console.error);
@@ -15,7 +15,12 @@ const args = yargs(process.argv.slice(2))
     .option("pause", {
         type: "boolean",
         description: "Set to true to pause the program, false to unpause",
-        required: true,
+        required: false,
+    })
+    .option("until", {
+        type: "number",
+        description: "Pause until this unix timestamp instead; 0 clears a timed pause. Takes precedence over --pause",
+        required: false,
     })
     .parseSync();
 
@@ -37,9 +42,13 @@ const main = async () => {
     console.log("Program ID:", program.programId.toBase58());
     console.log("Config PDA:", configPda.toBase58());
 
-    // Call initialize
-    await program.methods
-        .pause(args.pause)
+    const method = args.until !== undefined
+        ? program.methods.pauseUntil(new anchor.BN(args.until))
+        : args.pause
+            ? program.methods.pause()
+            : program.methods.unpause();
+
+    await method
         .accounts({
             programData: programData,
         })
@@ -4,6 +4,7 @@ import {Program} from "@coral-xyz/anchor";
 import {HastraSolVaultMint} from "../target/types/hastra_sol_vault_mint";
 import {PublicKey, SystemProgram} from "@solana/web3.js";
 import {getAssociatedTokenAddress} from "@solana/spl-token";
+import {encodeMemo} from "./cryptolib";
 
 const provider = anchor.AnchorProvider.env();
 anchor.setProvider(provider);
@@ -21,6 +22,22 @@ const args = yargs(process.argv.slice(2))
         description: "The mint token that will be burned (e.g. wYLDS) at redeem.",
         required: true,
     })
+    .option("min_usdc_out", {
+        type: "number",
+        description: "Minimum vault tokens (e.g. USDC) to accept when the redeem completes, net of fees. 0 disables the check.",
+        required: false,
+        default: 0,
+    })
+    .option("destination", {
+        type: "string",
+        description: "Vault token account (e.g. USDC) that receives the proceeds. Defaults to the signer's ATA for the vault mint.",
+        required: false,
+    })
+    .option("memo", {
+        type: "string",
+        description: "Optional reference (up to 32 bytes UTF-8) recorded in the emitted event for reconciliation",
+        required: false,
+    })
     .parseSync();
 
 const main = async () => {
@@ -45,6 +62,15 @@ const main = async () => {
         program.programId
     );
 
+    const [blockedEntryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("blocked"), signer.toBuffer()],
+        program.programId
+    );
+
+    const config = await program.account.config.fetch(configPda);
+    const destination = args.destination ? new PublicKey(args.destination) : null;
+    const memo = encodeMemo(args.memo);
+
     // Get user's mint token account
     const userMintTokenAccount = await getAssociatedTokenAddress(
         mint,
@@ -60,16 +86,18 @@ const main = async () => {
     console.log(`Redeem Vault Authority PDA: ${redeemVaultAuthorityPda.toBase58()}`);
 
     const tx = await program.methods
-        .requestRedeem(new anchor.BN(args.amount))
+        .requestRedeem(new anchor.BN(args.amount), new anchor.BN(args.min_usdc_out), destination, memo)
         .accountsStrict({
             signer: signer,
             userMintTokenAccount: userMintTokenAccount,
             redemptionRequest: redemptionRequestPda,
             mint: mint,
+            vaultMint: config.vault,
             config: configPda,
             systemProgram: SystemProgram.programId,
             tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-            redeemVaultAuthority: redeemVaultAuthorityPda
+            redeemVaultAuthority: redeemVaultAuthorityPda,
+            blockedEntry: blockedEntryPda,
         }).rpc();
 
     console.log("Transaction:", tx);
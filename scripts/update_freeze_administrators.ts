@@ -19,14 +19,16 @@ const args = yargs(process.argv.slice(2))
 
 const main = async () => {
     const freezeAdministrators: PublicKey[] = (args.freeze_administrators.split(",")).map((s: string) => new anchor.web3.PublicKey(s));
-    if(freezeAdministrators.length > 5) {
-        throw new Error(`Number of freeze administrators (${freezeAdministrators.length}) exceeds maximum 5`);
-    }
 
     const [configPda, bump] = PublicKey.findProgramAddressSync([
         Buffer.from("config")
     ], program.programId);
 
+    const { maxAdministrators } = await program.account.config.fetch(configPda);
+    if(freezeAdministrators.length > maxAdministrators) {
+        throw new Error(`Number of freeze administrators (${freezeAdministrators.length}) exceeds maximum ${maxAdministrators}`);
+    }
+
     // bpf_loader_upgradeable program id
     const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
         "BPFLoaderUpgradeab1e11111111111111111111111"
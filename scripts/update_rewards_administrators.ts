@@ -19,14 +19,16 @@ const args = yargs(process.argv.slice(2))
 
 const main = async () => {
     const rewardsAdministrators: PublicKey[] = (args.rewards_administrators.split(",")).map((s: string) => new anchor.web3.PublicKey(s));
-    if(rewardsAdministrators.length > 5) {
-        throw new Error(`Number of rewards administrators (${rewardsAdministrators.length}) exceeds maximum 5`);
-    }
 
     const [configPda, bump] = PublicKey.findProgramAddressSync([
         Buffer.from("config")
     ], program.programId);
 
+    const { maxAdministrators } = await program.account.config.fetch(configPda);
+    if(rewardsAdministrators.length > maxAdministrators) {
+        throw new Error(`Number of rewards administrators (${rewardsAdministrators.length}) exceeds maximum ${maxAdministrators}`);
+    }
+
     // bpf_loader_upgradeable program id
     const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
         "BPFLoaderUpgradeab1e11111111111111111111111"
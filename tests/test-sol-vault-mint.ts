@@ -6,9 +6,15 @@ import {
     createAccount,
     mintTo,
     getAccount,
+    getMint,
+    approve,
+    transfer,
+    setAuthority,
+    AuthorityType,
     TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { assert } from "chai";
+import * as crypto from "crypto";
 import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
 
 describe("sol-vault-mint", () => {
@@ -32,6 +38,70 @@ describe("sol-vault-mint", () => {
     const freezeAdmin = Keypair.generate();
     const rewardsAdmin = Keypair.generate();
 
+    const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+        "BPFLoaderUpgradeab1e11111111111111111111111"
+    );
+    const getProgramData = (): PublicKey =>
+        PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            BPF_LOADER_UPGRADEABLE_ID
+        )[0];
+
+    const deposit = (amount: anchor.BN) =>
+        program.methods
+            .deposit(amount, new Array(32).fill(0))
+            .accounts({
+                vaultTokenAccount: vaultTokenAccount,
+                mint: mintToken,
+                signer: user.publicKey,
+                userVaultTokenAccount: userVaultTokenAccount,
+                feeVaultTokenAccount: vaultTokenAccount,
+                userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                vaultMint: vaultMint,
+                vaultTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+    // Leaf of a rewards Merkle tree: sha256(0x00 || user || amount LE || epoch LE)
+    const rewardsLeaf = (recipient: PublicKey, amount: anchor.BN, epochIndex: anchor.BN): Buffer =>
+        crypto.createHash("sha256").update(Buffer.concat([
+            Buffer.from([0x00]), // leaf domain separation prefix
+            recipient.toBuffer(),
+            amount.toArrayLike(Buffer, "le", 8),
+            epochIndex.toArrayLike(Buffer, "le", 8)
+        ])).digest();
+
+    // Posts an epoch whose only leaf pays `amount` to the test user, so claims take an empty
+    // proof, and returns the epoch PDA
+    const createSingleLeafEpoch = async (epochIndex: anchor.BN, amount: anchor.BN): Promise<PublicKey> => {
+        const leafHash = rewardsLeaf(user.publicKey, amount, epochIndex);
+        await program.methods
+            .createRewardsEpoch(epochIndex, Array.from(leafHash), amount, new anchor.BN(0), { flagged: {} }, 0)
+            .accounts({ admin: rewardsAdmin.publicKey })
+            .signers([rewardsAdmin])
+            .rpc();
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    };
+
+    // Runs `body` with a config setting applied and puts `reset` back even when it fails
+    const withConfigSetting = async <T>(
+        setter: (value: T) => Promise<unknown>,
+        value: T,
+        reset: T,
+        body: () => Promise<void>
+    ) => {
+        await setter(value);
+        try {
+            await body();
+        } finally {
+            await setter(reset);
+        }
+    };
+
     before(async () => {
         // Airdrop SOL to test accounts
         await provider.connection.requestAirdrop(freezeAdmin.publicKey, 1000000000);
@@ -46,12 +116,12 @@ describe("sol-vault-mint", () => {
             6
         );
 
-        // Create mint token (wYLDS)
+        // Create mint token (wYLDS); initialize hands its mint and freeze authorities to the PDAs
         mintToken = await createMint(
             provider.connection,
             user.payer,
             user.publicKey,
-            null,
+            user.publicKey,
             6
         );
 
@@ -122,6 +192,8 @@ describe("sol-vault-mint", () => {
                 vaultMint: vaultMint,
                 mint: mintToken,
                 signer: user.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                vaultTokenProgram: TOKEN_PROGRAM_ID,
             })
             .rpc();
 
@@ -131,19 +203,127 @@ describe("sol-vault-mint", () => {
         assert.equal(config.mint.toBase58(), mintToken.toBase58());
         assert.equal(config.freezeAdministrators.length, 1);
         assert.equal(config.rewardsAdministrators.length, 1);
+        assert.equal(config.version, 1);
+        assert.equal(config.maxAdministrators, 5);
+
+        const mintInfo = await getMint(provider.connection, mintToken);
+        assert.equal(mintInfo.mintAuthority.toBase58(), mintAuthorityPda.toBase58());
+        assert.equal(mintInfo.freezeAuthority.toBase58(), freezeAuthorityPda.toBase58());
+    });
+
+    it("Rejects a second initialize", async () => {
+        try {
+            await program.methods
+                .initialize(
+                    vaultMint,
+                    mintToken,
+                    [freezeAdmin.publicKey],
+                    [rewardsAdmin.publicKey]
+                )
+                .accounts({
+                    vaultTokenAccount: vaultTokenAccount,
+                    vaultMint: vaultMint,
+                    mint: mintToken,
+                    signer: user.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    vaultTokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            assert.fail("Should have failed because the config already exists");
+        } catch (error) {
+            assert.include(error.toString(), "AlreadyInitialized");
+        }
+    });
+
+    it("Migrating a current config leaves it unchanged", async () => {
+        const before = await provider.connection.getAccountInfo(configPda);
+
+        await program.methods
+            .migrateConfig()
+            .accounts({
+                programData: getProgramData(),
+                vaultMint: vaultMint,
+                mint: mintToken,
+                signer: user.publicKey,
+            })
+            .rpc();
+
+        const after = await provider.connection.getAccountInfo(configPda);
+        assert.equal(after.data.length, before.data.length);
+        assert.equal((await program.account.config.fetch(configPda)).version, 1);
+    });
+
+    it("Migrating after an administrator list shrank ignores the stale bytes it left", async () => {
+        const setFreezeAdministrators = (administrators: PublicKey[]) =>
+            program.methods
+                .updateFreezeAdministrators(administrators)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        // Shrinking the list shifts every later field forward, leaving a copy of their old
+        // bytes behind the serialized data
+        await setFreezeAdministrators([freezeAdmin.publicKey, Keypair.generate().publicKey]);
+        await setFreezeAdministrators([freezeAdmin.publicKey]);
+        const before = await program.account.config.fetch(configPda);
+
+        await program.methods
+            .migrateConfig()
+            .accounts({
+                programData: getProgramData(),
+                vaultMint: vaultMint,
+                mint: mintToken,
+                signer: user.publicKey,
+            })
+            .rpc();
+
+        const after = await program.account.config.fetch(configPda);
+        assert.equal(JSON.stringify(after), JSON.stringify(before));
+    });
+
+    it("Reports the config through get_config", async () => {
+        const {events} = await program.methods
+            .getConfig()
+            .accounts({ mint: mintToken })
+            .simulate();
+        const snapshot = events.find((e) => e.name === "configSnapshot").data;
+        assert.equal(snapshot.version, 1);
+        assert.equal(snapshot.vault.toBase58(), vaultMint.toBase58());
+        assert.equal(snapshot.mint.toBase58(), mintToken.toBase58());
+        assert.isFalse(snapshot.paused);
+        assert.equal(snapshot.freezeAdministrators, 1);
+        assert.equal(snapshot.rewardsAdministrators, 1);
+    });
+
+    it("Emits a heartbeat", async () => {
+        const {events} = await program.methods
+            .heartbeat()
+            .accounts({ mint: mintToken })
+            .simulate();
+        const heartbeat = events.find((e) => e.name === "heartbeat").data;
+        assert.isFalse(heartbeat.paused);
+        assert.isAbove(heartbeat.slot.toNumber(), 0);
+        assert.equal(heartbeat.mintSupply.toString(), "0");
     });
 
     it("Deposits vault tokens and mints wYLDS tokens", async () => {
         const depositAmount = new anchor.BN(100000); // 0.1 token
 
         const tx = await program.methods
-            .deposit(depositAmount)
+            .deposit(depositAmount, new Array(32).fill(0))
             .accounts({
                 vaultTokenAccount: vaultTokenAccount,
                 mint: mintToken,
                 signer: user.publicKey,
                 userVaultTokenAccount: userVaultTokenAccount,
+                feeVaultTokenAccount: vaultTokenAccount,
                 userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                vaultMint: vaultMint,
+                vaultTokenProgram: TOKEN_PROGRAM_ID,
             })
             .rpc();
 
@@ -155,6 +335,140 @@ describe("sol-vault-mint", () => {
         assert.equal(userMintAccount.amount.toString(), depositAmount.toString());
     });
 
+    describe("deposit limits", () => {
+        const minDeposit = new anchor.BN(1000);
+        const maxDeposit = new anchor.BN(20000);
+
+        before(async () => {
+            await program.methods
+                .setDepositLimits(minDeposit, maxDeposit)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+        });
+
+        after(async () => {
+            await program.methods
+                .setDepositLimits(new anchor.BN(0), new anchor.BN(0))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+        });
+
+        it("Rejects a deposit one unit below the minimum", async () => {
+            try {
+                await deposit(minDeposit.subn(1));
+                assert.fail("Should have failed due to deposit below minimum");
+            } catch (error) {
+                assert.include(error.toString(), "DepositBelowMinimum");
+            }
+        });
+
+        it("Accepts a deposit exactly at the minimum", async () => {
+            const before = await getAccount(provider.connection, vaultTokenAccount);
+            await deposit(minDeposit);
+            const after = await getAccount(provider.connection, vaultTokenAccount);
+            assert.equal((after.amount - before.amount).toString(), minDeposit.toString());
+        });
+
+        it("Accepts a deposit exactly at the maximum", async () => {
+            const before = await getAccount(provider.connection, vaultTokenAccount);
+            await deposit(maxDeposit);
+            const after = await getAccount(provider.connection, vaultTokenAccount);
+            assert.equal((after.amount - before.amount).toString(), maxDeposit.toString());
+        });
+
+        it("Rejects a deposit one unit above the maximum", async () => {
+            try {
+                await deposit(maxDeposit.addn(1));
+                assert.fail("Should have failed due to deposit above maximum");
+            } catch (error) {
+                assert.include(error.toString(), "DepositAboveMaximum");
+            }
+        });
+    });
+
+    it("Tracks deposits per user and enforces the per-user cap", async () => {
+        const [positionPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), user.publicKey.toBuffer()],
+            program.programId
+        );
+        const setCap = (cap: anchor.BN) =>
+            program.methods
+                .setPerUserDepositCap(cap)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        // 100_000 + the two limit deposits above
+        const position = await program.account.userPosition.fetch(positionPda);
+        assert.equal(position.totalDeposited.toString(), "121000");
+
+        await setCap(position.totalDeposited.addn(500));
+        try {
+            await deposit(new anchor.BN(501));
+            assert.fail("Should have failed due to the per-user deposit cap");
+        } catch (error) {
+            assert.include(error.toString(), "UserDepositCapExceeded");
+        } finally {
+            await setCap(new anchor.BN(0));
+        }
+    });
+
+    it("Enforces the cooldown between a user's deposits", async () => {
+        const setCooldown = (seconds: number) =>
+            program.methods
+                .setDepositCooldown(new anchor.BN(seconds))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        // The user deposited moments ago in the tests above
+        await setCooldown(3600);
+        try {
+            await deposit(new anchor.BN(1000));
+            assert.fail("Should have failed due to the deposit cooldown");
+        } catch (error) {
+            assert.include(error.toString(), "DepositCooldownActive");
+        } finally {
+            await setCooldown(0);
+        }
+    });
+
+    it("Pauses deposits until a timestamp", async () => {
+        const pauseUntil = (ts: number) =>
+            program.methods
+                .pauseUntil(new anchor.BN(ts))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        await pauseUntil(Math.floor(Date.now() / 1000) + 3600);
+        try {
+            await deposit(new anchor.BN(1000));
+            assert.fail("Should have failed due to the timed pause");
+        } catch (error) {
+            assert.include(error.toString(), "ProgramPaused");
+        } finally {
+            await pauseUntil(0);
+        }
+
+        // A timestamp in the past no longer pauses the program
+        await pauseUntil(1);
+        await deposit(new anchor.BN(1000));
+        await pauseUntil(0);
+    });
+
     it("Redeems wYLDS tokens for vault tokens", async () => {
         const redeemAmount = new anchor.BN(50000); // 0.05 token
 
@@ -177,210 +491,2689 @@ describe("sol-vault-mint", () => {
         assert.equal(userMintAccount.amount.toString(), "50000"); // 100000 - 50000
     });
 
-    it("Creates rewards epoch", async () => {
-        const epochIndex = new anchor.BN(1);
-        const merkleRoot = Array(32).fill(0); // Mock merkle root
-        const totalRewards = new anchor.BN(10000);
+    describe("redemption requests", () => {
+        let redeemVaultAuthorityPda: PublicKey;
+        let redeemVaultTokenAccount: PublicKey;
+        let redemptionRequestPda: PublicKey;
 
-        const [epochPda] = PublicKey.findProgramAddressSync(
-            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
-            program.programId
-        );
+        before(async () => {
+            [redeemVaultAuthorityPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("redeem_vault_authority")],
+                program.programId
+            );
+            [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("redemption_request"), user.publicKey.toBuffer()],
+                program.programId
+            );
 
-        const tx = await program.methods
-            .createRewardsEpoch(epochIndex, merkleRoot, totalRewards)
-            .accounts({
-                admin: rewardsAdmin.publicKey,
-            })
-            .signers([rewardsAdmin])
-            .rpc();
+            // Redeem vault holds USDC owned by the redeem vault authority PDA
+            redeemVaultTokenAccount = await createAccount(
+                provider.connection,
+                user.payer,
+                vaultMint,
+                redeemVaultAuthorityPda,
+                Keypair.generate()
+            );
+            await mintTo(
+                provider.connection,
+                user.payer,
+                vaultMint,
+                redeemVaultTokenAccount,
+                user.publicKey,
+                1000000
+            );
 
-        // Verify epoch was created
-        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
-        assert.equal(epoch.index.toString(), epochIndex.toString());
-        assert.equal(epoch.total.toString(), totalRewards.toString());
-    });
+            // complete_redeem only pays out of the redeem vault recorded in the config
+            await program.methods
+                .setRedeemVault()
+                .accounts({
+                    redeemVaultTokenAccount: redeemVaultTokenAccount,
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
 
-    it("Fails unauthorized freeze attempt", async () => {
-        const unauthorizedUser = Keypair.generate();
+            // request_redeem requires a SOL buffer on the redeem vault authority PDA
+            const sig = await provider.connection.requestAirdrop(redeemVaultAuthorityPda, 1000000000);
+            await provider.connection.confirmTransaction(sig);
+        });
 
-        try {
+        const setMinRedeem = (minRedeem: number) =>
+            program.methods
+                .setMinRedeem(new anchor.BN(minRedeem))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        it("Rejects a redemption one unit below the minimum", async () => {
+            await setMinRedeem(10000);
+
+            try {
+                await program.methods
+                    .requestRedeem(new anchor.BN(9999), new anchor.BN(0), null, new Array(32).fill(0))
+                    .accounts({
+                        signer: user.publicKey,
+                        userMintTokenAccount: userMintTokenAccount,
+                        mint: mintToken,
+                        vaultMint: vaultMint,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
+
+                assert.fail("Should have failed due to the redemption minimum");
+            } catch (error) {
+                assert.include(error.toString(), "RedeemBelowMinimum");
+            }
+        });
+
+        it("Rejects a second redemption request while one is pending", async () => {
+            // Exactly at the minimum set above is accepted
+            const memo = Array.from(Buffer.from("ticket-42".padEnd(32, "\0")));
             await program.methods
-                .freezeTokenAccount()
+                .requestRedeem(new anchor.BN(10000), new anchor.BN(0), null, memo)
                 .accounts({
-                    tokenAccount: userMintTokenAccount,
+                    signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
                     mint: mintToken,
-                    signer: unauthorizedUser.publicKey,
+                    vaultMint: vaultMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
                 })
-                .signers([unauthorizedUser])
                 .rpc();
 
-            assert.fail("Should have failed due to unauthorized freeze administrator");
-        } catch (error) {
-            assert.include(error.toString(), "UnauthorizedFreezeAdministrator");
-        }
-    });
+            try {
+                await program.methods
+                    .requestRedeem(new anchor.BN(5000), new anchor.BN(0), null, new Array(32).fill(0))
+                    .accounts({
+                        signer: user.publicKey,
+                        userMintTokenAccount: userMintTokenAccount,
+                        mint: mintToken,
+                        vaultMint: vaultMint,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
 
-    it("Freezes user token account", async () => {
-        const tx = await program.methods
-            .freezeTokenAccount()
-            .accounts({
-                tokenAccount: userMintTokenAccount,
-                mint: mintToken,
-                signer: freezeAdmin.publicKey,
-            })
-            .signers([freezeAdmin])
-            .rpc();
+                assert.fail("Should have failed due to a pending redemption request");
+            } catch (error) {
+                assert.include(error.toString(), "RedemptionAlreadyPending");
+            }
 
-        // Verify account is frozen
-        const account = await getAccount(provider.connection, userMintTokenAccount);
-        assert.isTrue(account.isFrozen);
-    });
+            // The first request is untouched
+            const request = await program.account.redemptionRequest.fetch(redemptionRequestPda);
+            assert.equal(request.amount.toString(), "10000");
+            // No destination given, so proceeds go to the user's vault token ATA
+            assert.equal(request.destination.toBase58(), userVaultTokenAccount.toBase58());
+            assert.deepEqual(Array.from(request.memo), memo);
+        });
 
-    it("Thaws user token account", async () => {
-        const tx = await program.methods
-            .thawTokenAccount()
-            .accounts({
-                tokenAccount: userMintTokenAccount,
-                mint: mintToken,
-                signer: freezeAdmin.publicKey,
-            })
-            .signers([freezeAdmin])
-            .rpc();
+        const setMinVaultReserve = (reserve: number) =>
+            program.methods
+                .setMinVaultReserve(new anchor.BN(reserve))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
 
-        // Verify account is thawed
-        const account = await getAccount(provider.connection, userMintTokenAccount);
-        assert.isFalse(account.isFrozen);
-    });
+        after(async () => {
+            await setMinVaultReserve(0);
+            await setMinRedeem(0);
+        });
 
-    it("Updates freeze administrators", async () => {
-        const newFreezeAdmin = Keypair.generate();
-        await provider.connection.requestAirdrop(newFreezeAdmin.publicKey, 1000000000);
+        it("Rejects a completion that would breach the vault reserve", async () => {
+            // 1_000_000 in the vault, 10_000 redeemed leaves 990_000
+            await setMinVaultReserve(990001);
 
-        const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
-            "BPFLoaderUpgradeab1e11111111111111111111111"
-        );
-        const [programData] = PublicKey.findProgramAddressSync(
-            [program.programId.toBuffer()],
-            BPF_LOADER_UPGRADEABLE_ID
-        );
+            try {
+                await program.methods
+                    .completeRedeem(null)
+                    .accounts({
+                        admin: rewardsAdmin.publicKey,
+                        user: user.publicKey,
+                        userMintTokenAccount: userMintTokenAccount,
+                        userVaultTokenAccount: userVaultTokenAccount,
+                        redeemVaultTokenAccount: redeemVaultTokenAccount,
+                        feeVaultTokenAccount: redeemVaultTokenAccount,
+                        mint: mintToken,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        vaultMint: vaultMint,
+                        vaultTokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([rewardsAdmin])
+                    .rpc();
 
-        const tx = await program.methods
-            .updateFreezeAdministrators([freezeAdmin.publicKey, newFreezeAdmin.publicKey])
-            .accounts({
-                programData: programData,
-                signer: user.publicKey,
-            })
-            .rpc();
+                assert.fail("Should have failed due to the vault reserve");
+            } catch (error) {
+                assert.include(error.toString(), "VaultReserveBreached");
+            }
 
-        // Verify administrators were updated
-        const config = await program.account.config.fetch(configPda);
-        assert.equal(config.freezeAdministrators.length, 2);
-    });
+            // A reserve equal to the remaining balance is allowed (exercised below)
+            await setMinVaultReserve(990000);
+        });
 
-    it("Prevents too many administrators", async () => {
-        const tooManyAdmins = Array(6).fill(0).map(() => Keypair.generate().publicKey);
+        const completeRedeem = (
+            fillAmount: number | null,
+            feeVaultTokenAccount: PublicKey = redeemVaultTokenAccount
+        ) =>
+            program.methods
+                .completeRedeem(fillAmount === null ? null : new anchor.BN(fillAmount))
+                .accounts({
+                    admin: rewardsAdmin.publicKey,
+                    user: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    userVaultTokenAccount: userVaultTokenAccount,
+                    redeemVaultTokenAccount: redeemVaultTokenAccount,
+                    feeVaultTokenAccount,
+                    mint: mintToken,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    vaultMint: vaultMint,
+                    vaultTokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([rewardsAdmin])
+                .rpc();
 
-        const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
-            "BPFLoaderUpgradeab1e11111111111111111111111"
-        );
-        const [programData] = PublicKey.findProgramAddressSync(
-            [program.programId.toBuffer()],
-            BPF_LOADER_UPGRADEABLE_ID
-        );
+        it("Rejects completing a request whose wYLDS account was frozen", async () => {
+            const setFrozen = (frozen: boolean) =>
+                (frozen ? program.methods.freezeTokenAccount() : program.methods.thawTokenAccount())
+                    .accounts({
+                        tokenAccount: userMintTokenAccount,
+                        mint: mintToken,
+                        signer: freezeAdmin.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([freezeAdmin])
+                    .rpc();
 
-        try {
+            await setFrozen(true);
+            try {
+                await completeRedeem(null);
+                assert.fail("Should have failed due to the frozen wYLDS account");
+            } catch (error) {
+                assert.include(error.toString(), "UserTokenAccountFrozen");
+            }
+            await setFrozen(false);
+        });
+
+        it("Partially fills the original redemption request", async () => {
+            const before = await getAccount(provider.connection, userMintTokenAccount);
+
+            await completeRedeem(4000);
+
+            const after = await getAccount(provider.connection, userMintTokenAccount);
+            assert.equal((before.amount - after.amount).toString(), "4000");
+
+            // The rest of the request stays open
+            const request = await program.account.redemptionRequest.fetch(redemptionRequestPda);
+            assert.equal(request.amount.toString(), "6000");
+
+            try {
+                await completeRedeem(6001);
+                assert.fail("Should have failed due to the fill exceeding the request");
+            } catch (error) {
+                assert.include(error.toString(), "FillExceedsRequest");
+            }
+        });
+
+        it("Completes the original redemption request", async () => {
+            const before = await getAccount(provider.connection, userMintTokenAccount);
+
+            await completeRedeem(null);
+
+            const after = await getAccount(provider.connection, userMintTokenAccount);
+            assert.equal((before.amount - after.amount).toString(), "6000");
+
+            const request = await program.account.redemptionRequest.fetchNullable(redemptionRequestPda);
+            assert.isNull(request);
+        });
+
+        it("Settles redemption requests in a batch, skipping ones the vault cannot cover", async () => {
             await program.methods
-                .updateFreezeAdministrators(tooManyAdmins)
+                .requestRedeem(new anchor.BN(10000), new anchor.BN(0), null, new Array(32).fill(0))
                 .accounts({
-                    programData: programData,
                     signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    mint: mintToken,
+                    vaultMint: vaultMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
                 })
                 .rpc();
 
-            assert.fail("Should have failed due to too many administrators");
-        } catch (error) {
+            const [userPositionPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("position"), user.publicKey.toBuffer()],
+                program.programId
+            );
+            const completeBatch = () =>
+                program.methods
+                    .completeRedeemBatch()
+                    .accounts({
+                        admin: rewardsAdmin.publicKey,
+                        redeemVaultTokenAccount: redeemVaultTokenAccount,
+                        feeVaultTokenAccount: redeemVaultTokenAccount,
+                        vaultMint: vaultMint,
+                        mint: mintToken,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        vaultTokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .remainingAccounts([
+                        { pubkey: redemptionRequestPda, isWritable: true, isSigner: false },
+                        { pubkey: user.publicKey, isWritable: true, isSigner: false },
+                        { pubkey: userMintTokenAccount, isWritable: true, isSigner: false },
+                        { pubkey: userVaultTokenAccount, isWritable: true, isSigner: false },
+                        { pubkey: userPositionPda, isWritable: true, isSigner: false },
+                    ])
+                    .signers([rewardsAdmin])
+                    .rpc();
+
+            // Paying out would dip below the reserve, so the request is skipped and stays open
+            const vaultBalance = (await getAccount(provider.connection, redeemVaultTokenAccount)).amount;
+            await setMinVaultReserve(Number(vaultBalance));
+            await completeBatch();
+            assert.isNotNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+
+            await setMinVaultReserve(0);
+            const before = await getAccount(provider.connection, userMintTokenAccount);
+            await completeBatch();
+            const after = await getAccount(provider.connection, userMintTokenAccount);
+            assert.equal((before.amount - after.amount).toString(), "10000");
+            assert.isNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+        });
+
+        it("Serves redemption requests in queue order", async () => {
+            const setQueueHead = (head: anchor.BN) =>
+                program.methods
+                    .setRedeemQueueHead(head)
+                    .accounts({
+                        programData: getProgramData(),
+                        signer: user.publicKey,
+                    })
+                    .rpc();
+
+            await program.methods
+                .requestRedeem(new anchor.BN(10000), new anchor.BN(0), null, new Array(32).fill(0))
+                .accounts({
+                    signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    mint: mintToken,
+                    vaultMint: vaultMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            let config = await program.account.config.fetch(configPda);
+            const request = await program.account.redemptionRequest.fetch(redemptionRequestPda);
+            assert.equal(request.queuePosition.toString(), config.redeemQueueHead.toString());
+            assert.equal(config.redeemQueueTail.toString(), request.queuePosition.addn(1).toString());
+
+            // The head never moves backwards or past the tail
+            for (const head of [config.redeemQueueHead.subn(1), config.redeemQueueTail.addn(1)]) {
+                try {
+                    await setQueueHead(head);
+                    assert.fail("Should have failed due to an invalid queue head");
+                } catch (error) {
+                    assert.include(error.toString(), "InvalidRedeemQueueHead");
+                }
+            }
+
+            // Once the head has moved past it, the request can no longer be completed
+            await setQueueHead(config.redeemQueueTail);
+            try {
+                await completeRedeem(null);
+                assert.fail("Should have failed due to the request not being at the queue head");
+            } catch (error) {
+                assert.include(error.toString(), "RedeemOutOfOrder");
+            }
+
+            // Cancelling a request behind the head leaves the head where it is
+            await program.methods
+                .cancelRedeem()
+                .accounts({
+                    signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            config = await program.account.config.fetch(configPda);
+            assert.equal(config.redeemQueueHead.toString(), config.redeemQueueTail.toString());
+        });
+
+        it("Skips a request cancelled behind the head once the head reaches it", async () => {
+            // Two other holders queue ahead of and between the user's requests
+            const holder = async () => {
+                const signer = Keypair.generate();
+                const sig = await provider.connection.requestAirdrop(signer.publicKey, 1000000000);
+                await provider.connection.confirmTransaction(sig);
+                const mintAccount = await createAccount(provider.connection, user.payer, mintToken, signer.publicKey);
+                const vaultAccount = await createAccount(
+                    provider.connection, user.payer, vaultMint, signer.publicKey, Keypair.generate()
+                );
+                await transfer(provider.connection, user.payer, userMintTokenAccount, mintAccount, user.publicKey, 10000);
+                await program.methods
+                    .requestRedeem(new anchor.BN(10000), new anchor.BN(0), vaultAccount, new Array(32).fill(0))
+                    .accounts({
+                        signer: signer.publicKey,
+                        userMintTokenAccount: mintAccount,
+                        mint: mintToken,
+                        vaultMint: vaultMint,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([signer])
+                    .rpc();
+                return { signer, mintAccount, vaultAccount };
+            };
+
+            const first = await holder();
+            const second = await holder();
+            await program.methods
+                .requestRedeem(new anchor.BN(10000), new anchor.BN(0), null, new Array(32).fill(0))
+                .accounts({
+                    signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    mint: mintToken,
+                    vaultMint: vaultMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            const request = await program.account.redemptionRequest.fetch(redemptionRequestPda);
+
+            // The second holder leaves the queue while the first is still at the head
+            await program.methods
+                .cancelRedeem()
+                .accounts({
+                    signer: second.signer.publicKey,
+                    userMintTokenAccount: second.mintAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([second.signer])
+                .rpc();
+            let config = await program.account.config.fetch(configPda);
+            assert.equal(config.redeemQueueHead.toString(), request.queuePosition.subn(2).toString());
+
+            await program.methods
+                .completeRedeem(null)
+                .accounts({
+                    admin: rewardsAdmin.publicKey,
+                    user: first.signer.publicKey,
+                    userMintTokenAccount: first.mintAccount,
+                    userVaultTokenAccount: first.vaultAccount,
+                    redeemVaultTokenAccount: redeemVaultTokenAccount,
+                    feeVaultTokenAccount: redeemVaultTokenAccount,
+                    mint: mintToken,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    vaultMint: vaultMint,
+                    vaultTokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([rewardsAdmin])
+                .rpc();
+
+            // The head skips the cancelled position and lands on the user's request
+            config = await program.account.config.fetch(configPda);
+            assert.equal(config.redeemQueueHead.toString(), request.queuePosition.toString());
+
+            const before = await getAccount(provider.connection, userMintTokenAccount);
+            await completeRedeem(null);
+            const after = await getAccount(provider.connection, userMintTokenAccount);
+            assert.equal((before.amount - after.amount).toString(), "10000");
+            config = await program.account.config.fetch(configPda);
+            assert.equal(config.redeemQueueHead.toString(), config.redeemQueueTail.toString());
+        });
+
+        it("Lets only the upgrade authority complete a redemption out of queue order", async () => {
+            await program.methods
+                .requestRedeem(new anchor.BN(10000), new anchor.BN(0), null, new Array(32).fill(0))
+                .accounts({
+                    signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    mint: mintToken,
+                    vaultMint: vaultMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            const request = await program.account.redemptionRequest.fetch(redemptionRequestPda);
+
+            // Put the request behind the head so complete_redeem refuses it
+            await program.methods
+                .setRedeemQueueHead(request.queuePosition.addn(1))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+            const completePriority = (admin: Keypair) =>
+                program.methods
+                    .completeRedeemPriority()
+                    .accounts({
+                        redeem: {
+                            admin: admin.publicKey,
+                            user: user.publicKey,
+                            userMintTokenAccount: userMintTokenAccount,
+                            userVaultTokenAccount: userVaultTokenAccount,
+                            redeemVaultTokenAccount: redeemVaultTokenAccount,
+                            feeVaultTokenAccount: redeemVaultTokenAccount,
+                            mint: mintToken,
+                            tokenProgram: TOKEN_PROGRAM_ID,
+                            vaultMint: vaultMint,
+                            vaultTokenProgram: TOKEN_PROGRAM_ID,
+                        },
+                        programData: getProgramData(),
+                    })
+                    .signers([admin])
+                    .rpc();
+
+            try {
+                await completePriority(rewardsAdmin);
+                assert.fail("Should have failed due to the signer not being the upgrade authority");
+            } catch (error) {
+                assert.include(error.toString(), "InvalidUpgradeAuthority");
+            }
+
+            const before = await getAccount(provider.connection, userMintTokenAccount);
+            await completePriority(user.payer);
+            const after = await getAccount(provider.connection, userMintTokenAccount);
+            assert.equal((before.amount - after.amount).toString(), "10000");
+            assert.isNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+
+            const config = await program.account.config.fetch(configPda);
+            assert.equal(config.redeemQueueHead.toString(), request.queuePosition.addn(1).toString());
+        });
+
+        it("Moves deposited vault tokens into the redeem vault", async () => {
+            const rebalance = (signer: Keypair) =>
+                program.methods
+                    .rebalanceToRedeemVault(new anchor.BN(1000))
+                    .accounts({
+                        vaultTokenAccount: vaultTokenAccount,
+                        redeemVaultTokenAccount: redeemVaultTokenAccount,
+                        vaultMint: vaultMint,
+                        signer: signer.publicKey,
+                        vaultTokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([signer])
+                    .rpc();
+
+            try {
+                await rebalance(rewardsAdmin);
+                assert.fail("Should have failed due to the signer not owning the deposit vault");
+            } catch (error) {
+                assert.include(error.toString(), "InvalidVaultAuthority");
+            }
+
+            const before = await getAccount(provider.connection, redeemVaultTokenAccount);
+            await rebalance(user.payer);
+            const after = await getAccount(provider.connection, redeemVaultTokenAccount);
+            assert.equal((after.amount - before.amount).toString(), "1000");
+        });
+
+        it("Reports whether the wYLDS supply is backed", async () => {
+            const {events} = await program.methods
+                .checkBacking()
+                .accounts({
+                    vaultTokenAccount: vaultTokenAccount,
+                    redeemVaultTokenAccount: redeemVaultTokenAccount,
+                    mint: mintToken,
+                })
+                .simulate();
+            const report = events.find((e) => e.name === "backingReport").data;
+
+            const vault = await getAccount(provider.connection, vaultTokenAccount);
+            const redeemVault = await getAccount(provider.connection, redeemVaultTokenAccount);
+            const mintInfo = await getMint(provider.connection, mintToken);
+            assert.equal(report.vaultBalance.toString(), vault.amount.toString());
+            assert.equal(report.redeemVaultBalance.toString(), redeemVault.amount.toString());
+            assert.equal(report.mintSupply.toString(), mintInfo.supply.toString());
+
+            const expectedBps = report.totalVaultTokens.muln(10000).div(report.requiredBacking);
+            assert.equal(report.backingRatioBps.toString(), expectedBps.toString());
+            assert.equal(report.fullyBacked, report.totalVaultTokens.gte(report.requiredBacking));
+        });
+
+        it("Lets only the upgrade authority withdraw from the redeem vault while paused", async () => {
+            const pauseAccounts = { programData: getProgramData(), signer: user.publicKey };
+            const emergencyWithdraw = (signer: Keypair) =>
+                program.methods
+                    .emergencyWithdraw(new anchor.BN(1))
+                    .accounts({
+                        programData: getProgramData(),
+                        signer: signer.publicKey,
+                        redeemVaultTokenAccount: redeemVaultTokenAccount,
+                        vaultMint: vaultMint,
+                        destinationTokenAccount: userVaultTokenAccount,
+                        vaultTokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([signer])
+                    .rpc();
+
+            try {
+                await emergencyWithdraw(user.payer);
+                assert.fail("Should have failed due to the program not being paused");
+            } catch (error) {
+                assert.include(error.toString(), "ProgramNotPaused");
+            }
+
+            await program.methods.pause().accounts(pauseAccounts).rpc();
+            try {
+                try {
+                    await emergencyWithdraw(rewardsAdmin);
+                    assert.fail("Should have failed due to the signer not being the upgrade authority");
+                } catch (error) {
+                    assert.include(error.toString(), "InvalidUpgradeAuthority");
+                }
+
+                const before = await getAccount(provider.connection, redeemVaultTokenAccount);
+                await emergencyWithdraw(user.payer);
+                const after = await getAccount(provider.connection, redeemVaultTokenAccount);
+                assert.equal((before.amount - after.amount).toString(), "1");
+            } finally {
+                await program.methods.unpause().accounts(pauseAccounts).rpc();
+            }
+        });
+
+        const requestRedeem = (amount: number) =>
+            program.methods
+                .requestRedeem(new anchor.BN(amount), new anchor.BN(0), null, new Array(32).fill(0))
+                .accounts({
+                    signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    mint: mintToken,
+                    vaultMint: vaultMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+        const setRedeemFee = (bps: number) =>
+            program.methods
+                .setRedeemFee(bps)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+        const setFeeVault = (feeVaultTokenAccount: PublicKey) =>
+            program.methods
+                .setDepositFee(0)
+                .accounts({
+                    feeVaultTokenAccount,
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        it("Withholds the redemption fee in the fee vault and pays out the rest", async () => {
+            const feeVault = await createAccount(
+                provider.connection, user.payer, vaultMint, user.publicKey, Keypair.generate()
+            );
+            const previousFeeVault = (await program.account.config.fetch(configPda)).feeVault;
+            await setFeeVault(feeVault);
+            await setRedeemFee(100);
+
+            try {
+                await requestRedeem(10000);
+
+                // With a fee set, only the configured fee vault is accepted
+                try {
+                    await completeRedeem(null);
+                    assert.fail("Should have failed due to the wrong fee vault");
+                } catch (error) {
+                    assert.include(error.toString(), "InvalidFeeVault");
+                }
+
+                const userBefore = await getAccount(provider.connection, userVaultTokenAccount);
+                const redeemVaultBefore = await getAccount(provider.connection, redeemVaultTokenAccount);
+                const wyldsBefore = await getAccount(provider.connection, userMintTokenAccount);
+                await completeRedeem(null, feeVault);
+                const userAfter = await getAccount(provider.connection, userVaultTokenAccount);
+                const redeemVaultAfter = await getAccount(provider.connection, redeemVaultTokenAccount);
+                const wyldsAfter = await getAccount(provider.connection, userMintTokenAccount);
+
+                // The full wYLDS amount is burned; the vault tokens are split between fee and user
+                assert.equal((wyldsBefore.amount - wyldsAfter.amount).toString(), "10000");
+                const gross = redeemVaultBefore.amount - redeemVaultAfter.amount;
+                const fee = (await getAccount(provider.connection, feeVault)).amount;
+                assert.equal(fee.toString(), (gross * BigInt(100) / BigInt(10000)).toString());
+                assert.isTrue(fee > BigInt(0));
+                assert.equal((userAfter.amount - userBefore.amount).toString(), (gross - fee).toString());
+            } finally {
+                await setRedeemFee(0);
+                await setFeeVault(previousFeeVault);
+            }
+        });
+
+        it("Cancels a pending redemption request and revokes the delegate approval", async () => {
+            const before = await getAccount(provider.connection, userMintTokenAccount);
+            await requestRedeem(10000);
+
+            // request_redeem approves the redeem vault authority over the requested amount
+            const pending = await getAccount(provider.connection, userMintTokenAccount);
+            assert.equal(pending.delegate.toBase58(), redeemVaultAuthorityPda.toBase58());
+            assert.equal(pending.delegatedAmount.toString(), "10000");
+
+            await program.methods
+                .cancelRedeem()
+                .accounts({
+                    signer: user.publicKey,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            // Nothing is burned, the approval is gone and the request is closed
+            const after = await getAccount(provider.connection, userMintTokenAccount);
+            assert.equal(after.amount.toString(), before.amount.toString());
+            assert.isNull(after.delegate);
+            assert.equal(after.delegatedAmount.toString(), "0");
+            assert.isNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+
+            const config = await program.account.config.fetch(configPda);
+            assert.equal(config.redeemQueueHead.toString(), config.redeemQueueTail.toString());
+        });
+
+        it("Holds redemption requests until the redeem delay has passed", async () => {
+            const setRedeemDelay = (seconds: number) =>
+                program.methods
+                    .setRedeemDelay(new anchor.BN(seconds))
+                    .accounts({
+                        programData: getProgramData(),
+                        signer: user.publicKey,
+                    })
+                    .rpc();
+
+            await setRedeemDelay(3600);
+            try {
+                await requestRedeem(10000);
+                try {
+                    await completeRedeem(null);
+                    assert.fail("Should have failed due to the redeem delay");
+                } catch (error) {
+                    assert.include(error.toString(), "RedeemTimelockNotElapsed");
+                }
+                assert.isNotNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+
+                // The delay is read at completion time, so shortening it releases the request
+                await setRedeemDelay(0);
+                const before = await getAccount(provider.connection, userMintTokenAccount);
+                await completeRedeem(null);
+                const after = await getAccount(provider.connection, userMintTokenAccount);
+                assert.equal((before.amount - after.amount).toString(), "10000");
+            } finally {
+                await setRedeemDelay(0);
+            }
+        });
+
+        const setRedeemExpiry = (seconds: number) =>
+            program.methods
+                .setRedeemExpiry(new anchor.BN(seconds))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        it("Lets a rewards administrator expire a stale redemption request", async () => {
+            const expireRedeem = () =>
+                program.methods
+                    .expireRedeem()
+                    .accounts({
+                        admin: rewardsAdmin.publicKey,
+                        user: user.publicKey,
+                        userMintTokenAccount: userMintTokenAccount,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([rewardsAdmin])
+                    .rpc();
+
+            await requestRedeem(10000);
+            try {
+                // With no expiry configured a request never goes stale
+                try {
+                    await expireRedeem();
+                    assert.fail("Should have failed due to the expiry being disabled");
+                } catch (error) {
+                    assert.include(error.toString(), "RedemptionNotExpired");
+                }
+
+                await setRedeemExpiry(2);
+                try {
+                    await expireRedeem();
+                    assert.fail("Should have failed due to the request not having expired yet");
+                } catch (error) {
+                    assert.include(error.toString(), "RedemptionNotExpired");
+                }
+
+                await new Promise((resolve) => setTimeout(resolve, 3000));
+                const before = await getAccount(provider.connection, userMintTokenAccount);
+                await expireRedeem();
+
+                // Nothing is burned and the request leaves the queue
+                const after = await getAccount(provider.connection, userMintTokenAccount);
+                assert.equal(after.amount.toString(), before.amount.toString());
+                assert.isNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+                const config = await program.account.config.fetch(configPda);
+                assert.equal(config.redeemQueueHead.toString(), config.redeemQueueTail.toString());
+            } finally {
+                await setRedeemExpiry(0);
+            }
+        });
+
+        it("Reclaims expired redemption requests in bulk, skipping fresh ones", async () => {
+            const reclaim = (remainingAccounts: PublicKey[]) =>
+                program.methods
+                    .reclaimAbandonedRedemptions()
+                    .accounts({ admin: rewardsAdmin.publicKey, tokenProgram: TOKEN_PROGRAM_ID })
+                    .remainingAccounts(
+                        remainingAccounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+                    )
+                    .signers([rewardsAdmin])
+                    .rpc();
+
+            await requestRedeem(10000);
+            await setRedeemExpiry(2);
+            try {
+                // Accounts come in (request, user, wYLDS account) groups
+                try {
+                    await reclaim([redemptionRequestPda, user.publicKey]);
+                    assert.fail("Should have failed due to an incomplete group");
+                } catch (error) {
+                    assert.include(error.toString(), "InvalidReclaimBatch");
+                }
+
+                // Not expired yet, so the request is skipped rather than failing the batch
+                await reclaim([redemptionRequestPda, user.publicKey, userMintTokenAccount]);
+                assert.isNotNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+
+                await new Promise((resolve) => setTimeout(resolve, 3000));
+                await reclaim([redemptionRequestPda, user.publicKey, userMintTokenAccount]);
+                assert.isNull(await program.account.redemptionRequest.fetchNullable(redemptionRequestPda));
+                const config = await program.account.config.fetch(configPda);
+                assert.equal(config.redeemQueueHead.toString(), config.redeemQueueTail.toString());
+            } finally {
+                await setRedeemExpiry(0);
+            }
+        });
+
+        it("Only points redemptions at a vault-mint account owned by the redeem vault authority", async () => {
+            const setRedeemVault = (account: PublicKey, signer: Keypair) =>
+                program.methods
+                    .setRedeemVault()
+                    .accounts({
+                        redeemVaultTokenAccount: account,
+                        programData: getProgramData(),
+                        signer: signer.publicKey,
+                    })
+                    .signers([signer])
+                    .rpc();
+            const expectFailure = async (account: PublicKey, signer: Keypair, code: string) => {
+                try {
+                    await setRedeemVault(account, signer);
+                    assert.fail(`Should have failed with ${code}`);
+                } catch (error) {
+                    assert.include(error.toString(), code);
+                }
+            };
+
+            const userOwned = await createAccount(
+                provider.connection, user.payer, vaultMint, user.publicKey, Keypair.generate()
+            );
+            const wrongMint = await createAccount(
+                provider.connection, user.payer, mintToken, redeemVaultAuthorityPda, Keypair.generate()
+            );
+            const replacement = await createAccount(
+                provider.connection, user.payer, vaultMint, redeemVaultAuthorityPda, Keypair.generate()
+            );
+
+            await expectFailure(userOwned, user.payer, "InvalidVaultAuthority");
+            await expectFailure(wrongMint, user.payer, "InvalidVaultMint");
+            await expectFailure(replacement, rewardsAdmin, "InvalidUpgradeAuthority");
+
+            await setRedeemVault(replacement, user.payer);
+            try {
+                const config = await program.account.config.fetch(configPda);
+                assert.equal(config.redeemVault.toBase58(), replacement.toBase58());
+            } finally {
+                await setRedeemVault(redeemVaultTokenAccount, user.payer);
+            }
+        });
+
+        it("Quotes the same gross, fee and net amounts that complete_redeem pays", async () => {
+            const feeVault = await createAccount(
+                provider.connection, user.payer, vaultMint, user.publicKey, Keypair.generate()
+            );
+            const previousFeeVault = (await program.account.config.fetch(configPda)).feeVault;
+            await setFeeVault(feeVault);
+            await setRedeemFee(150);
+
+            try {
+                const {events} = await program.methods
+                    .simulateRedeem(new anchor.BN(10000))
+                    .accounts({
+                        redeemVaultTokenAccount: redeemVaultTokenAccount,
+                        priceOracle: null,
+                    })
+                    .simulate();
+                const quote = events.find((e) => e.name === "redeemQuote").data;
+                assert.equal(quote.amount.toString(), "10000");
+                assert.equal(quote.net.toString(), quote.gross.sub(quote.fee).toString());
+                assert.isTrue(quote.vaultCanFill);
+
+                await requestRedeem(10000);
+                const userBefore = await getAccount(provider.connection, userVaultTokenAccount);
+                const redeemVaultBefore = await getAccount(provider.connection, redeemVaultTokenAccount);
+                await completeRedeem(null, feeVault);
+                const userAfter = await getAccount(provider.connection, userVaultTokenAccount);
+                const redeemVaultAfter = await getAccount(provider.connection, redeemVaultTokenAccount);
+
+                assert.equal((redeemVaultBefore.amount - redeemVaultAfter.amount).toString(), quote.gross.toString());
+                assert.equal((await getAccount(provider.connection, feeVault)).amount.toString(), quote.fee.toString());
+                assert.equal((userAfter.amount - userBefore.amount).toString(), quote.net.toString());
+            } finally {
+                await setRedeemFee(0);
+                await setFeeVault(previousFeeVault);
+            }
+        });
+    });
+
+    it("Creates rewards epoch", async () => {
+        const epochIndex = new anchor.BN(0);
+        const merkleRoot = Array(32).fill(0); // Mock merkle root
+        const totalRewards = new anchor.BN(10000);
+
+        const [epochPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+
+        const tx = await program.methods
+            .createRewardsEpoch(epochIndex, merkleRoot, totalRewards, new anchor.BN(0), { flagged: {} }, 0)
+            .accounts({
+                admin: rewardsAdmin.publicKey,
+            })
+            .signers([rewardsAdmin])
+            .rpc();
+
+        // Verify epoch was created
+        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
+        assert.equal(epoch.index.toString(), epochIndex.toString());
+        assert.equal(epoch.total.toString(), totalRewards.toString());
+
+        // Claims are bounded by one past the highest epoch index created
+        const config = await program.account.config.fetch(configPda);
+        assert.equal(config.epochsCreated.toString(), "1");
+        assert.equal(config.nextEpochIndex.toString(), "1");
+    });
+
+    it("Rejects an out-of-order rewards epoch", async () => {
+        try {
+            await program.methods
+                .createRewardsEpoch(new anchor.BN(5), Array(32).fill(0), new anchor.BN(1), new anchor.BN(0), { flagged: {} }, 0)
+                .accounts({
+                    admin: rewardsAdmin.publicKey,
+                })
+                .signers([rewardsAdmin])
+                .rpc();
+
+            assert.fail("Should have failed because epoch 1 is next");
+        } catch (error) {
+            assert.include(error.toString(), "NonSequentialEpoch");
+        }
+    });
+
+    it("Fails unauthorized freeze attempt", async () => {
+        const unauthorizedUser = Keypair.generate();
+
+        try {
+            await program.methods
+                .freezeTokenAccount()
+                .accounts({
+                    tokenAccount: userMintTokenAccount,
+                    mint: mintToken,
+                    signer: unauthorizedUser.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([unauthorizedUser])
+                .rpc();
+
+            assert.fail("Should have failed due to unauthorized freeze administrator");
+        } catch (error) {
+            assert.include(error.toString(), "UnauthorizedFreezeAdministrator");
+        }
+    });
+
+    it("Freezes user token account", async () => {
+        const tx = await program.methods
+            .freezeTokenAccount()
+            .accounts({
+                tokenAccount: userMintTokenAccount,
+                mint: mintToken,
+                signer: freezeAdmin.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([freezeAdmin])
+            .rpc();
+
+        // Verify account is frozen
+        const account = await getAccount(provider.connection, userMintTokenAccount);
+        assert.isTrue(account.isFrozen);
+    });
+
+    it("Thaws user token account", async () => {
+        const tx = await program.methods
+            .thawTokenAccount()
+            .accounts({
+                tokenAccount: userMintTokenAccount,
+                mint: mintToken,
+                signer: freezeAdmin.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([freezeAdmin])
+            .rpc();
+
+        // Verify account is thawed
+        const account = await getAccount(provider.connection, userMintTokenAccount);
+        assert.isFalse(account.isFrozen);
+    });
+
+    it("Records the reason for a freeze and clears it on thaw", async () => {
+        const [freezeRecordPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("freeze_record"), userMintTokenAccount.toBuffer()],
+            program.programId
+        );
+        const accounts = {
+            tokenAccount: userMintTokenAccount,
+            mint: mintToken,
+            signer: freezeAdmin.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        };
+
+        await program.methods
+            .freezeTokenAccountWithReason(7)
+            .accounts(accounts)
+            .signers([freezeAdmin])
+            .rpc();
+
+        const record = await program.account.freezeRecord.fetch(freezeRecordPda);
+        assert.equal(record.reasonCode, 7);
+        assert.equal(record.admin.toBase58(), freezeAdmin.publicKey.toBase58());
+        assert.isTrue((await getAccount(provider.connection, userMintTokenAccount)).isFrozen);
+
+        await program.methods
+            .thawTokenAccountWithReason(8)
+            .accounts(accounts)
+            .signers([freezeAdmin])
+            .rpc();
+
+        assert.isNull(await program.account.freezeRecord.fetchNullable(freezeRecordPda));
+        assert.isFalse((await getAccount(provider.connection, userMintTokenAccount)).isFrozen);
+    });
+
+    it("Freezes a batch of token accounts, skipping frozen ones", async () => {
+        const otherTokenAccount = await createAccount(
+            provider.connection,
+            user.payer,
+            mintToken,
+            Keypair.generate().publicKey
+        );
+        const batch = [userMintTokenAccount, otherTokenAccount];
+        const freezeBatch = () =>
+            program.methods
+                .freezeTokenAccountsBatch()
+                .accounts({
+                    mint: mintToken,
+                    signer: freezeAdmin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .remainingAccounts(batch.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+                .signers([freezeAdmin])
+                .rpc();
+
+        await freezeBatch();
+        for (const tokenAccount of batch) {
+            assert.isTrue((await getAccount(provider.connection, tokenAccount)).isFrozen);
+        }
+
+        // Everything is already frozen; the batch still succeeds
+        await freezeBatch();
+
+        for (const tokenAccount of batch) {
+            await program.methods
+                .thawTokenAccount()
+                .accounts({
+                    tokenAccount,
+                    mint: mintToken,
+                    signer: freezeAdmin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([freezeAdmin])
+                .rpc();
+        }
+        assert.isFalse((await getAccount(provider.connection, userMintTokenAccount)).isFrozen);
+    });
+
+    it("Limits a freeze administrator to their freeze quota", async () => {
+        const setFreezeScope = (quota: number) =>
+            program.methods
+                .setFreezeScope(freezeAdmin.publicKey, quota)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+        const freeze = (tokenAccount: PublicKey) =>
+            program.methods
+                .freezeTokenAccount()
+                .accounts({
+                    tokenAccount,
+                    mint: mintToken,
+                    signer: freezeAdmin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([freezeAdmin])
+                .rpc();
+        const newTokenAccount = () =>
+            createAccount(provider.connection, user.payer, mintToken, Keypair.generate().publicKey);
+
+        await setFreezeScope(1);
+        await freeze(await newTokenAccount());
+        try {
+            await freeze(await newTokenAccount());
+            assert.fail("Should have failed because the quota is spent");
+        } catch (error) {
+            assert.include(error.toString(), "FreezeQuotaExceeded");
+        }
+
+        // Resetting restores the allotment
+        await setFreezeScope(1_000_000);
+        await freeze(await newTokenAccount());
+    });
+
+    it("Updates freeze administrators", async () => {
+        const newFreezeAdmin = Keypair.generate();
+        await provider.connection.requestAirdrop(newFreezeAdmin.publicKey, 1000000000);
+
+        const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+            "BPFLoaderUpgradeab1e11111111111111111111111"
+        );
+        const [programData] = PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            BPF_LOADER_UPGRADEABLE_ID
+        );
+
+        const tx = await program.methods
+            .updateFreezeAdministrators([freezeAdmin.publicKey, newFreezeAdmin.publicKey])
+            .accounts({
+                programData: programData,
+                signer: user.publicKey,
+            })
+            .rpc();
+
+        // Verify administrators were updated
+        const config = await program.account.config.fetch(configPda);
+        assert.equal(config.freezeAdministrators.length, 2);
+    });
+
+    it("Prevents too many administrators", async () => {
+        const tooManyAdmins = Array(6).fill(0).map(() => Keypair.generate().publicKey);
+
+        const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+            "BPFLoaderUpgradeab1e11111111111111111111111"
+        );
+        const [programData] = PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            BPF_LOADER_UPGRADEABLE_ID
+        );
+
+        try {
+            await program.methods
+                .updateFreezeAdministrators(tooManyAdmins)
+                .accounts({
+                    programData: programData,
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+            assert.fail("Should have failed due to too many administrators");
+        } catch (error) {
             assert.include(error.toString(), "TooManyAdministrators");
         }
-    });
+    });
+
+    it("Rejects duplicate administrators", async () => {
+        try {
+            await program.methods
+                .updateRewardsAdministrators([rewardsAdmin.publicKey, rewardsAdmin.publicKey])
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+            assert.fail("Should have failed due to a duplicate administrator");
+        } catch (error) {
+            assert.include(error.toString(), "DuplicateAdministrator");
+        }
+    });
+
+    it("Adds and removes a single rewards administrator", async () => {
+        const extraAdmin = Keypair.generate().publicKey;
+        const accounts = {
+            programData: getProgramData(),
+            signer: user.publicKey,
+        };
+
+        await program.methods.addRewardsAdministrator(extraAdmin).accounts(accounts).rpc();
+        let config = await program.account.config.fetch(configPda);
+        assert.equal(config.rewardsAdministrators.length, 2);
+        assert.equal(config.rewardsAdministrators[0].toBase58(), rewardsAdmin.publicKey.toBase58());
+
+        await program.methods.removeRewardsAdministrator(extraAdmin).accounts(accounts).rpc();
+        config = await program.account.config.fetch(configPda);
+        assert.equal(config.rewardsAdministrators.length, 1);
+
+        try {
+            await program.methods.removeRewardsAdministrator(extraAdmin).accounts(accounts).rpc();
+            assert.fail("Should have failed for an absent administrator");
+        } catch (error) {
+            assert.include(error.toString(), "AdministratorNotFound");
+        }
+    });
+
+    it("Resizes the config when the administrator limit changes", async () => {
+        const setMaxAdministrators = (max: number) =>
+            program.methods
+                .setMaxAdministrators(max)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        const before = (await provider.connection.getAccountInfo(configPda)).data.length;
+
+        // Two lists, one more 32-byte key each
+        await setMaxAdministrators(6);
+        const grown = (await provider.connection.getAccountInfo(configPda)).data.length;
+        assert.equal(grown - before, 64);
+        assert.equal((await program.account.config.fetch(configPda)).maxAdministrators, 6);
+
+        try {
+            await setMaxAdministrators(33);
+            assert.fail("Should have failed above the administrator cap");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidMaxAdministrators");
+        }
+
+        await setMaxAdministrators(5);
+        assert.equal((await provider.connection.getAccountInfo(configPda)).data.length, before);
+    });
+
+    it("Claims rewards with valid merkle proof", async () => {
+        const epochIndex = new anchor.BN(1);
+        const claimAmount = new anchor.BN(5000);
+
+        // Mock merkle proof - in real implementation, this would be computed off-chain
+        const mockProof: number[][] = [];
+
+        // For testing, create a simple merkle root that validates our claim
+        const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
+
+        // Claim rewards
+        const [claimRecordPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("claim"), epochPda.toBuffer(), user.publicKey.toBuffer()],
+            program.programId
+        );
+
+        const tx = await program.methods
+            .claimRewards(claimAmount, mockProof)
+            .accounts({
+                user: user.publicKey,
+                epoch: epochPda,
+                mint: mintToken,
+                userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        // Verify claim record was created
+        const claimRecord = await program.account.claimRecord.fetch(claimRecordPda);
+        assert.isNotNull(claimRecord);
+        assert.equal(claimRecord.amount.toString(), claimAmount.toString());
+        assert.isTrue(claimRecord.claimedTs.toNumber() > 0);
+
+        // The claim is also recorded in the user's bitmap: bit 1 of the first byte
+        const {events} = await program.methods
+            .getClaimBitmap(user.publicKey)
+            .accounts({})
+            .simulate();
+        const bitmap = events.find((e) => e.name === "claimBitmapSnapshot").data;
+        assert.equal(bitmap.bits.length, 32);
+        assert.equal(bitmap.bits[0], 0b10);
+    });
+
+    it("Prevents double claiming", async () => {
+        const epochIndex = new anchor.BN(1);
+        const claimAmount = new anchor.BN(5000);
+        const mockProof: number[][] = [];
+
+        const [epochPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+
+        try {
+            await program.methods
+                .claimRewards(claimAmount, mockProof)
+                .accounts({
+                    user: user.publicKey,
+                    epoch: epochPda,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            assert.fail("Should have failed due to double claim attempt");
+        } catch (error) {
+            assert.include(error.toString(), "RewardsAlreadyClaimed");
+        }
+    });
+
+    it("Rejects claims after the epoch claim deadline", async () => {
+        const epochIndex = new anchor.BN(2);
+        const claimAmount = new anchor.BN(5000);
+
+        const [epochPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+
+        const leafHash = rewardsLeaf(user.publicKey, claimAmount, epochIndex);
+
+        // Deadline one second in the past relative to the cluster clock
+        const slot = await provider.connection.getSlot();
+        const now = await provider.connection.getBlockTime(slot);
+        const deadline = new anchor.BN(now - 1);
+
+        await program.methods
+            .createRewardsEpoch(epochIndex, Array.from(leafHash), claimAmount, deadline, { flagged: {} }, 0)
+            .accounts({
+                admin: rewardsAdmin.publicKey,
+            })
+            .signers([rewardsAdmin])
+            .rpc();
+
+        try {
+            await program.methods
+                .claimRewards(claimAmount, [])
+                .accounts({
+                    user: user.publicKey,
+                    epoch: epochPda,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            assert.fail("Should have failed due to an expired claim deadline");
+        } catch (error) {
+            assert.include(error.toString(), "EpochClaimExpired");
+        }
+    });
+
+    it("Rejects proofs that do not match the epoch tree depth", async () => {
+        const epochIndex = new anchor.BN(3);
+        const claimAmount = new anchor.BN(5000);
+
+        // Single-leaf tree: depth 0, the leaf is the root
+        const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
+
+        try {
+            await program.methods
+                .claimRewards(claimAmount, [{ sibling: Array(32).fill(0), isLeft: false }])
+                .accounts({
+                    user: user.publicKey,
+                    epoch: epochPda,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            assert.fail("Should have failed due to a proof longer than the tree depth");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidProofLength");
+        }
+    });
+
+    it("Claims several epochs in one batch", async () => {
+        const claimAmount = new anchor.BN(1000);
+        const epochIndexes = [new anchor.BN(4), new anchor.BN(5)];
+        const remainingAccounts = [];
+
+        for (const epochIndex of epochIndexes) {
+            const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
+            const [claimRecordPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("claim"), epochPda.toBuffer(), user.publicKey.toBuffer()],
+                program.programId
+            );
+            remainingAccounts.push(
+                { pubkey: epochPda, isSigner: false, isWritable: true },
+                { pubkey: claimRecordPda, isSigner: false, isWritable: true },
+            );
+        }
+
+        const before = await getAccount(provider.connection, userMintTokenAccount);
+
+        await program.methods
+            .claimRewardsMulti(epochIndexes.map(epochIndex => ({ epochIndex, amount: claimAmount, proof: [] })))
+            .accounts({
+                user: user.publicKey,
+                mint: mintToken,
+                userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(remainingAccounts)
+            .rpc();
+
+        const after = await getAccount(provider.connection, userMintTokenAccount);
+        assert.equal((after.amount - before.amount).toString(), "2000");
+
+        // Each epoch got its own claim record, so claiming again fails
+        try {
+            await program.methods
+                .claimRewardsMulti([{ epochIndex: epochIndexes[0], amount: claimAmount, proof: [] }])
+                .accounts({
+                    user: user.publicKey,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .remainingAccounts(remainingAccounts.slice(0, 2))
+                .rpc();
+
+            assert.fail("Should have failed due to double claim attempt");
+        } catch (error) {
+            assert.include(error.toString(), "RewardsAlreadyClaimed");
+        }
+    });
+
+    it("Rejects claims against a frozen epoch until it is unfrozen", async () => {
+        const epochIndex = new anchor.BN(6);
+        const claimAmount = new anchor.BN(1000);
+
+        const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
+
+        await program.methods
+            .freezeEpoch()
+            .accounts({
+                admin: rewardsAdmin.publicKey,
+                epoch: epochPda,
+            })
+            .signers([rewardsAdmin])
+            .rpc();
+
+        const claim = () =>
+            program.methods
+                .claimRewards(claimAmount, [])
+                .accounts({
+                    user: user.publicKey,
+                    epoch: epochPda,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+        try {
+            await claim();
+            assert.fail("Should have failed because the epoch is frozen");
+        } catch (error) {
+            assert.include(error.toString(), "EpochFrozen");
+        }
+
+        await program.methods
+            .unfreezeEpoch()
+            .accounts({
+                admin: rewardsAdmin.publicKey,
+                epoch: epochPda,
+            })
+            .signers([rewardsAdmin])
+            .rpc();
+
+        await claim();
+        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
+        assert.isFalse(epoch.frozen);
+        assert.equal(epoch.claimed.toString(), claimAmount.toString());
+    });
+
+    it("Re-posts the root of a frozen epoch with no claims", async () => {
+        const epochIndex = new anchor.BN(7);
+        const claimAmount = new anchor.BN(1000);
+
+        const [epochPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+
+        const leafHash = rewardsLeaf(user.publicKey, claimAmount, epochIndex);
+
+        // Posted with a bad root
+        await program.methods
+            .createRewardsEpoch(epochIndex, Array(32).fill(7), claimAmount, new anchor.BN(0), { flagged: {} }, 0)
+            .accounts({
+                admin: rewardsAdmin.publicKey,
+            })
+            .signers([rewardsAdmin])
+            .rpc();
+
+        const updateRoot = (pda: PublicKey) =>
+            program.methods
+                .updateRewardsEpochRoot(Array.from(leafHash), claimAmount)
+                .accounts({
+                    admin: rewardsAdmin.publicKey,
+                    epoch: pda,
+                })
+                .signers([rewardsAdmin])
+                .rpc();
+        const freeze = (pda: PublicKey) =>
+            program.methods
+                .freezeEpoch()
+                .accounts({
+                    admin: rewardsAdmin.publicKey,
+                    epoch: pda,
+                })
+                .signers([rewardsAdmin])
+                .rpc();
+
+        try {
+            await updateRoot(epochPda);
+            assert.fail("Should have failed because the epoch is not frozen");
+        } catch (error) {
+            assert.include(error.toString(), "EpochNotFrozen");
+        }
+
+        await freeze(epochPda);
+        await updateRoot(epochPda);
+
+        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
+        assert.isFalse(epoch.frozen);
+        assert.deepEqual(Buffer.from(epoch.merkleRoot), leafHash);
+
+        // The corrected root now verifies
+        await program.methods
+            .claimRewards(claimAmount, [])
+            .accounts({
+                user: user.publicKey,
+                epoch: epochPda,
+                mint: mintToken,
+                userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        // Once claimed against, the root is fixed
+        await freeze(epochPda);
+        try {
+            await updateRoot(epochPda);
+            assert.fail("Should have failed because the epoch has claims");
+        } catch (error) {
+            assert.include(error.toString(), "EpochHasClaims");
+        }
+    });
+
+    it("Claims rewards into another wallet's token account", async () => {
+        const epochIndex = new anchor.BN(8);
+        const claimAmount = new anchor.BN(1000);
+
+        const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
+
+        const coldWallet = anchor.web3.Keypair.generate();
+        const coldTokenAccount = await createAccount(
+            provider.connection,
+            user.payer,
+            mintToken,
+            coldWallet.publicKey
+        );
+
+        const claimTo = () =>
+            program.methods
+                .claimRewardsTo(claimAmount, [])
+                .accounts({
+                    claim: {
+                        user: user.publicKey,
+                        epoch: epochPda,
+                        mint: mintToken,
+                        userMintTokenAccount: userMintTokenAccount,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    },
+                    destination: coldTokenAccount,
+                })
+                .rpc();
+
+        const userBefore = await getAccount(provider.connection, userMintTokenAccount);
+        await claimTo();
+
+        const cold = await getAccount(provider.connection, coldTokenAccount);
+        assert.equal(cold.amount.toString(), claimAmount.toString());
+        const userAfter = await getAccount(provider.connection, userMintTokenAccount);
+        assert.equal(userAfter.amount.toString(), userBefore.amount.toString());
+
+        // The claim record is keyed on the signer, so a second claim fails
+        try {
+            await claimTo();
+            assert.fail("Should have failed due to double claim attempt");
+        } catch (error) {
+            assert.include(error.toString(), "RewardsAlreadyClaimed");
+        }
+    });
+
+    it("Airdrops rewards to a short list of recipients", async () => {
+        const other = Keypair.generate();
+        const otherTokenAccount = await createAccount(
+            provider.connection,
+            user.payer,
+            mintToken,
+            other.publicKey
+        );
+        const airdrop = (recipients: { recipient: PublicKey, amount: anchor.BN }[], accounts: PublicKey[]) =>
+            program.methods
+                .airdrop(recipients)
+                .accounts({
+                    mint: mintToken,
+                    admin: rewardsAdmin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .remainingAccounts(accounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+                .signers([rewardsAdmin])
+                .rpc();
+
+        const userBefore = await getAccount(provider.connection, userMintTokenAccount);
+        await airdrop(
+            [
+                { recipient: user.publicKey, amount: new anchor.BN(100) },
+                { recipient: other.publicKey, amount: new anchor.BN(200) },
+            ],
+            [userMintTokenAccount, otherTokenAccount]
+        );
+        const userAfter = await getAccount(provider.connection, userMintTokenAccount);
+        assert.equal((userAfter.amount - userBefore.amount).toString(), "100");
+        assert.equal((await getAccount(provider.connection, otherTokenAccount)).amount.toString(), "200");
+
+        try {
+            await airdrop([{ recipient: other.publicKey, amount: new anchor.BN(0) }], [otherTokenAccount]);
+            assert.fail("Should have failed due to a zero amount");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidAmount");
+        }
+
+        // The token account must belong to the named recipient
+        try {
+            await airdrop([{ recipient: user.publicKey, amount: new anchor.BN(1) }], [otherTokenAccount]);
+            assert.fail("Should have failed due to a token account of another owner");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidTokenOwner");
+        }
+    });
+
+    it("Sweeps unclaimed rewards after the claim deadline", async () => {
+        // Epoch 2 was created with a deadline in the past and never claimed
+        const epochIndex = new anchor.BN(2);
+        const [epochPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+
+        await program.methods
+            .sweepUnclaimed()
+            .accounts({
+                admin: rewardsAdmin.publicKey,
+                epoch: epochPda,
+            })
+            .signers([rewardsAdmin])
+            .rpc();
+
+        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
+        assert.isTrue(epoch.swept);
+
+        try {
+            await program.methods
+                .sweepUnclaimed()
+                .accounts({
+                    admin: rewardsAdmin.publicKey,
+                    epoch: epochPda,
+                })
+                .signers([rewardsAdmin])
+                .rpc();
+
+            assert.fail("Should have failed because the epoch is already swept");
+        } catch (error) {
+            assert.include(error.toString(), "EpochSwept");
+        }
+    });
+
+    it("Hands the economic settings to a config admin in two steps", async () => {
+        const configAdmin = anchor.web3.Keypair.generate();
+
+        const setMintCap = (signer: anchor.web3.Keypair) =>
+            program.methods
+                .setMintCap(new anchor.BN(0))
+                .accounts({
+                    programData: getProgramData(),
+                    signer: signer.publicKey,
+                })
+                .signers([signer])
+                .rpc();
+        const propose = (newAdmin: PublicKey) =>
+            program.methods
+                .proposeConfigAdmin(newAdmin)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+        const accept = (signer: anchor.web3.Keypair) =>
+            program.methods
+                .acceptConfigAdmin()
+                .accounts({
+                    signer: signer.publicKey,
+                })
+                .signers([signer])
+                .rpc();
+
+        await propose(configAdmin.publicKey);
+
+        // Only the proposed key can accept
+        try {
+            await accept(rewardsAdmin);
+            assert.fail("Should have failed because the signer was not proposed");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidPendingConfigAdmin");
+        }
+
+        await accept(configAdmin);
+        let config = await program.account.config.fetch(configPda);
+        assert.equal(config.configAdmin.toBase58(), configAdmin.publicKey.toBase58());
+        assert.equal(config.pendingConfigAdmin.toBase58(), PublicKey.default.toBase58());
+
+        await setMintCap(configAdmin);
+
+        // The upgrade authority no longer holds the setter
+        try {
+            await setMintCap(user.payer);
+            assert.fail("Should have failed because the upgrade authority is not the config admin");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidConfigAdmin");
+        }
+
+        // ...but can still hand the role back to itself
+        await propose(user.publicKey);
+        await accept(user.payer);
+        await setMintCap(user.payer);
+    });
+
+    it("Deposits on a user's behalf through a delegate approval", async () => {
+        const [depositDelegatePda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("deposit_delegate")],
+            program.programId
+        );
+        const amount = new anchor.BN(1000);
+
+        const depositDelegated = () =>
+            program.methods
+                .depositDelegated(amount)
+                .accounts({
+                    vaultTokenAccount: vaultTokenAccount,
+                    mint: mintToken,
+                    user: user.publicKey,
+                    relayer: freezeAdmin.publicKey,
+                    userVaultTokenAccount: userVaultTokenAccount,
+                    feeVaultTokenAccount: vaultTokenAccount,
+                    userMintTokenAccount: userMintTokenAccount,
+                    allowlistEntry: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    vaultTokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([freezeAdmin])
+                .rpc();
+
+        await approve(
+            provider.connection,
+            user.payer,
+            userVaultTokenAccount,
+            depositDelegatePda,
+            user.publicKey,
+            BigInt(amount.toString())
+        );
+
+        const before = await getAccount(provider.connection, userMintTokenAccount);
+        await depositDelegated();
+        const after = await getAccount(provider.connection, userMintTokenAccount);
+        assert.equal((after.amount - before.amount).toString(), amount.toString());
+
+        // The approval was used up
+        const source = await getAccount(provider.connection, userVaultTokenAccount);
+        assert.equal(source.delegatedAmount.toString(), "0");
+        try {
+            await depositDelegated();
+            assert.fail("Should have failed because the approval is spent");
+        } catch (error) {
+            assert.include(error.toString(), "DelegateApprovalInsufficient");
+        }
+    });
+
+    it("Only mints externally through a direct CPI from the allowed program", async () => {
+        const setAllowMintProgramCaller = (caller: PublicKey) =>
+            program.methods
+                .setAllowMintProgramCaller(caller)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        // Naming the allowed program's id as the caller account is not enough: this is a
+        // top-level call, not a CPI from that program
+        const allowedProgram = Keypair.generate().publicKey;
+        await setAllowMintProgramCaller(allowedProgram);
+        await program.methods
+            .setMintCallerQuota(allowedProgram, new anchor.BN(1_000_000), new anchor.BN(3600))
+            .accounts({
+                programData: getProgramData(),
+                signer: user.publicKey,
+            })
+            .rpc();
+        const [quotaPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_caller_quota"), allowedProgram.toBuffer()],
+            program.programId
+        );
+        const quota = await program.account.mintCallerQuota.fetch(quotaPda);
+        assert.equal(quota.maxPerWindow.toString(), "1000000");
+        assert.equal(quota.mintedInWindow.toString(), "0");
+
+        try {
+            await program.methods
+                .externalProgramMint(new anchor.BN(1000))
+                .accounts({
+                    mint: mintToken,
+                    externalMintProgramCaller: user.publicKey,
+                    destination: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            assert.fail("Should have failed because the call is not a CPI from the allowed program");
+        } catch (error) {
+            assert.include(error.toString(), "UnauthorizedMintCaller");
+        }
+
+        // While paused nothing is minted, whoever the caller is
+        const pauseAccounts = { programData: getProgramData(), signer: user.publicKey };
+        await program.methods.pause().accounts(pauseAccounts).rpc();
+        try {
+            await program.methods
+                .externalProgramMint(new anchor.BN(1000))
+                .accounts({
+                    mint: mintToken,
+                    externalMintProgramCaller: user.publicKey,
+                    destination: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            assert.fail("Should have failed because the program is paused");
+        } catch (error) {
+            assert.include(error.toString(), "ProgramPaused");
+        } finally {
+            await program.methods.unpause().accounts(pauseAccounts).rpc();
+        }
+
+        await setAllowMintProgramCaller(PublicKey.default);
+    });
+
+    it("Accepts deposits of an additional vault mint at its own rate", async () => {
+        // A second stablecoin with 8 decimals, minted 1:1 into 6-decimal wYLDS
+        const otherMint = await createMint(provider.connection, user.payer, user.publicKey, null, 8);
+        const otherVaultTokenAccount = await createAccount(
+            provider.connection, user.payer, otherMint, user.publicKey, Keypair.generate()
+        );
+        const userOtherTokenAccount = await createAccount(
+            provider.connection, user.payer, otherMint, user.publicKey
+        );
+        await mintTo(provider.connection, user.payer, otherMint, userOtherTokenAccount, user.publicKey, 100_000_000);
+        const [redeemVaultAuthorityPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("redeem_vault_authority")],
+            program.programId
+        );
+        const otherRedeemVaultTokenAccount = await createAccount(
+            provider.connection, user.payer, otherMint, redeemVaultAuthorityPda, Keypair.generate()
+        );
+        await mintTo(provider.connection, user.payer, otherMint, otherRedeemVaultTokenAccount, user.publicKey, 50_000_000);
+
+        await program.methods
+            .addVaultMint(new anchor.BN(1), new anchor.BN(1))
+            .accounts({
+                vaultMint: otherMint,
+                vaultTokenAccount: otherVaultTokenAccount,
+                redeemVaultTokenAccount: otherRedeemVaultTokenAccount,
+                programData: getProgramData(),
+                signer: user.publicKey,
+            })
+            .rpc();
+        assert.equal((await program.account.config.fetch(configPda)).vaultMintCount, 1);
+
+        const [userPositionPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), user.publicKey.toBuffer()],
+            program.programId
+        );
+        const depositedBefore = (await program.account.userPosition.fetch(userPositionPda)).totalDeposited;
+
+        const before = await getAccount(provider.connection, userMintTokenAccount);
+        await program.methods
+            .depositAsset(new anchor.BN(100_000_000), new Array(32).fill(0))
+            .accounts({
+                vaultMint: otherMint,
+                vaultTokenAccount: otherVaultTokenAccount,
+                mint: mintToken,
+                signer: user.publicKey,
+                userVaultTokenAccount: userOtherTokenAccount,
+                userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                vaultTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+        const after = await getAccount(provider.connection, userMintTokenAccount);
+        assert.equal((after.amount - before.amount).toString(), "1000000");
+        assert.equal((await getAccount(provider.connection, otherVaultTokenAccount)).amount.toString(), "100000000");
+
+        // The deposit counts against the per-user cap at its value in the primary vault token
+        const deposited = async () => (await program.account.userPosition.fetch(userPositionPda)).totalDeposited;
+        assert.equal((await deposited()).sub(depositedBefore).toString(), "1000000");
+
+        // Redeeming half of it back pays out of the asset's own redeem vault
+        const config = await program.account.config.fetch(configPda);
+        const fee = Math.floor((50_000_000 * config.redeemFeeBps) / 10_000);
+        const redeemAsset = (minVaultOut: number) =>
+            program.methods
+                .redeemAsset(new anchor.BN(500_000), new anchor.BN(minVaultOut))
+                .accounts({
+                    vaultMint: otherMint,
+                    redeemVaultTokenAccount: otherRedeemVaultTokenAccount,
+                    mint: mintToken,
+                    signer: user.publicKey,
+                    userVaultTokenAccount: userOtherTokenAccount,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    vaultTokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+        try {
+            await redeemAsset(50_000_000 - fee + 1);
+            assert.fail("Should have failed due to the slippage floor");
+        } catch (error) {
+            assert.include(error.toString(), "SlippageExceeded");
+        }
+        await redeemAsset(50_000_000 - fee);
+        assert.equal(
+            (await getAccount(provider.connection, userOtherTokenAccount)).amount.toString(),
+            (50_000_000 - fee).toString()
+        );
+        assert.equal((await getAccount(provider.connection, otherRedeemVaultTokenAccount)).amount.toString(), fee.toString());
+        assert.equal((await deposited()).sub(depositedBefore).toString(), "500000");
+
+        const [vaultAssetPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_asset"), otherMint.toBuffer()],
+            program.programId
+        );
+        await program.methods
+            .removeVaultMint()
+            .accounts({
+                vaultAsset: vaultAssetPda,
+                programData: getProgramData(),
+                signer: user.publicKey,
+            })
+            .rpc();
+        assert.equal((await program.account.config.fetch(configPda)).vaultMintCount, 0);
+    });
+
+    it("Burns the signer's own wYLDS without redeeming", async () => {
+        const before = await getAccount(provider.connection, userMintTokenAccount);
+        const supplyBefore = (await getMint(provider.connection, mintToken)).supply;
+        const mintedBefore = (await program.account.config.fetch(configPda)).totalMinted;
+
+        await program.methods
+            .burnTokens(new anchor.BN(1000))
+            .accounts({
+                mint: mintToken,
+                userMintTokenAccount: userMintTokenAccount,
+                signer: user.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        const after = await getAccount(provider.connection, userMintTokenAccount);
+        assert.equal((before.amount - after.amount).toString(), "1000");
+        assert.equal((supplyBefore - (await getMint(provider.connection, mintToken)).supply).toString(), "1000");
+        const mintedAfter = (await program.account.config.fetch(configPda)).totalMinted;
+        assert.equal(mintedBefore.sub(mintedAfter).toString(), "1000");
+    });
+
+    it("Sweeps collected fees from the fee authority's vault to a treasury", async () => {
+        const [feeAuthorityPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("fee_authority")],
+            program.programId
+        );
+        const feeVault = await createAccount(
+            provider.connection, user.payer, vaultMint, feeAuthorityPda, Keypair.generate()
+        );
+        const treasury = await createAccount(
+            provider.connection, user.payer, vaultMint, user.publicKey, Keypair.generate()
+        );
+        // Stand-in for collected fees
+        await mintTo(provider.connection, user.payer, vaultMint, feeVault, user.publicKey, 5000);
+
+        const setDepositFee = (feeVaultTokenAccount: PublicKey) =>
+            program.methods
+                .setDepositFee(0)
+                .accounts({
+                    feeVaultTokenAccount,
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+        const previousFeeVault = (await program.account.config.fetch(configPda)).feeVault;
+        await setDepositFee(feeVault);
+
+        await program.methods
+            .sweepFees(new anchor.BN(3000))
+            .accounts({
+                programData: getProgramData(),
+                signer: user.publicKey,
+                feeVaultTokenAccount: feeVault,
+                vaultMint: vaultMint,
+                treasuryTokenAccount: treasury,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        assert.equal((await getAccount(provider.connection, feeVault)).amount.toString(), "2000");
+        assert.equal((await getAccount(provider.connection, treasury)).amount.toString(), "3000");
+
+        await setDepositFee(previousFeeVault);
+    });
+
+    it("Uses slot-based claim deadlines for new epochs when enabled", async () => {
+        const setSlotBased = (enabled: boolean) =>
+            program.methods
+                .setSlotBasedEpochDeadlines(enabled)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        await setSlotBased(true);
+        const epochIndex = new anchor.BN(9);
+        const [epochPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        // Claims stay open for 2 slots after creation
+        await program.methods
+            .createRewardsEpoch(epochIndex, new Array(32).fill(1), new anchor.BN(1000), new anchor.BN(2), { flagged: {} }, 0)
+            .accounts({ admin: rewardsAdmin.publicKey })
+            .signers([rewardsAdmin])
+            .rpc();
+        await setSlotBased(false);
+
+        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
+        assert.isTrue(epoch.createdSlot.toNumber() > 0);
+        assert.equal(epoch.claimDeadlineSlot.toNumber(), epoch.createdSlot.toNumber() + 2);
+        assert.equal(epoch.claimDeadlineTs.toNumber(), 0);
+
+        // Wait for the deadline slot to pass, then the epoch can be swept
+        while ((await provider.connection.getSlot()) <= epoch.claimDeadlineSlot.toNumber()) {
+            await new Promise((resolve) => setTimeout(resolve, 200));
+        }
+        await program.methods
+            .sweepUnclaimed()
+            .accounts({ admin: rewardsAdmin.publicKey, epoch: epochPda })
+            .signers([rewardsAdmin])
+            .rpc();
+        assert.isTrue((await program.account.rewardsEpoch.fetch(epochPda)).swept);
+    });
+
+    it("Only enforces frozen-until-KYC with a default-frozen Token-2022 mint", async () => {
+        // The test mint is a classic SPL Token mint without DefaultAccountState
+        try {
+            await program.methods
+                .setDefaultFrozen(true)
+                .accounts({
+                    mint: mintToken,
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+            assert.fail("Should have failed due to the mint not defaulting to frozen");
+        } catch (error) {
+            assert.include(error.toString(), "MintNotDefaultFrozen");
+        }
 
-    it("Claims rewards with valid merkle proof", async () => {
-        const epochIndex = new anchor.BN(2);
-        const claimAmount = new anchor.BN(5000);
+        // Turning it off is always allowed
+        await program.methods
+            .setDefaultFrozen(false)
+            .accounts({
+                mint: mintToken,
+                programData: getProgramData(),
+                signer: user.publicKey,
+            })
+            .rpc();
+        assert.isFalse((await program.account.config.fetch(configPda)).defaultFrozen);
 
-        // Create epoch first
-        const [epochPda] = PublicKey.findProgramAddressSync(
-            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+        try {
+            await program.methods
+                .thawAfterKyc()
+                .accounts({
+                    tokenAccount: userMintTokenAccount,
+                    mint: mintToken,
+                    signer: freezeAdmin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([freezeAdmin])
+                .rpc();
+            assert.fail("Should have failed due to enforcement being off");
+        } catch (error) {
+            assert.include(error.toString(), "DefaultFrozenDisabled");
+        }
+    });
+
+    it("Funds the redeem vault authority and withdraws only the excess", async () => {
+        const [redeemVaultAuthority] = PublicKey.findProgramAddressSync(
+            [Buffer.from("redeem_vault_authority")],
             program.programId
         );
+        const balance = () => provider.connection.getBalance(redeemVaultAuthority);
+        const withdraw = (lamports: number, signer: Keypair) =>
+            program.methods
+                .withdrawRedeemAuthorityExcess(new anchor.BN(lamports))
+                .accounts({
+                    recipient: signer.publicKey,
+                    programData: getProgramData(),
+                    signer: signer.publicKey,
+                })
+                .signers([signer])
+                .rpc();
 
-        // Mock merkle proof - in real implementation, this would be computed off-chain
-        const mockProof: number[][] = [];
+        const before = await balance();
+        await program.methods
+            .fundRedeemAuthority(new anchor.BN(1_000_000))
+            .accounts({ signer: user.publicKey })
+            .rpc();
+        assert.equal(await balance(), before + 1_000_000);
 
-        // For testing, create a simple merkle root that validates our claim
-        const crypto = require('crypto');
-        const userData = Buffer.concat([
-            user.publicKey.toBuffer(),
-            claimAmount.toArrayLike(Buffer, "le", 8),
-            epochIndex.toArrayLike(Buffer, "le", 8)
-        ]);
-        const leafHash = crypto.createHash('sha256').update(userData).digest();
+        try {
+            await withdraw(1_000_000, rewardsAdmin);
+            assert.fail("Should have failed due to the signer not being the upgrade authority");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidUpgradeAuthority");
+        }
+
+        // Everything above the buffer can go, but not a lamport more
+        const config = await program.account.config.fetch(configPda);
+        const rentExempt = await provider.connection.getMinimumBalanceForRentExemption(0);
+        const floor = Math.max(config.redeemAuthorityMinLamports.toNumber(), rentExempt);
+        const excess = (await balance()) - floor;
+        try {
+            await withdraw(excess + 1, user.payer);
+            assert.fail("Should have failed due to breaching the SOL buffer");
+        } catch (error) {
+            assert.include(error.toString(), "RedeemAuthorityBufferBreached");
+        }
+        await withdraw(excess, user.payer);
+        assert.equal(await balance(), floor);
+
+        // Restore the balance for later redemptions
+        await program.methods
+            .fundRedeemAuthority(new anchor.BN(Math.max(before - floor, 1)))
+            .accounts({ signer: user.publicKey })
+            .rpc();
+    });
 
+    it("Stamps new redemption requests with the current layout version", async () => {
+        const [requestPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("redemption_request"), user.publicKey.toBuffer()],
+            program.programId
+        );
         await program.methods
-            .createRewardsEpoch(epochIndex, Array.from(leafHash), claimAmount)
+            .requestRedeem(new anchor.BN(10000), new anchor.BN(0), null, new Array(32).fill(0))
             .accounts({
-                admin: rewardsAdmin.publicKey,
+                signer: user.publicKey,
+                userMintTokenAccount: userMintTokenAccount,
+                mint: mintToken,
+                vaultMint: vaultMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
             })
-            .signers([rewardsAdmin])
             .rpc();
+        assert.equal((await program.account.redemptionRequest.fetch(requestPda)).version, 1);
 
-        // Claim rewards
-        const [claimRecordPda] = PublicKey.findProgramAddressSync(
-            [Buffer.from("claim"), epochPda.toBuffer(), user.publicKey.toBuffer()],
+        // A request already at the current layout has nothing to migrate
+        try {
+            await program.methods
+                .migrateRedemptionRequest()
+                .accounts({
+                    admin: rewardsAdmin.publicKey,
+                    user: user.publicKey,
+                    vaultMint: vaultMint,
+                })
+                .signers([rewardsAdmin])
+                .rpc();
+            assert.fail("Should have failed due to the request already being migrated");
+        } catch (error) {
+            assert.include(error.toString(), "RedemptionRequestVersionMismatch");
+        }
+
+        await program.methods
+            .cancelRedeem()
+            .accounts({
+                signer: user.publicKey,
+                userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+    });
+
+    it("Accepts a rotated-out rewards administrator only during the grace period", async () => {
+        const accounts = {
+            programData: getProgramData(),
+            signer: user.publicKey,
+        };
+        const setGrace = (seconds: number) =>
+            program.methods.setRewardsAdminGracePeriod(new anchor.BN(seconds)).accounts(accounts).rpc();
+        const createEpoch = (index: number) =>
+            program.methods
+                .createRewardsEpoch(new anchor.BN(index), new Array(32).fill(2), new anchor.BN(1000), new anchor.BN(0), { flagged: {} }, 0)
+                .accounts({ admin: rewardsAdmin.publicKey })
+                .signers([rewardsAdmin])
+                .rpc();
+
+        await setGrace(3600);
+        await program.methods.removeRewardsAdministrator(rewardsAdmin.publicKey).accounts(accounts).rpc();
+        let config = await program.account.config.fetch(configPda);
+        assert.equal(config.rewardsAdministrators.length, 0);
+        assert.equal(config.previousRewardsAdministrators[0].toBase58(), rewardsAdmin.publicKey.toBase58());
+        assert.isTrue(config.graceUntilTs.toNumber() > Math.floor(Date.now() / 1000));
+
+        // Still accepted while the grace period lasts
+        await createEpoch(10);
+
+        // Without a grace period a removal takes effect at once
+        await setGrace(0);
+        await program.methods.addRewardsAdministrator(rewardsAdmin.publicKey).accounts(accounts).rpc();
+        await program.methods.removeRewardsAdministrator(rewardsAdmin.publicKey).accounts(accounts).rpc();
+        config = await program.account.config.fetch(configPda);
+        assert.equal(config.previousRewardsAdministrators.length, 0);
+        try {
+            await createEpoch(11);
+            assert.fail("Should have failed due to the removed rewards administrator");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidRewardsAdministrator");
+        }
+
+        await program.methods.addRewardsAdministrator(rewardsAdmin.publicKey).accounts(accounts).rpc();
+    });
+
+    it("Stamps rewards epochs with the current layout version", async () => {
+        const index = new anchor.BN(0);
+        const [epochPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("epoch"), index.toArrayLike(Buffer, "le", 8)],
             program.programId
         );
+        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
+        assert.equal(epoch.version, 1);
 
-        const tx = await program.methods
-            .claimRewards(claimAmount, mockProof)
+        // An epoch already at the current layout has nothing to migrate
+        try {
+            await program.methods
+                .migrateRewardsEpoch(index, epoch.merkleRoot, epoch.treeDepth, epoch.claimed)
+                .accounts({ admin: rewardsAdmin.publicKey })
+                .signers([rewardsAdmin])
+                .rpc();
+            assert.fail("Should have failed due to the epoch already being migrated");
+        } catch (error) {
+            assert.include(error.toString(), "RewardsEpochVersionMismatch");
+        }
+
+        // Only rewards administrators can migrate epochs
+        const outsider = Keypair.generate();
+        try {
+            await program.methods
+                .migrateRewardsEpoch(index, epoch.merkleRoot, epoch.treeDepth, epoch.claimed)
+                .accounts({ admin: outsider.publicKey })
+                .signers([outsider])
+                .rpc();
+            assert.fail("Should have failed due to not being a rewards administrator");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidRewardsAdministrator");
+        }
+    });
+
+    it("Restakes claimed rewards outside the per-user deposit cap", async () => {
+        const epochIndex = new anchor.BN(11);
+        const claimAmount = new anchor.BN(3000);
+        const [positionPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), user.publicKey.toBuffer()],
+            program.programId
+        );
+        const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
+
+        // A cap the user has already reached would reject any further deposit
+        const before = await program.account.userPosition.fetch(positionPda);
+        const setCap = (cap: anchor.BN) =>
+            program.methods
+                .setPerUserDepositCap(cap)
+                .accounts({ programData: getProgramData(), signer: user.publicKey })
+                .rpc();
+        await withConfigSetting(setCap, before.totalDeposited, new anchor.BN(0), async () => {
+            await program.methods
+                .claimAndRestake(claimAmount, [])
+                .accounts({
+                    claim: {
+                        user: user.publicKey,
+                        epoch: epochPda,
+                        mint: mintToken,
+                        userMintTokenAccount: userMintTokenAccount,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    },
+                })
+                .rpc();
+        });
+
+        // At the 1:1 rate the payout is backed by as many vault tokens, booked apart from deposits
+        const after = await program.account.userPosition.fetch(positionPda);
+        assert.equal(after.totalDeposited.toString(), before.totalDeposited.toString());
+        assert.equal(
+            after.restakedRewards.sub(before.restakedRewards).toString(),
+            claimAmount.toString()
+        );
+    });
+
+    it("Defers part of a split deposit until the vesting cliff", async () => {
+        const [vestingPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vesting"), user.publicKey.toBuffer()],
+            program.programId
+        );
+        const depositWithSplit = (amount: number, immediateBps: number) =>
+            program.methods
+                .depositWithSplit(new anchor.BN(amount), immediateBps)
+                .accounts({
+                    vaultTokenAccount: vaultTokenAccount,
+                    mint: mintToken,
+                    signer: user.publicKey,
+                    userVaultTokenAccount: userVaultTokenAccount,
+                    feeVaultTokenAccount: vaultTokenAccount,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    vaultMint: vaultMint,
+                    vaultTokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+        const setCliff = (seconds: number) =>
+            program.methods
+                .setVestingCliff(new anchor.BN(seconds))
+                .accounts({ programData: getProgramData(), signer: user.publicKey })
+                .rpc();
+        const claimVested = () =>
+            program.methods
+                .claimVested()
+                .accounts({
+                    signer: user.publicKey,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+        // Nothing is deferred, so no vesting record is created
+        const balanceBefore = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+        await depositWithSplit(1000, 10000);
+        assert.isNull(await program.account.vestingRecord.fetchNullable(vestingPda));
+        assert.equal(
+            (await getAccount(provider.connection, userMintTokenAccount)).amount - balanceBefore,
+            BigInt(1000)
+        );
+
+        await setCliff(2);
+        try {
+            await depositWithSplit(1000, 4000);
+            const first = await program.account.vestingRecord.fetch(vestingPda);
+            assert.equal(first.amount.toString(), "600");
+
+            // A second deferral adds to the record without pushing back its unlock time
+            await depositWithSplit(1000, 4000);
+            const second = await program.account.vestingRecord.fetch(vestingPda);
+            assert.equal(second.amount.toString(), "1200");
+            assert.equal(second.unlockTs.toString(), first.unlockTs.toString());
+
+            try {
+                await claimVested();
+                assert.fail("Should have failed due to the vesting cliff");
+            } catch (error) {
+                assert.include(error.toString(), "VestingCliffNotReached");
+            }
+
+            // Past the cliff the whole deferred amount is minted and the record closed
+            await new Promise((resolve) => setTimeout(resolve, 3000));
+            const beforeClaim = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+            await claimVested();
+            assert.equal(
+                (await getAccount(provider.connection, userMintTokenAccount)).amount - beforeClaim,
+                BigInt(1200)
+            );
+            assert.isNull(await program.account.vestingRecord.fetchNullable(vestingPda));
+        } finally {
+            await setCliff(0);
+        }
+    });
+
+    it("Rejects vested claims from a blocked address", async () => {
+        const [blockedPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("blocked"), user.publicKey.toBuffer()],
+            program.programId
+        );
+        await program.methods
+            .depositWithSplit(new anchor.BN(1000), 5000)
             .accounts({
-                user: user.publicKey,
-                epoch: epochPda,
+                vaultTokenAccount: vaultTokenAccount,
                 mint: mintToken,
+                signer: user.publicKey,
+                userVaultTokenAccount: userVaultTokenAccount,
+                feeVaultTokenAccount: vaultTokenAccount,
                 userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                vaultMint: vaultMint,
+                vaultTokenProgram: TOKEN_PROGRAM_ID,
             })
             .rpc();
 
-        // Verify claim record was created
-        const claimRecord = await program.account.claimRecord.fetch(claimRecordPda);
-        assert.isNotNull(claimRecord);
+        await program.methods
+            .blockAddress()
+            .accounts({ admin: freezeAdmin.publicKey, user: user.publicKey })
+            .signers([freezeAdmin])
+            .rpc();
+        try {
+            await program.methods
+                .claimVested()
+                .accounts({
+                    signer: user.publicKey,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            assert.fail("Should have failed due to the blocked address");
+        } catch (error) {
+            assert.include(error.toString(), "AddressBlocked");
+        } finally {
+            await program.methods
+                .unblockAddress()
+                .accounts({ admin: freezeAdmin.publicKey, blockedEntry: blockedPda })
+                .signers([freezeAdmin])
+                .rpc();
+        }
+
+        // Unblocked, the deferred half (the cliff is 0) can be claimed again
+        await program.methods
+            .claimVested()
+            .accounts({
+                signer: user.publicKey,
+                mint: mintToken,
+                userMintTokenAccount: userMintTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
     });
 
-    it("Prevents double claiming", async () => {
-        const epochIndex = new anchor.BN(2);
-        const claimAmount = new anchor.BN(5000);
-        const mockProof: number[][] = [];
+    it("Withholds the claim fee from the payout but counts the full allocation", async () => {
+        const epochIndex = new anchor.BN(12);
+        const claimAmount = new anchor.BN(10000);
+        const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
 
-        const [epochPda] = PublicKey.findProgramAddressSync(
-            [Buffer.from("epoch"), epochIndex.toArrayLike(Buffer, "le", 8)],
+        const setClaimFee = (bps: number) =>
+            program.methods
+                .setClaimFee(bps)
+                .accounts({ programData: getProgramData(), signer: user.publicKey })
+                .rpc();
+        const supplyBefore = (await getMint(provider.connection, mintToken)).supply;
+        const balanceBefore = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+        await withConfigSetting(setClaimFee, 250, 0, async () => {
+            await program.methods
+                .claimRewards(claimAmount, [])
+                .accounts({
+                    user: user.publicKey,
+                    epoch: epochPda,
+                    mint: mintToken,
+                    userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+        });
+
+        // 2.5% of 10_000 is withheld: only the net amount is minted, the fee nowhere
+        const balanceAfter = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+        const supplyAfter = (await getMint(provider.connection, mintToken)).supply;
+        assert.equal((balanceAfter - balanceBefore).toString(), "9750");
+        assert.equal((supplyAfter - supplyBefore).toString(), "9750");
+
+        // The epoch books the whole allocation, so its budget is used up
+        const epoch = await program.account.rewardsEpoch.fetch(epochPda);
+        assert.equal(epoch.claimed.toString(), claimAmount.toString());
+        assert.equal(epoch.claimed.toString(), epoch.total.toString());
+    });
+
+    it("Caps the total wYLDS minted", async () => {
+        const setMintCap = (cap: anchor.BN) =>
+            program.methods
+                .setMintCap(cap)
+                .accounts({
+                    programData: getProgramData(),
+                    signer: user.publicKey,
+                })
+                .rpc();
+
+        const mintedBefore = (await program.account.config.fetch(configPda)).totalMinted;
+        await withConfigSetting(setMintCap, mintedBefore.addn(500), new anchor.BN(0), async () => {
+            try {
+                await deposit(new anchor.BN(501));
+                assert.fail("Should have failed due to the mint cap");
+            } catch (error) {
+                assert.include(error.toString(), "MintCapExceeded");
+            }
+
+            // Minting exactly up to the cap is allowed and counted
+            await deposit(new anchor.BN(500));
+            const config = await program.account.config.fetch(configPda);
+            assert.equal(config.totalMinted.toString(), config.mintCap.toString());
+        });
+    });
+
+    it("Pays rewards out of the rewards vault instead of minting when enabled", async () => {
+        const epochIndex = new anchor.BN(13);
+        const claimAmount = new anchor.BN(10000);
+        const epochPda = await createSingleLeafEpoch(epochIndex, claimAmount);
+
+        // Pre-fund a wYLDS rewards vault owned by the rewards vault authority PDA
+        const [rewardsVaultAuthorityPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("rewards_vault_authority")],
             program.programId
         );
+        const rewardsVault = await createAccount(
+            provider.connection, user.payer, mintToken, rewardsVaultAuthorityPda, Keypair.generate()
+        );
+        await transfer(
+            provider.connection, user.payer, userMintTokenAccount, rewardsVault, user.publicKey, 10000
+        );
 
-        try {
-            await program.methods
-                .claimRewards(claimAmount, mockProof)
+        const setRewardsFromVault = (enabled: boolean) =>
+            program.methods
+                .setRewardsFromVault(enabled)
+                .accounts({ programData: getProgramData(), signer: user.publicKey })
+                .rpc();
+        const claim = (rewardsVaultTokenAccount: PublicKey | null) =>
+            program.methods
+                .claimRewards(claimAmount, [])
                 .accounts({
                     user: user.publicKey,
                     epoch: epochPda,
                     mint: mintToken,
+                    rewardsVaultTokenAccount,
                     userMintTokenAccount: userMintTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
                 })
                 .rpc();
 
-            assert.fail("Should have failed due to double claim attempt");
+        await withConfigSetting(setRewardsFromVault, true, false, async () => {
+            try {
+                await claim(null);
+                assert.fail("Should have failed due to the missing rewards vault");
+            } catch (error) {
+                assert.include(error.toString(), "MissingRewardsVault");
+            }
+
+            const supplyBefore = (await getMint(provider.connection, mintToken)).supply;
+            const balanceBefore = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+            await claim(rewardsVault);
+
+            // The payout moves out of the vault; no new wYLDS is minted
+            const balanceAfter = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+            const supplyAfter = (await getMint(provider.connection, mintToken)).supply;
+            assert.equal((balanceAfter - balanceBefore).toString(), "10000");
+            assert.equal(supplyAfter.toString(), supplyBefore.toString());
+            assert.equal((await getAccount(provider.connection, rewardsVault)).amount.toString(), "0");
+        });
+    });
+
+    it("Only accepts deposits from allowlisted addresses while the allowlist is enabled", async () => {
+        const [allowlistPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("allow"), user.publicKey.toBuffer()],
+            program.programId
+        );
+        const setAllowlistEnabled = (enabled: boolean) =>
+            program.methods
+                .setAllowlistEnabled(enabled)
+                .accounts({ programData: getProgramData(), signer: user.publicKey })
+                .rpc();
+        const depositWith = (allowlistEntry: PublicKey | null) =>
+            program.methods
+                .deposit(new anchor.BN(1000), new Array(32).fill(0))
+                .accounts({
+                    vaultTokenAccount: vaultTokenAccount,
+                    mint: mintToken,
+                    signer: user.publicKey,
+                    userVaultTokenAccount: userVaultTokenAccount,
+                    feeVaultTokenAccount: vaultTokenAccount,
+                    userMintTokenAccount: userMintTokenAccount,
+                    allowlistEntry,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    vaultMint: vaultMint,
+                    vaultTokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+        await setAllowlistEnabled(true);
+        try {
+            try {
+                await depositWith(null);
+                assert.fail("Should have failed due to the address not being allowlisted");
+            } catch (error) {
+                assert.include(error.toString(), "AddressNotAllowlisted");
+            }
+
+            // Only rewards administrators manage the allowlist
+            try {
+                await program.methods
+                    .addToAllowlist()
+                    .accounts({ admin: freezeAdmin.publicKey, user: user.publicKey })
+                    .signers([freezeAdmin])
+                    .rpc();
+                assert.fail("Should have failed due to the signer not being a rewards administrator");
+            } catch (error) {
+                assert.include(error.toString(), "InvalidRewardsAdministrator");
+            }
+
+            await program.methods
+                .addToAllowlist()
+                .accounts({ admin: rewardsAdmin.publicKey, user: user.publicKey })
+                .signers([rewardsAdmin])
+                .rpc();
+            const entry = await program.account.allowlistEntry.fetch(allowlistPda);
+            assert.equal(entry.user.toBase58(), user.publicKey.toBase58());
+
+            const before = await getAccount(provider.connection, userMintTokenAccount);
+            await depositWith(allowlistPda);
+            const after = await getAccount(provider.connection, userMintTokenAccount);
+            assert.isTrue(after.amount > before.amount);
+
+            // Removing the entry closes it and locks the address out again
+            await program.methods
+                .removeFromAllowlist()
+                .accounts({ admin: rewardsAdmin.publicKey, allowlistEntry: allowlistPda })
+                .signers([rewardsAdmin])
+                .rpc();
+            assert.isNull(await program.account.allowlistEntry.fetchNullable(allowlistPda));
+            try {
+                await depositWith(null);
+                assert.fail("Should have failed due to the address no longer being allowlisted");
+            } catch (error) {
+                assert.include(error.toString(), "AddressNotAllowlisted");
+            }
+        } finally {
+            await setAllowlistEnabled(false);
+        }
+    });
+
+    it("Lets only the upgrade authority hand the mint authority to another address", async () => {
+        const [mintAuthorityPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_authority")],
+            program.programId
+        );
+        const transferMintAuthority = (signer: Keypair, newAuthority: PublicKey) =>
+            program.methods
+                .transferMintAuthority(newAuthority)
+                .accounts({
+                    mint: mintToken,
+                    programData: getProgramData(),
+                    signer: signer.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([signer])
+                .rpc();
+
+        try {
+            await transferMintAuthority(rewardsAdmin, rewardsAdmin.publicKey);
+            assert.fail("Should have failed due to the signer not being the upgrade authority");
         } catch (error) {
-            // Account already exists error indicates claim record prevents double claiming
-            assert.isTrue(error.toString().includes("already in use") ||
-                error.toString().includes("RewardsAlreadyClaimed"));
+            assert.include(error.toString(), "InvalidUpgradeAuthority");
         }
+        try {
+            await transferMintAuthority(user.payer, PublicKey.default);
+            assert.fail("Should have failed due to the default new authority");
+        } catch (error) {
+            assert.include(error.toString(), "InvalidNewAuthority");
+        }
+
+        await transferMintAuthority(user.payer, user.publicKey);
+        assert.equal(
+            (await getMint(provider.connection, mintToken)).mintAuthority.toBase58(),
+            user.publicKey.toBase58()
+        );
+
+        // Hand it straight back so the program can keep minting
+        await setAuthority(
+            provider.connection,
+            user.payer,
+            mintToken,
+            user.payer,
+            AuthorityType.MintTokens,
+            mintAuthorityPda
+        );
+        assert.equal(
+            (await getMint(provider.connection, mintToken)).mintAuthority.toBase58(),
+            mintAuthorityPda.toBase58()
+        );
     });
 });